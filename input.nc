@@ -1,8 +1,9 @@
 fn print(a: usize, b: usize) void -> {}
 
-fn main(in: usize) usize -> {
+fn main() i32 -> {
+    let in: usize = 1;
     let a: usize = in;
     a += a;
     if( a == a) print(a, a);
-    return a;
+    return 0;
 }
@@ -0,0 +1,954 @@
+use std::collections::{HashMap, HashSet};
+
+use super::lexer::{CToken, tokenize};
+
+// What a header turned into: notc source declaring its items, and what had to be left out
+#[derive(Debug, Default)]
+pub struct HeaderImport {
+    pub source: String,
+    pub warnings: Vec<String>,
+}
+
+// C types, as far as notc can follow them
+#[derive(Debug, Clone)]
+pub(super) enum CType {
+    // A type notc has a name for, eg i32 or an imported struct
+    Named(String),
+    // Known to C but not to notc, so only usable behind a pointer
+    Opaque(String),
+    Pointer(Box<CType>),
+    Array(Box<CType>),
+    Function {
+        params: Vec<(Option<String>, CType)>,
+        ret: Box<CType>,
+        variadic: bool,
+    },
+}
+
+impl CType {
+    // How the type is written in notc. Pointers to anything notc can't name become *void
+    pub(super) fn spell(&self) -> Option<String> {
+        match self {
+            CType::Named(name) => return Some(name.clone()),
+            CType::Opaque(_) | CType::Array(_) => return None,
+            // notc function types are already pointers
+            CType::Pointer(inner) if matches!(**inner, CType::Function { .. }) => {
+                return inner.spell();
+            }
+            CType::Pointer(inner) => {
+                return Some(format!("*{}", inner.spell().unwrap_or("void".to_string())));
+            }
+            CType::Function {
+                params,
+                ret,
+                variadic: false,
+            } => {
+                let mut spelled = Vec::new();
+                for (_, param) in params {
+                    spelled.push(param.spell()?);
+                }
+                return Some(format!("fn({}) {}", spelled.join(", "), ret.spell()?));
+            }
+            CType::Function { .. } => return None,
+        }
+    }
+
+    pub(super) fn describe(&self) -> String {
+        match self {
+            CType::Named(name) | CType::Opaque(name) => return name.clone(),
+            CType::Pointer(inner) => return format!("{}*", inner.describe()),
+            CType::Array(inner) => return format!("{}[]", inner.describe()),
+            CType::Function { .. } => return "function".to_string(),
+        }
+    }
+}
+
+// The notc type for a list of C type keywords, eg unsigned int is usize. notc integers are 32
+// bits, so longs have no notc type rather than being cut down to one
+pub(super) fn primitive(words: &[&str]) -> Option<&'static str> {
+    let has = |w: &str| words.contains(&w);
+    let only = |allowed: &[&str]| words.iter().all(|w| allowed.contains(w));
+
+    if has("long") {
+        return None;
+    }
+
+    if words == ["void"] {
+        return Some("void");
+    }
+    if words == ["_Bool"] || words == ["bool"] {
+        return Some("bool");
+    }
+    if has("char") && only(&["char", "signed", "unsigned"]) {
+        return Some("u8");
+    }
+    if has("unsigned") && only(&["unsigned", "int", "short"]) {
+        return Some("usize");
+    }
+    if only(&["signed", "int", "short"]) && !words.is_empty() {
+        return Some("i32");
+    }
+    return None;
+}
+
+// Typedefs from the standard headers, which other headers use from files notc doesn't read, eg
+// uint8_t from stdint.h
+const STANDARD_TYPEDEFS: &[(&str, &str)] =
+    &[("uint8_t", "u8"), ("int32_t", "i32"), ("uint32_t", "usize")];
+// Standard typedefs wider than any notc integer, which are left out like longs
+const WIDE_TYPEDEFS: &[&str] = &[
+    "size_t",
+    "ssize_t",
+    "ptrdiff_t",
+    "intptr_t",
+    "uintptr_t",
+    "int64_t",
+    "uint64_t",
+    "off_t",
+];
+
+pub(super) const TYPE_WORDS: &[&str] = &[
+    "void", "_Bool", "bool", "char", "short", "int", "long", "float", "double", "signed",
+    "unsigned", "__int128", "_Complex",
+];
+pub(super) const QUALIFIERS: &[&str] = &[
+    "const",
+    "volatile",
+    "restrict",
+    "__restrict",
+    "__restrict__",
+    "__const",
+    "extern",
+    "static",
+    "inline",
+    "__inline",
+    "__inline__",
+    "register",
+    "auto",
+    "__extension__",
+    "_Noreturn",
+    "_Thread_local",
+];
+// Compiler extensions that take arguments, eg __attribute__((nonnull))
+const EXTENSIONS: &[&str] = &[
+    "__attribute__",
+    "__attribute",
+    "__asm__",
+    "__asm",
+    "asm",
+    "__declspec",
+    "_Alignas",
+];
+
+// Whether a name can be used in notc as is
+pub(super) fn usable_name(name: &str) -> bool {
+    return !crate::TEXT_MATCHES.iter().any(|(k, _)| *k == name)
+        && !crate::PRIMATIVE_TYPES.contains(&name);
+}
+
+// Names starting with __, or _ and a capital, belong to the C implementation
+fn reserved(name: &str) -> bool {
+    return name.starts_with("__")
+        || name.starts_with('_') && name[1..].starts_with(|c: char| c.is_ascii_uppercase());
+}
+
+// Called on struct, union and enum bodies with the kind, tag, and whether it is in a typedef
+pub(super) type Define<'a> =
+    dyn FnMut(&mut DeclParser, &str, Option<String>, bool) -> Result<CType, String> + 'a;
+
+#[derive(Debug)]
+pub(super) struct Specifiers {
+    pub typedef: bool,
+    pub base: CType,
+}
+
+pub(super) struct DeclParser {
+    pub tokens: Vec<CToken>,
+    pub pos: usize,
+    pub typedefs: HashMap<String, CType>,
+    // Struct and enum tags, eg "struct tm", and the notc name they were imported as
+    pub tags: HashMap<String, String>,
+}
+
+impl DeclParser {
+    pub fn new(tokens: Vec<CToken>) -> Self {
+        return DeclParser {
+            tokens,
+            pos: 0,
+            typedefs: STANDARD_TYPEDEFS
+                .iter()
+                .map(|(c, notc)| (c.to_string(), CType::Named(notc.to_string())))
+                .chain(
+                    WIDE_TYPEDEFS
+                        .iter()
+                        .map(|c| (c.to_string(), CType::Opaque(c.to_string()))),
+                )
+                .collect(),
+            tags: HashMap::new(),
+        };
+    }
+
+    pub fn peek(&self) -> Option<&CToken> {
+        return self.tokens.get(self.pos);
+    }
+    pub fn peek_at(&self, offset: usize) -> Option<&CToken> {
+        return self.tokens.get(self.pos + offset);
+    }
+    pub fn is_punct(&self, p: &str) -> bool {
+        return matches!(self.peek(), Some(CToken::Punct(q)) if *q == p);
+    }
+    pub fn eat_punct(&mut self, p: &str) -> bool {
+        if self.is_punct(p) {
+            self.pos += 1;
+            return true;
+        }
+        return false;
+    }
+    pub fn expect_punct(&mut self, p: &str) -> Result<(), String> {
+        if self.eat_punct(p) {
+            return Ok(());
+        }
+        return Err(format!("expected '{}'", p));
+    }
+    pub fn peek_identifier(&self) -> Option<&str> {
+        match self.peek() {
+            Some(CToken::Identifier(i)) => return Some(i),
+            _ => return None,
+        }
+    }
+
+    // Index of the bracket closing the one at `open`
+    pub fn matching(&self, open: usize) -> Option<usize> {
+        let mut depth = 0;
+        for i in open..self.tokens.len() {
+            match &self.tokens[i] {
+                CToken::Punct("(" | "[" | "{") => depth += 1,
+                CToken::Punct(")" | "]" | "}") => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Some(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        return None;
+    }
+    pub fn skip_group(&mut self) {
+        match self.matching(self.pos) {
+            Some(close) => self.pos = close + 1,
+            None => self.pos = self.tokens.len(),
+        }
+    }
+
+    // Steps over qualifiers and compiler extensions, which notc has no use for
+    pub fn skip_noise(&mut self) {
+        loop {
+            match self.peek_identifier() {
+                Some(i) if QUALIFIERS.contains(&i) => self.pos += 1,
+                Some(i) if EXTENSIONS.contains(&i) => {
+                    self.pos += 1;
+                    if self.is_punct("(") {
+                        self.skip_group();
+                    }
+                }
+                _ => return,
+            }
+        }
+    }
+
+    pub fn starts_declarator(&self, offset: usize) -> bool {
+        match self.peek_at(offset) {
+            Some(CToken::Punct("*" | "(")) => return true,
+            Some(CToken::Identifier(i)) => {
+                return !TYPE_WORDS.contains(&i.as_str())
+                    && !QUALIFIERS.contains(&i.as_str())
+                    && !matches!(i.as_str(), "typedef" | "struct" | "union" | "enum");
+            }
+            _ => return false,
+        }
+    }
+
+    // The type part of a declaration, eg `static const unsigned int`. Struct and enum bodies
+    // are handed to `define`, which returns the name to use for them
+    pub fn specifiers(&mut self, define: &mut Define) -> Result<Specifiers, String> {
+        let mut typedef = false;
+        let mut words: Vec<String> = Vec::new();
+        let mut base = None;
+
+        loop {
+            self.skip_noise();
+            let word;
+            match self.peek_identifier() {
+                Some(i) => word = i.to_string(),
+                None => break,
+            }
+
+            if word == "typedef" {
+                typedef = true;
+                self.pos += 1;
+            } else if TYPE_WORDS.contains(&word.as_str()) {
+                words.push(word);
+                self.pos += 1;
+            } else if matches!(word.as_str(), "struct" | "union" | "enum") && base.is_none() {
+                self.pos += 1;
+                self.skip_noise();
+                let tag = self.peek_identifier().map(|t| t.to_string());
+                if tag.is_some() {
+                    self.pos += 1;
+                }
+                let key = tag.as_ref().map(|t| format!("{} {}", word, t));
+
+                if self.is_punct("{") {
+                    base = Some(define(self, &word, tag, typedef)?);
+                } else {
+                    base = Some(match key.as_ref().and_then(|k| self.tags.get(k)) {
+                        Some(name) => CType::Named(name.clone()),
+                        None => CType::Opaque(key.unwrap_or(word)),
+                    });
+                }
+            } else if base.is_none() && words.is_empty() {
+                match self.typedefs.get(&word) {
+                    Some(t) => {
+                        base = Some(t.clone());
+                        self.pos += 1;
+                    }
+                    // A type from somewhere notc can't see, eg va_list from stdarg.h
+                    None if self.starts_declarator(1) => {
+                        base = Some(CType::Opaque(word));
+                        self.pos += 1;
+                    }
+                    // Macros standing in for nothing, eg __BEGIN_DECLS
+                    None if reserved(&word) => self.pos += 1,
+                    None => break,
+                }
+            } else {
+                break;
+            }
+        }
+
+        let base = match base {
+            Some(base) => base,
+            None if words.is_empty() => return Err("expected a type".to_string()),
+            None => {
+                let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+                match primitive(&words) {
+                    Some(p) => CType::Named(p.to_string()),
+                    None => CType::Opaque(words.join(" ")),
+                }
+            }
+        };
+        return Ok(Specifiers { typedef, base });
+    }
+
+    // A declarator, eg `*name`, `name[4]` or `(*name)(int)`, built around the base type. C
+    // reads these inside out, so nested declarators get the type made by what follows them
+    pub fn declarator(
+        &mut self,
+        base: CType,
+        define: &mut Define,
+    ) -> Result<(Option<String>, CType), String> {
+        let mut t = base;
+        loop {
+            self.skip_noise();
+            if !self.eat_punct("*") {
+                break;
+            }
+            t = CType::Pointer(Box::new(t));
+        }
+
+        if self.is_punct("(") && matches!(self.peek_at(1), Some(CToken::Punct("*" | "(" | "^"))) {
+            let open = self.pos;
+            let close = self.matching(open).ok_or("unclosed parenthesis")?;
+            self.pos = close + 1;
+            t = self.suffixes(t, define)?;
+            let after = self.pos;
+
+            self.pos = open + 1;
+            let (name, t) = self.declarator(t, define)?;
+            self.skip_noise();
+            if self.pos != close {
+                return Err("couldn't read the declarator".to_string());
+            }
+            self.pos = after;
+            return Ok((name, t));
+        }
+
+        let mut name = None;
+        if let Some(i) = self.peek_identifier() {
+            name = Some(i.to_string());
+            self.pos += 1;
+        }
+        let t = self.suffixes(t, define)?;
+        return Ok((name, t));
+    }
+
+    fn suffixes(&mut self, base: CType, define: &mut Define) -> Result<CType, String> {
+        enum Suffix {
+            Params(Vec<(Option<String>, CType)>, bool),
+            Array,
+        }
+
+        let mut suffixes = Vec::new();
+        loop {
+            if self.is_punct("[") {
+                self.skip_group();
+                suffixes.push(Suffix::Array);
+            } else if self.eat_punct("(") {
+                let mut params = Vec::new();
+                let mut variadic = false;
+                let empty = self.is_punct(")")
+                    || (self.peek_identifier() == Some("void")
+                        && matches!(self.peek_at(1), Some(CToken::Punct(")"))));
+                if empty {
+                    while !self.eat_punct(")") {
+                        self.pos += 1;
+                    }
+                } else {
+                    loop {
+                        if self.eat_punct("...") {
+                            variadic = true;
+                        } else {
+                            let specifiers = self.specifiers(define)?;
+                            let (name, ptype) = self.declarator(specifiers.base, define)?;
+                            // Arrays and functions are passed as pointers
+                            let ptype = match ptype {
+                                CType::Array(inner) => CType::Pointer(inner),
+                                f @ CType::Function { .. } => CType::Pointer(Box::new(f)),
+                                t => t,
+                            };
+                            params.push((name, ptype));
+                        }
+                        self.skip_noise();
+                        if self.eat_punct(")") {
+                            break;
+                        }
+                        self.expect_punct(",")?;
+                    }
+                }
+                suffixes.push(Suffix::Params(params, variadic));
+            } else {
+                break;
+            }
+        }
+
+        let mut t = base;
+        for suffix in suffixes.into_iter().rev() {
+            t = match suffix {
+                Suffix::Array => CType::Array(Box::new(t)),
+                Suffix::Params(params, variadic) => CType::Function {
+                    params,
+                    ret: Box::new(t),
+                    variadic,
+                },
+            };
+        }
+        return Ok(t);
+    }
+
+    // Skips the rest of a declaration that couldn't be read, including any function body
+    pub fn skip_declaration(&mut self) {
+        let mut depth = 0;
+        while let Some(token) = self.peek() {
+            match token {
+                CToken::Punct("(" | "[") => depth += 1,
+                CToken::Punct(")" | "]") => depth -= 1,
+                CToken::Punct("{") if depth == 0 => {
+                    let is_body = self.pos > 0 && self.tokens[self.pos - 1] == CToken::Punct(")");
+                    self.skip_group();
+                    if is_body {
+                        return;
+                    }
+                    continue;
+                }
+                CToken::Punct(";") if depth <= 0 => {
+                    self.pos += 1;
+                    return;
+                }
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+// Turns a C header into extern declarations, each pointing back at the header with #[include]
+pub fn import_header(text: &str, header: &str) -> HeaderImport {
+    let tokens: Vec<CToken> = tokenize(text)
+        .into_iter()
+        .filter(|t| !matches!(t, CToken::Comment(_)))
+        .collect();
+    let mut importer = HeaderImporter {
+        header: header.to_string(),
+        declared: HashSet::new(),
+        import: HeaderImport::default(),
+        warnings: Vec::new(),
+    };
+    let mut parser = DeclParser::new(tokens);
+
+    while let Some(token) = parser.peek().cloned() {
+        match token {
+            CToken::Directive(directive) => {
+                parser.pos += 1;
+                importer.directive(&directive);
+            }
+            // extern "C" { ... } blocks around the declarations, only their contents matter
+            CToken::Identifier(i)
+                if i == "extern" && matches!(parser.peek_at(1), Some(CToken::Str(_))) =>
+            {
+                parser.pos += 2
+            }
+            CToken::Punct(";" | "{" | "}") => parser.pos += 1,
+            // Macros standing in for nothing, eg __BEGIN_DECLS
+            CToken::Identifier(i) if reserved(&i) && !parser.starts_declarator(1) => {
+                parser.pos += 1
+            }
+            _ => {
+                let start = parser.pos;
+                if let Err(e) = importer.declaration(&mut parser) {
+                    parser.pos = start;
+                    parser.skip_declaration();
+                    let name = declared_name(&parser, &parser.tokens[start..parser.pos]);
+                    let warning = match &name {
+                        Some(name) => format!("skipped {}: {}", name, e),
+                        None => format!("skipped a declaration: {}", e),
+                    };
+                    importer.warn(name, warning);
+                }
+            }
+        }
+    }
+
+    // Headers often declare things more than once under different #ifs, so only warn about
+    // names that didn't make it in some other way, and only once each
+    let mut import = importer.import;
+    let mut warned = HashSet::new();
+    for (name, warning) in importer.warnings {
+        match name {
+            Some(name) if importer.declared.contains(&name) => {}
+            // One reason is enough
+            Some(name) if !warned.insert(name.clone()) => {}
+            _ => import.warnings.push(warning),
+        }
+    }
+    return import;
+}
+
+// Guesses the name a declaration was for, eg fopen in
+// extern FILE *__REDIRECT (fopen, (const char *__filename), fopen64);
+fn declared_name(parser: &DeclParser, tokens: &[CToken]) -> Option<String> {
+    for i in 0..tokens.len().saturating_sub(1) {
+        if let CToken::Identifier(name) = &tokens[i]
+            && !reserved(name)
+            && !TYPE_WORDS.contains(&name.as_str())
+            && !QUALIFIERS.contains(&name.as_str())
+            && !parser.typedefs.contains_key(name)
+            && matches!(
+                tokens[i + 1],
+                CToken::Punct("(" | "," | ";" | "[" | ")" | "=")
+            )
+        {
+            return Some(name.clone());
+        }
+    }
+    return None;
+}
+
+struct HeaderImporter {
+    header: String,
+    declared: HashSet<String>,
+    import: HeaderImport,
+    // Warnings, with the name they are about if it could still be imported later
+    warnings: Vec<(Option<String>, String)>,
+}
+
+impl HeaderImporter {
+    fn warn(&mut self, name: Option<String>, warning: String) {
+        self.warnings.push((name, warning));
+    }
+
+    // Claims a name for an item, false if it can't or shouldn't be imported
+    fn claim(&mut self, name: &str) -> bool {
+        if reserved(name) || self.declared.contains(name) {
+            return false;
+        }
+        if !usable_name(name) {
+            self.warn(
+                None,
+                format!("skipped {}, the name is a notc keyword", name),
+            );
+            return false;
+        }
+        self.declared.insert(name.to_string());
+        return true;
+    }
+
+    // Simple constants, eg #define BUFSIZ 8192, become extern statics read through the macro
+    fn directive(&mut self, directive: &str) {
+        let rest;
+        match directive.strip_prefix("define") {
+            Some(r) if r.starts_with(char::is_whitespace) => rest = r.trim_start(),
+            _ => return,
+        }
+        let name_end = rest
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(rest.len());
+        let (name, value) = rest.split_at(name_end);
+        if name.is_empty() || reserved(name) || name.starts_with('_') {
+            return;
+        }
+        if value.starts_with('(') {
+            self.warn(
+                Some(name.to_string()),
+                format!("skipped {}, function-like macros aren't supported", name),
+            );
+            return;
+        }
+
+        let mut tokens = tokenize(value);
+        // Flags with no value, eg #define HAVE_THING
+        if tokens.is_empty() {
+            return;
+        }
+        // Renames, eg #define stdin stdin, leave the real declaration to be imported
+        if let [CToken::Identifier(_)] = tokens.as_slice() {
+            return;
+        }
+        if tokens.first() == Some(&CToken::Punct("(")) && tokens.last() == Some(&CToken::Punct(")"))
+        {
+            tokens = tokens[1..tokens.len() - 1].to_vec();
+        }
+        if tokens.first() == Some(&CToken::Punct("-")) {
+            tokens.remove(0);
+        }
+
+        let vtype = match tokens.as_slice() {
+            [CToken::Str(_)] => "*u8",
+            [CToken::Char(_)] => "u8",
+            [CToken::Number(n)] if is_integer(n) => {
+                if n.to_ascii_lowercase().contains('u') {
+                    "usize"
+                } else {
+                    "i32"
+                }
+            }
+            _ => {
+                self.warn(
+                    Some(name.to_string()),
+                    format!("skipped {}, only literal constants are imported", name),
+                );
+                return;
+            }
+        };
+        if self.claim(name) {
+            self.emit_static(name, vtype);
+        }
+    }
+
+    fn declaration(&mut self, parser: &mut DeclParser) -> Result<(), String> {
+        let specifiers =
+            parser.specifiers(&mut |p, kind, tag, typedef| self.define(p, kind, tag, typedef))?;
+
+        loop {
+            let (name, ctype) = parser
+                .declarator(specifiers.base.clone(), &mut |p, kind, tag, typedef| {
+                    self.define(p, kind, tag, typedef)
+                })?;
+            parser.skip_noise();
+            // Trailing macros, eg __THROW or __nonnull ((1))
+            while let Some(CToken::Identifier(i)) = parser.peek() {
+                if !reserved(i) {
+                    break;
+                }
+                parser.pos += 1;
+                if parser.is_punct("(") {
+                    parser.skip_group();
+                }
+                parser.skip_noise();
+            }
+
+            if let Some(name) = name {
+                if specifiers.typedef {
+                    // Typedefs of the struct just imported, eg typedef struct { .. } Name;, map
+                    // the name to itself
+                    parser.typedefs.entry(name).or_insert(ctype);
+                } else {
+                    self.item(&name, &ctype);
+                }
+            }
+
+            // Initialisers and bodies are C's business
+            if parser.eat_punct("=") {
+                while !parser.is_punct(",") && !parser.is_punct(";") && parser.peek().is_some() {
+                    if parser.is_punct("(") || parser.is_punct("{") {
+                        parser.skip_group();
+                    } else {
+                        parser.pos += 1;
+                    }
+                }
+            }
+            if parser.is_punct("{") {
+                parser.skip_group();
+                return Ok(());
+            }
+            if parser.eat_punct(",") {
+                continue;
+            }
+            return parser.expect_punct(";");
+        }
+    }
+
+    fn item(&mut self, name: &str, ctype: &CType) {
+        match ctype {
+            CType::Function {
+                params,
+                ret,
+                variadic,
+            } => {
+                let mut spelled = Vec::new();
+                for (i, (pname, ptype)) in params.iter().enumerate() {
+                    match ptype.spell() {
+                        Some(t) => spelled.push(match pname {
+                            Some(p) if usable_name(p) && !spelled_has(&spelled, p) => {
+                                format!("{}: {}", p, t)
+                            }
+                            _ => format!("p{}: {}", i, t),
+                        }),
+                        None => {
+                            if !reserved(name) {
+                                self.warn(
+                                    Some(name.to_string()),
+                                    format!(
+                                        "skipped {}, its parameter type {} isn't supported",
+                                        name,
+                                        ptype.describe()
+                                    ),
+                                );
+                            }
+                            return;
+                        }
+                    }
+                }
+                if *variadic {
+                    spelled.push("...".to_string());
+                }
+                let rtype;
+                match ret.spell() {
+                    Some(r) => rtype = r,
+                    None => {
+                        if !reserved(name) {
+                            self.warn(
+                                Some(name.to_string()),
+                                format!(
+                                    "skipped {}, its return type {} isn't supported",
+                                    name,
+                                    ret.describe()
+                                ),
+                            );
+                        }
+                        return;
+                    }
+                }
+
+                if self.claim(name) {
+                    self.import.source += &format!(
+                        "#[include(\"{}\")]\nextern fn {}({}) {};\n",
+                        self.header,
+                        name,
+                        spelled.join(", "),
+                        rtype
+                    );
+                }
+            }
+            _ => match ctype.spell() {
+                Some(t) => {
+                    if self.claim(name) {
+                        self.emit_static(name, &t);
+                    }
+                }
+                None => {
+                    if !reserved(name) {
+                        self.warn(
+                            Some(name.to_string()),
+                            format!(
+                                "skipped {}, its type {} isn't supported",
+                                name,
+                                ctype.describe()
+                            ),
+                        );
+                    }
+                }
+            },
+        }
+    }
+
+    fn emit_static(&mut self, name: &str, vtype: &str) {
+        self.import.source += &format!(
+            "#[include(\"{}\")]\nextern static {}: {};\n",
+            self.header, name, vtype
+        );
+    }
+
+    // Imports a struct or enum body. Typedefed ones take the typedef's name, others keep their
+    // tag and are spelt as such in C, eg struct tm
+    fn define(
+        &mut self,
+        parser: &mut DeclParser,
+        kind: &str,
+        tag: Option<String>,
+        typedef: bool,
+    ) -> Result<CType, String> {
+        let close = parser.matching(parser.pos).ok_or("unclosed brace")?;
+        let typedef_name = match (parser.tokens.get(close + 1), parser.tokens.get(close + 2)) {
+            (Some(CToken::Identifier(n)), Some(CToken::Punct(";" | ","))) if typedef => {
+                Some(n.clone())
+            }
+            _ => None,
+        };
+        let key = tag.as_ref().map(|t| format!("{} {}", kind, t));
+        let name = typedef_name.clone().or(tag.clone());
+
+        if kind == "union" {
+            parser.pos = close + 1;
+            return Ok(CType::Opaque(key.unwrap_or("union".to_string())));
+        }
+
+        // Registered up front, so structs can point to themselves
+        let claimed = match &name {
+            Some(name) => self.claim(name),
+            None => false,
+        };
+        if let (Some(key), Some(name), true) = (&key, &name, claimed) {
+            parser.tags.insert(key.clone(), name.clone());
+        }
+
+        parser.pos += 1;
+        let mut body = String::new();
+        if kind == "enum" {
+            let mut variants = Vec::new();
+            while let Some(CToken::Identifier(variant)) = parser.peek().cloned() {
+                parser.pos += 1;
+                if parser.eat_punct("=") {
+                    while !parser.is_punct(",") && !parser.is_punct("}") && parser.peek().is_some()
+                    {
+                        parser.pos += 1;
+                    }
+                }
+                variants.push(variant);
+                if !parser.eat_punct(",") {
+                    break;
+                }
+            }
+            parser.expect_punct("}")?;
+
+            if name.is_none() {
+                // Anonymous enums just name some constants
+                for variant in variants {
+                    if self.claim(&variant) {
+                        self.emit_static(&variant, "i32");
+                    }
+                }
+                return Ok(CType::Named("i32".to_string()));
+            }
+            for variant in variants {
+                if !usable_name(&variant) || self.declared.contains(&variant) {
+                    self.warn(None, format!("left out enum constant {}", variant));
+                    continue;
+                }
+                self.declared.insert(variant.clone());
+                body += &format!("    {},\n", variant);
+            }
+        } else {
+            while !parser.is_punct("}") && parser.peek().is_some() {
+                let start = parser.pos;
+                let field = self.field(parser);
+                match field {
+                    Ok(fields) => body += &fields,
+                    Err(e) => {
+                        parser.pos = start;
+                        parser.skip_declaration();
+                        self.warn(
+                            None,
+                            format!(
+                                "left out a field of {}: {}",
+                                name.clone().unwrap_or(kind.to_string()),
+                                e
+                            ),
+                        );
+                    }
+                }
+            }
+            parser.expect_punct("}")?;
+        }
+
+        match name {
+            Some(name) if claimed => {
+                let mut source = format!("#[include(\"{}\")]\n", self.header);
+                if typedef_name.is_none() {
+                    source += &format!("#[export_name(\"{}\")]\n", key.clone().unwrap());
+                }
+                source += &format!("extern {} {} {{\n{}}}\n", kind, name, body);
+                self.import.source += &source;
+                return Ok(CType::Named(name));
+            }
+            Some(name) => {
+                // Already imported, or can't be, so use whatever the first import was
+                return Ok(match key.and_then(|k| parser.tags.get(&k).cloned()) {
+                    Some(n) => CType::Named(n),
+                    None => CType::Opaque(name),
+                });
+            }
+            None => {
+                self.warn(None, format!("skipped an anonymous {}", kind));
+                return Ok(CType::Opaque(kind.to_string()));
+            }
+        }
+    }
+
+    // One field declaration in a struct body, which can declare several fields
+    fn field(&mut self, parser: &mut DeclParser) -> Result<String, String> {
+        let specifiers =
+            parser.specifiers(&mut |p, kind, tag, typedef| self.define(p, kind, tag, typedef))?;
+        let mut fields = String::new();
+        loop {
+            let (name, ftype) = parser
+                .declarator(specifiers.base.clone(), &mut |p, kind, tag, typedef| {
+                    self.define(p, kind, tag, typedef)
+                })?;
+            // Bit fields
+            if parser.eat_punct(":") {
+                return Err("bit fields aren't supported".to_string());
+            }
+            let name = name.ok_or("unnamed fields aren't supported")?;
+            if !usable_name(&name) {
+                return Err(format!("{} is a notc keyword", name));
+            }
+            let ftype = ftype.spell().ok_or(format!(
+                "{} has the unsupported type {}",
+                name,
+                ftype.describe()
+            ))?;
+            fields += &format!("    {}: {},\n", name, ftype);
+
+            parser.skip_noise();
+            if parser.eat_punct(",") {
+                continue;
+            }
+            parser.expect_punct(";")?;
+            return Ok(fields);
+        }
+    }
+}
+
+fn spelled_has(params: &[String], name: &str) -> bool {
+    return params.iter().any(|p| p.split(':').next() == Some(name));
+}
+
+fn is_integer(n: &str) -> bool {
+    let n = n.to_ascii_lowercase();
+    let digits = n.trim_end_matches(['u', 'l']);
+    if let Some(hex) = digits.strip_prefix("0x") {
+        return !hex.is_empty() && hex.chars().all(|c| c.is_ascii_hexdigit());
+    }
+    return !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit());
+}
@@ -0,0 +1,141 @@
+use std::ops::Range;
+
+// A tokenizer for the parts of C that notc reads, good enough for headers and simple sources
+//
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CToken {
+    Identifier(String),
+    Number(String),
+    Str(String),
+    Char(String),
+    Punct(&'static str),
+    // A whole preprocessor line, without the '#' and with continuations joined
+    Directive(String),
+    // Text between /* */ or after //, kept so translations can carry it over
+    Comment(String),
+}
+
+const PUNCTUATION: &[&str] = &[
+    "...", "<<=", ">>=", "->", "++", "--", "<<", ">>", "<=", ">=", "==", "!=", "&&", "||", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "(", ")", "[", "]", "{", "}", ";", ",", ".", "*",
+    "&", "+", "-", "~", "!", "/", "%", "<", ">", "^", "|", "?", ":", "=",
+];
+
+pub fn tokenize(text: &str) -> Vec<CToken> {
+    return tokenize_spanned(text).into_iter().map(|(t, _)| t).collect();
+}
+
+// Tokens along with where they are in the text
+pub fn tokenize_spanned(text: &str) -> Vec<(CToken, Range<usize>)> {
+    let mut tokens = Vec::new();
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    // Directives only start at the beginning of a line
+    let mut line_start = true;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        let start = i;
+
+        if c == '\n' {
+            line_start = true;
+            i += 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        if text[i..].starts_with("//") {
+            let end = text[i..].find('\n').map_or(text.len(), |e| i + e);
+            tokens.push((
+                CToken::Comment(text[i + 2..end].trim().to_string()),
+                start..end,
+            ));
+            i = end;
+            continue;
+        }
+        if text[i..].starts_with("/*") {
+            let end = text[i + 2..].find("*/").map_or(text.len(), |e| i + 2 + e);
+            i = (end + 2).min(text.len());
+            tokens.push((
+                CToken::Comment(text[start + 2..end].trim().to_string()),
+                start..i,
+            ));
+            continue;
+        }
+
+        if c == '#' && line_start {
+            let mut directive = String::new();
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'\n' {
+                if text[i..].starts_with("\\\n") {
+                    i += 2;
+                    directive.push(' ');
+                    continue;
+                }
+                if text[i..].starts_with("/*") {
+                    let end = text[i + 2..].find("*/").map_or(text.len(), |e| i + 2 + e);
+                    i = (end + 2).min(text.len());
+                    directive.push(' ');
+                    continue;
+                }
+                if text[i..].starts_with("//") {
+                    break;
+                }
+                directive.push(bytes[i] as char);
+                i += 1;
+            }
+            tokens.push((CToken::Directive(directive.trim().to_string()), start..i));
+            continue;
+        }
+        line_start = false;
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            tokens.push((CToken::Identifier(text[start..i].to_string()), start..i));
+            continue;
+        }
+
+        if c.is_ascii_digit() || (c == '.' && i + 1 < bytes.len() && bytes[i + 1].is_ascii_digit())
+        {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'.') {
+                i += 1;
+            }
+            tokens.push((CToken::Number(text[start..i].to_string()), start..i));
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            i += 1;
+            while i < bytes.len() && bytes[i] as char != c {
+                if bytes[i] == b'\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(bytes.len());
+            let literal = text[start..i].to_string();
+            let token = match c {
+                '"' => CToken::Str(literal),
+                _ => CToken::Char(literal),
+            };
+            tokens.push((token, start..i));
+            continue;
+        }
+
+        match PUNCTUATION.iter().find(|p| text[i..].starts_with(**p)) {
+            Some(p) => {
+                i += p.len();
+                tokens.push((CToken::Punct(p), start..i));
+            }
+            // Not something we understand, step over it
+            None => i += text[i..].chars().next().map_or(1, |c| c.len_utf8()),
+        }
+    }
+
+    return tokens;
+}
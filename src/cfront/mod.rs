@@ -0,0 +1,5 @@
+// Reading C, to bring declarations from headers into notc and to translate C sources
+//
+pub mod header;
+pub mod lexer;
+pub mod translate;
@@ -0,0 +1,859 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::Range,
+    rc::Rc,
+};
+
+use super::header::{CType, DeclParser, QUALIFIERS, TYPE_WORDS, usable_name};
+use super::lexer::{CToken, tokenize_spanned};
+use crate::{
+    lexer::Lexer,
+    parsing::{ParseError, SpannedAstTree},
+};
+
+// C source rewritten as notc. Whatever notc can't say yet is left in as a TODO comment
+#[derive(Debug)]
+pub struct Translation {
+    pub source: String,
+    pub todos: usize,
+}
+
+// Binary operators notc has, with how tightly they bind in C
+const BINARY: &[(&str, usize)] = &[("*", 10), ("/", 10), ("+", 9), ("-", 9), ("==", 6)];
+// Ones it doesn't, so they can be reported instead of ending the expression
+const UNSUPPORTED_BINARY: &[&str] = &[
+    "%", "<<", ">>", "<", ">", "<=", ">=", "!=", "&", "^", "|", "&&", "||", "?", "=", "+=", "-=",
+    "*=", "/=", "%=", "&=", "|=", "^=", "<<=", ">>=",
+];
+const ASSIGNMENTS: &[&str] = &["=", "+=", "-=", "*=", "/="];
+
+pub fn translate(text: &str) -> Translation {
+    let mut tokens = Vec::new();
+    let mut spans = Vec::new();
+    let mut comments = Vec::new();
+    for (token, span) in tokenize_spanned(text) {
+        match token {
+            // Comments are kept aside, and written out before the code that followed them
+            CToken::Comment(comment) => comments.push((tokens.len(), comment)),
+            token => {
+                tokens.push(token);
+                spans.push(span);
+            }
+        }
+    }
+
+    let mut translator = Translator {
+        text,
+        spans,
+        comments,
+        next_comment: 0,
+        out: String::new(),
+        todos: 0,
+        defined: defined_functions(&tokens),
+        variants: HashMap::new(),
+    };
+    let mut parser = DeclParser::new(tokens);
+
+    while let Some(token) = parser.peek().cloned() {
+        let start = parser.pos;
+        let comments = translator.comments_before(start, 0);
+        translator.out += &comments;
+        match token {
+            CToken::Directive(directive) => {
+                parser.pos += 1;
+                let line = translator.directive(&directive, start);
+                translator.out += &line;
+            }
+            CToken::Punct(";") => parser.pos += 1,
+            _ => {
+                if let Err(e) = translator.item(&mut parser) {
+                    parser.pos = start;
+                    parser.skip_declaration();
+                    let todo = translator.todo(&e, start..parser.pos, 0);
+                    translator.out += &format!("{}\n\n", todo);
+                }
+            }
+        }
+    }
+    let comments = translator.comments_before(usize::MAX, 0);
+    translator.out += &comments;
+
+    return Translation {
+        source: translator.out,
+        todos: translator.todos,
+    };
+}
+
+// Parses a translation back, to make sure it is valid notc
+pub fn check(source: &str) -> Result<(), ParseError> {
+    let source: Rc<str> = source.into();
+    let mut lexer = Lexer::from_rc_str(source.clone());
+    return SpannedAstTree::from_rc_str(source).parse_all(&mut lexer);
+}
+
+// Functions with bodies, whose prototypes notc doesn't need
+fn defined_functions(tokens: &[CToken]) -> HashSet<String> {
+    let mut defined = HashSet::new();
+    let mut depth = 0;
+    for i in 0..tokens.len() {
+        match &tokens[i] {
+            CToken::Punct("{") => depth += 1,
+            CToken::Punct("}") => depth -= 1,
+            CToken::Identifier(name) if depth == 0 => {
+                if tokens.get(i + 1) != Some(&CToken::Punct("(")) {
+                    continue;
+                }
+                let mut parens = 0;
+                for (j, token) in tokens.iter().enumerate().skip(i + 1) {
+                    match token {
+                        CToken::Punct("(") => parens += 1,
+                        CToken::Punct(")") => parens -= 1,
+                        _ => {}
+                    }
+                    if parens == 0 {
+                        if tokens.get(j + 1) == Some(&CToken::Punct("{")) {
+                            defined.insert(name.clone());
+                        }
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    return defined;
+}
+
+// C names that are notc keywords get an underscore, eg let becomes let_
+fn ident(name: &str) -> String {
+    if usable_name(name) {
+        return name.to_string();
+    }
+    return format!("{}_", name);
+}
+
+fn spell(t: &CType) -> Result<String, String> {
+    return t
+        .spell()
+        .ok_or(format!("the type {} isn't supported", t.describe()));
+}
+
+// An integer literal as notc reads it, in decimal and without C's suffixes
+fn integer(n: &str) -> Option<String> {
+    let lower = n.to_ascii_lowercase();
+    let digits = lower.trim_end_matches(['u', 'l']);
+    let value = if let Some(hex) = digits.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if digits.len() > 1 && digits.starts_with('0') {
+        u64::from_str_radix(&digits[1..], 8).ok()?
+    } else {
+        digits.parse().ok()?
+    };
+    return Some(value.to_string());
+}
+
+struct Translator<'a> {
+    text: &'a str,
+    // Where each token came from, so TODOs can quote the C
+    spans: Vec<Range<usize>>,
+    // Comments, with the index of the token they came before
+    comments: Vec<(usize, String)>,
+    next_comment: usize,
+    out: String,
+    todos: usize,
+    defined: HashSet<String>,
+    // Enum constants and the enum they belong to, as notc names them through it
+    variants: HashMap<String, String>,
+}
+
+impl Translator<'_> {
+    fn indent(level: usize) -> String {
+        return "    ".repeat(level);
+    }
+
+    fn comments_before(&mut self, token: usize, level: usize) -> String {
+        let mut out = String::new();
+        while let Some((at, comment)) = self.comments.get(self.next_comment) {
+            if *at > token {
+                break;
+            }
+            for line in comment.lines() {
+                let line = line.trim().trim_start_matches('*').trim();
+                out += &format!("{}// {}\n", Self::indent(level), line);
+            }
+            self.next_comment += 1;
+        }
+        return out;
+    }
+
+    // The C of some tokens, commented out with the reason it was left
+    fn todo(&mut self, reason: &str, tokens: Range<usize>, level: usize) -> String {
+        self.todos += 1;
+        let indent = Self::indent(level);
+        let mut out = format!("{}// TODO: {}", indent, reason);
+        if tokens.start < tokens.end && tokens.end <= self.spans.len() {
+            let start = self.spans[tokens.start].start;
+            let c = &self.text[start..self.spans[tokens.end - 1].end];
+            // Later lines are indented relative to the first
+            let column = start - self.text[..start].rfind('\n').map_or(0, |n| n + 1);
+            for line in c.lines() {
+                let dedent = line.len() - line.trim_start().len();
+                out += &format!(
+                    "\n{}//     {}",
+                    indent,
+                    line[dedent.min(column)..].trim_end()
+                );
+            }
+        }
+        return out;
+    }
+
+    fn directive(&mut self, directive: &str, token: usize) -> String {
+        if let Some(header) = directive.strip_prefix("include") {
+            let header = header.trim();
+            if header.len() > 2 {
+                return format!("import_c \"{}\";\n\n", &header[1..header.len() - 1]);
+            }
+        }
+        return format!(
+            "{}\n\n",
+            self.todo(
+                "preprocessor directives aren't supported",
+                token..token + 1,
+                0
+            )
+        );
+    }
+
+    //
+    // Top level declarations
+    //
+    fn item(&mut self, parser: &mut DeclParser) -> Result<(), String> {
+        let start = parser.pos;
+        let specifiers =
+            parser.specifiers(&mut |p, kind, tag, typedef| self.define(p, kind, tag, typedef))?;
+        // Just a struct or enum
+        if parser.eat_punct(";") {
+            return Ok(());
+        }
+
+        loop {
+            let (name, ctype) = parser
+                .declarator(specifiers.base.clone(), &mut |p, kind, tag, typedef| {
+                    self.define(p, kind, tag, typedef)
+                })?;
+            parser.skip_noise();
+            let name = name.ok_or("expected a name")?;
+
+            if specifiers.typedef {
+                let alias = ident(&name);
+                match &ctype {
+                    // typedef struct { .. } Name; already named the struct
+                    CType::Named(n) if *n == alias => {}
+                    _ => {
+                        let target;
+                        match spell(&ctype) {
+                            Ok(t) => target = t,
+                            Err(e) => {
+                                // Uses of the name are reported where they are
+                                parser.typedefs.entry(name).or_insert(ctype);
+                                return Err(e);
+                            }
+                        }
+                        self.out += &format!("type {} = {};\n\n", alias, target);
+                    }
+                }
+                parser.typedefs.entry(name).or_insert(CType::Named(alias));
+            } else {
+                match ctype {
+                    CType::Function {
+                        params,
+                        ret,
+                        variadic,
+                    } => {
+                        if parser.is_punct("{") {
+                            if variadic {
+                                return Err("variadic functions can't be defined in notc".into());
+                            }
+                            let function = self.function(parser, &name, &params, &ret)?;
+                            self.out += &function;
+                            return Ok(());
+                        }
+                        if !self.defined.contains(&name) {
+                            let mut params = self.params(&params)?;
+                            if variadic {
+                                params.push("...".to_string());
+                            }
+                            self.out += &format!(
+                                "extern fn {}({}) {};\n\n",
+                                ident(&name),
+                                params.join(", "),
+                                spell(&ret)?
+                            );
+                        }
+                    }
+                    _ => {
+                        parser.pos = start;
+                        parser.skip_declaration();
+                        let todo = self.todo(
+                            "global variables aren't supported yet",
+                            start..parser.pos,
+                            0,
+                        );
+                        self.out += &format!("{}\n\n", todo);
+                        return Ok(());
+                    }
+                }
+            }
+
+            if parser.eat_punct(",") {
+                continue;
+            }
+            return parser.expect_punct(";");
+        }
+    }
+
+    fn params(&self, params: &[(Option<String>, CType)]) -> Result<Vec<String>, String> {
+        let mut out = Vec::new();
+        for (i, (name, ptype)) in params.iter().enumerate() {
+            let name = match name {
+                Some(name) => ident(name),
+                None => format!("p{}", i),
+            };
+            out.push(format!("{}: {}", name, spell(ptype)?));
+        }
+        return Ok(out);
+    }
+
+    fn function(
+        &mut self,
+        parser: &mut DeclParser,
+        name: &str,
+        params: &[(Option<String>, CType)],
+        ret: &CType,
+    ) -> Result<String, String> {
+        if params.iter().any(|(name, _)| name.is_none()) {
+            return Err("function definitions need named parameters".into());
+        }
+        let params = self.params(params)?;
+        let signature = format!(
+            "fn {}({}) {} -> ",
+            ident(name),
+            params.join(", "),
+            spell(ret)?
+        );
+        let body = self.block(parser, 0);
+        return Ok(format!("{}{}\n\n", signature, body));
+    }
+
+    // Imports a struct or enum body as a notc type. Typedefed ones take the typedef's name
+    fn define(
+        &mut self,
+        parser: &mut DeclParser,
+        kind: &str,
+        tag: Option<String>,
+        typedef: bool,
+    ) -> Result<CType, String> {
+        let open = parser.pos;
+        let close = parser.matching(open).ok_or("unclosed brace")?;
+        let typedef_name = match (parser.tokens.get(close + 1), parser.tokens.get(close + 2)) {
+            (Some(CToken::Identifier(n)), Some(CToken::Punct(";" | ","))) if typedef => {
+                Some(n.clone())
+            }
+            _ => None,
+        };
+        let name;
+        match typedef_name.or(tag.clone()) {
+            Some(n) if kind != "union" => name = ident(&n),
+            Some(_) => return Err("unions aren't supported".into()),
+            None => return Err(format!("anonymous {}s aren't supported", kind)),
+        }
+        if let Some(tag) = &tag {
+            parser
+                .tags
+                .insert(format!("{} {}", kind, tag), name.clone());
+        }
+
+        let mut out = self.comments_before(open, 0);
+        out += &format!("{} {} {{\n", kind, name);
+        parser.pos += 1;
+        if kind == "enum" {
+            while let Some(CToken::Identifier(variant)) = parser.peek().cloned() {
+                let start = parser.pos;
+                parser.pos += 1;
+                if parser.eat_punct("=") {
+                    while !parser.is_punct(",") && !parser.is_punct("}") && parser.peek().is_some()
+                    {
+                        parser.pos += 1;
+                    }
+                    let todo =
+                        self.todo("notc enums can't pick their values", start..parser.pos, 1);
+                    out += &format!("{}\n", todo);
+                }
+                out += &format!("    {},\n", ident(&variant));
+                self.variants.insert(variant, name.clone());
+                if !parser.eat_punct(",") {
+                    break;
+                }
+            }
+        } else {
+            while !parser.is_punct("}") && parser.peek().is_some() {
+                let start = parser.pos;
+                out += &self.comments_before(start, 1);
+                match self.fields(parser) {
+                    Ok(fields) => out += &fields,
+                    Err(e) => {
+                        parser.pos = start;
+                        parser.skip_declaration();
+                        let todo = self.todo(&e, start..parser.pos, 1);
+                        out += &format!("{}\n", todo);
+                    }
+                }
+            }
+        }
+        parser.pos = close + 1;
+        out += "}\n\n";
+        self.out += &out;
+
+        return Ok(CType::Named(name));
+    }
+
+    fn fields(&mut self, parser: &mut DeclParser) -> Result<String, String> {
+        let specifiers =
+            parser.specifiers(&mut |p, kind, tag, typedef| self.define(p, kind, tag, typedef))?;
+        let mut out = String::new();
+        loop {
+            let (name, ftype) = parser
+                .declarator(specifiers.base.clone(), &mut |p, kind, tag, typedef| {
+                    self.define(p, kind, tag, typedef)
+                })?;
+            if parser.is_punct(":") {
+                return Err("bit fields aren't supported".into());
+            }
+            let name = name.ok_or("unnamed fields aren't supported")?;
+            out += &format!("    {}: {},\n", ident(&name), spell(&ftype)?);
+            if parser.eat_punct(",") {
+                continue;
+            }
+            parser.expect_punct(";")?;
+            return Ok(out);
+        }
+    }
+
+    //
+    // Statements
+    //
+    fn block(&mut self, parser: &mut DeclParser, level: usize) -> String {
+        let mut out = "{\n".to_string();
+        parser.pos += 1;
+        while !parser.is_punct("}") && parser.peek().is_some() {
+            out += &self.comments_before(parser.pos, level + 1);
+            let statement = self.statement(parser, level + 1);
+            out += &format!("{}\n", statement);
+        }
+        parser.pos += 1;
+        out += &self.comments_before(parser.pos - 1, level + 1);
+        out += &format!("{}}}", Self::indent(level));
+        return out;
+    }
+
+    // A statement, indented to the level. Ones that can't be translated become TODOs
+    fn statement(&mut self, parser: &mut DeclParser, level: usize) -> String {
+        let start = parser.pos;
+        match self.try_statement(parser, level) {
+            Ok(statement) => return statement,
+            Err(e) => {
+                parser.pos = start;
+                skip_statement(parser);
+                return self.todo(&e, start..parser.pos, level);
+            }
+        }
+    }
+
+    // Statements inside an if, which can't be just a comment
+    fn branch(&mut self, parser: &mut DeclParser, level: usize) -> String {
+        if parser.is_punct("{") {
+            return self.block(parser, level);
+        }
+        let statement = self.statement(parser, level + 1);
+        if statement.trim_start().starts_with("//") {
+            return format!("{{\n{}\n{}}}", statement, Self::indent(level));
+        }
+        return statement.trim_start().to_string();
+    }
+
+    fn try_statement(&mut self, parser: &mut DeclParser, level: usize) -> Result<String, String> {
+        let indent = Self::indent(level);
+
+        if parser.is_punct("{") {
+            return Ok(format!("{}{}", indent, self.block(parser, level)));
+        }
+        if parser.eat_punct(";") {
+            return Ok(format!("{}{{}}", indent));
+        }
+
+        let keyword = parser.peek_identifier().map(|k| k.to_string());
+        match keyword.as_deref() {
+            Some("if") => {
+                parser.pos += 1;
+                parser.expect_punct("(")?;
+                let condition = self.expression(parser)?;
+                parser.expect_punct(")")?;
+                let mut out = format!(
+                    "{}if ({}) {}",
+                    indent,
+                    condition,
+                    self.branch(parser, level)
+                );
+                if parser.peek_identifier() == Some("else") {
+                    parser.pos += 1;
+                    out += &format!(" else {}", self.branch(parser, level));
+                }
+                return Ok(out);
+            }
+            Some("return") => {
+                parser.pos += 1;
+                if parser.eat_punct(";") {
+                    return Ok(format!("{}return;", indent));
+                }
+                let value = self.expression(parser)?;
+                parser.expect_punct(";")?;
+                return Ok(format!("{}return {};", indent, value));
+            }
+            Some("while" | "for" | "do") => return Err("notc has no loops yet".into()),
+            Some("switch") => return Err("notc has no switch yet".into()),
+            Some(k @ ("goto" | "break" | "continue")) => {
+                return Err(format!("notc has no {} yet", k));
+            }
+            _ => {}
+        }
+
+        if self.is_declaration(parser) {
+            return self.declaration(parser, level);
+        }
+
+        // Increments, eg i++; or --i;
+        for (op, notc) in [("++", "+="), ("--", "-=")] {
+            if parser.is_punct(op)
+                && let Some(CToken::Identifier(name)) = parser.peek_at(1).cloned()
+                && matches!(parser.peek_at(2), Some(CToken::Punct(";")))
+            {
+                parser.pos += 3;
+                return Ok(format!("{}{} {} 1;", indent, self.name(&name), notc));
+            }
+            if let Some(CToken::Identifier(name)) = parser.peek().cloned()
+                && matches!(parser.peek_at(1), Some(CToken::Punct(p)) if *p == op)
+                && matches!(parser.peek_at(2), Some(CToken::Punct(";")))
+            {
+                parser.pos += 3;
+                return Ok(format!("{}{} {} 1;", indent, self.name(&name), notc));
+            }
+        }
+
+        // Discards, eg (void)f(x);
+        if parser.is_punct("(")
+            && parser.peek_at(1) == Some(&CToken::Identifier("void".to_string()))
+            && matches!(parser.peek_at(2), Some(CToken::Punct(")")))
+        {
+            parser.pos += 3;
+            let value = self.expression(parser)?;
+            parser.expect_punct(";")?;
+            return Ok(format!("{}_ = {};", indent, value));
+        }
+        if let (Some(CToken::Identifier(_)), Some(CToken::Punct(":"))) =
+            (parser.peek(), parser.peek_at(1))
+        {
+            return Err("notc has no labels".into());
+        }
+
+        // Assignments to variables, fields, indexes and dereferences, and calls
+        let target = self.unary(parser)?.0;
+        match parser.peek().cloned() {
+            Some(CToken::Punct(op)) if ASSIGNMENTS.contains(&op) => {
+                parser.pos += 1;
+                let value = self.expression(parser)?;
+                parser.expect_punct(";")?;
+                return Ok(format!("{}{} {} {};", indent, target, op, value));
+            }
+            Some(CToken::Punct(";")) if target.ends_with(')') && !target.starts_with('(') => {
+                parser.pos += 1;
+                return Ok(format!("{}{};", indent, target));
+            }
+            _ => {
+                return Err("only calls, assignments and (void) discards can be statements".into());
+            }
+        }
+    }
+
+    fn is_declaration(&self, parser: &DeclParser) -> bool {
+        match parser.peek() {
+            Some(CToken::Identifier(i)) => {
+                return TYPE_WORDS.contains(&i.as_str())
+                    || QUALIFIERS.contains(&i.as_str())
+                    || matches!(i.as_str(), "struct" | "union" | "enum" | "typedef")
+                    || parser.typedefs.contains_key(i)
+                    // Two names in a row can only be a type and a variable
+                    || matches!(parser.peek_at(1), Some(CToken::Identifier(_)));
+            }
+            _ => return false,
+        }
+    }
+
+    // Local variables, one let for each declarator
+    fn declaration(&mut self, parser: &mut DeclParser, level: usize) -> Result<String, String> {
+        let specifiers =
+            parser.specifiers(&mut |p, kind, tag, typedef| self.define(p, kind, tag, typedef))?;
+        if specifiers.typedef {
+            return Err("local typedefs aren't supported".into());
+        }
+        if parser.eat_punct(";") {
+            return Ok(format!("{}{{}}", Self::indent(level)));
+        }
+
+        let mut lets = Vec::new();
+        loop {
+            let (name, vtype) = parser
+                .declarator(specifiers.base.clone(), &mut |p, kind, tag, typedef| {
+                    self.define(p, kind, tag, typedef)
+                })?;
+            let name = name.ok_or("expected a name")?;
+            let vtype = spell(&vtype)?;
+            let value;
+            if parser.eat_punct("=") {
+                if parser.is_punct("{") {
+                    return Err("initialiser lists aren't supported".into());
+                }
+                value = self.expression(parser)?;
+            } else if matches!(vtype.as_str(), "i32" | "usize" | "u8") {
+                // notc variables always start with a value
+                value = "0".to_string();
+            } else {
+                return Err(format!("{} needs an initial value", name));
+            }
+            lets.push(format!(
+                "{}let {}: {} = {};",
+                Self::indent(level),
+                ident(&name),
+                vtype,
+                value
+            ));
+
+            if parser.eat_punct(",") {
+                continue;
+            }
+            parser.expect_punct(";")?;
+            return Ok(lets.join("\n"));
+        }
+    }
+
+    //
+    // Expressions
+    //
+    fn name(&self, name: &str) -> String {
+        match self.variants.get(name) {
+            Some(e) => return format!("{}::{}", e, ident(name)),
+            None => return ident(name),
+        }
+    }
+
+    fn expression(&mut self, parser: &mut DeclParser) -> Result<String, String> {
+        return Ok(self.binary(parser, 0)?.0);
+    }
+
+    // Precedence climbing over the operators notc has. Operands that are operations themselves
+    // are put in parentheses, so notc reads them the same way C did. Also returns whether the
+    // result is an operation
+    fn binary(&mut self, parser: &mut DeclParser, min: usize) -> Result<(String, bool), String> {
+        let (mut left, mut compound) = self.unary(parser)?;
+        loop {
+            let op;
+            match parser.peek() {
+                Some(CToken::Punct(p)) => op = *p,
+                _ => break,
+            }
+            let precedence;
+            match BINARY.iter().find(|(o, _)| *o == op) {
+                Some((_, p)) => precedence = *p,
+                None if UNSUPPORTED_BINARY.contains(&op) => {
+                    return Err(format!("notc has no {} operator yet", op));
+                }
+                None => break,
+            }
+            if precedence < min {
+                break;
+            }
+            parser.pos += 1;
+
+            let (right, right_compound) = self.binary(parser, precedence + 1)?;
+            let wrap = |s: String, c: bool| if c { format!("({})", s) } else { s };
+            left = format!(
+                "{} {} {}",
+                wrap(left, compound),
+                op,
+                wrap(right, right_compound)
+            );
+            compound = true;
+        }
+        return Ok((left, compound));
+    }
+
+    fn unary(&mut self, parser: &mut DeclParser) -> Result<(String, bool), String> {
+        match parser.peek().cloned() {
+            Some(CToken::Punct(op @ ("-" | "!"))) => {
+                parser.pos += 1;
+                let (operand, compound) = self.unary(parser)?;
+                if compound {
+                    return Ok((format!("{}({})", op, operand), false));
+                }
+                return Ok((format!("{}{}", op, operand), false));
+            }
+            Some(CToken::Punct("+")) => {
+                parser.pos += 1;
+                return self.unary(parser);
+            }
+            Some(CToken::Punct("*")) => {
+                parser.pos += 1;
+                let (operand, compound) = self.unary(parser)?;
+                if compound {
+                    return Ok((format!("*({})", operand), false));
+                }
+                return Ok((format!("*{}", operand), false));
+            }
+            Some(CToken::Punct(op @ ("&" | "~" | "++" | "--"))) => {
+                return Err(format!("notc has no unary {} operator yet", op));
+            }
+            Some(CToken::Identifier(i)) if i == "sizeof" => {
+                return Err("notc has no sizeof yet".into());
+            }
+            _ => return self.postfix(parser),
+        }
+    }
+
+    // Calls, indexes and fields. notc reaches fields through pointers with . as well, so -> is
+    // written as .
+    fn postfix(&mut self, parser: &mut DeclParser) -> Result<(String, bool), String> {
+        let (mut out, compound) = self.primary(parser)?;
+        let name = !compound && out.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if parser.is_punct("(") {
+            if !name {
+                return Err("only named functions can be called".into());
+            }
+            parser.pos += 1;
+            let mut args = Vec::new();
+            if !parser.eat_punct(")") {
+                loop {
+                    args.push(self.binary(parser, 0)?.0);
+                    if parser.eat_punct(")") {
+                        break;
+                    }
+                    parser.expect_punct(",")?;
+                }
+            }
+            out = format!("{}({})", out, args.join(", "));
+        }
+        loop {
+            match parser.peek() {
+                Some(CToken::Punct("[")) => {
+                    parser.pos += 1;
+                    let index = self.expression(parser)?;
+                    parser.expect_punct("]")?;
+                    out = format!("{}[{}]", out, index);
+                }
+                Some(CToken::Punct("." | "->")) => {
+                    parser.pos += 1;
+                    let field = parser.peek_identifier().ok_or("expected a field")?;
+                    out = format!("{}.{}", out, ident(field));
+                    parser.pos += 1;
+                }
+                Some(CToken::Punct("(")) => {
+                    return Err("only named functions can be called".into());
+                }
+                Some(CToken::Punct("++" | "--")) => {
+                    return Err("increments can only be statements in notc".into());
+                }
+                _ => return Ok((out, compound)),
+            }
+        }
+    }
+
+    fn primary(&mut self, parser: &mut DeclParser) -> Result<(String, bool), String> {
+        let token = parser.peek().cloned().ok_or("expected an expression")?;
+        parser.pos += 1;
+        match token {
+            CToken::Identifier(name) => return Ok((self.name(&name), false)),
+            CToken::Number(n) => match integer(&n) {
+                Some(n) => return Ok((n, false)),
+                None => return Err(format!("notc has no literals like {} yet", n)),
+            },
+            // Neighbouring strings are joined, as in C
+            CToken::Str(s) => {
+                let mut literal = s[..s.len() - 1].to_string();
+                while let Some(CToken::Str(next)) = parser.peek().cloned() {
+                    literal += &next[1..next.len() - 1];
+                    parser.pos += 1;
+                }
+                return Ok((literal + "\"", false));
+            }
+            CToken::Char(_) => return Err("notc has no character literals yet".into()),
+            CToken::Punct("(") => {
+                let is_cast = match parser.peek() {
+                    Some(CToken::Identifier(i)) => {
+                        TYPE_WORDS.contains(&i.as_str())
+                            || QUALIFIERS.contains(&i.as_str())
+                            || matches!(i.as_str(), "struct" | "union" | "enum")
+                            || parser.typedefs.contains_key(i)
+                    }
+                    _ => false,
+                };
+                if is_cast {
+                    return Err("notc has no casts yet".into());
+                }
+                let inner = self.binary(parser, 0)?.0;
+                parser.expect_punct(")")?;
+                return Ok((format!("({})", inner), false));
+            }
+            _ => return Err("expected an expression".into()),
+        }
+    }
+}
+
+// Steps over a whole statement, including whatever statements are inside it
+fn skip_statement(parser: &mut DeclParser) {
+    if parser.is_punct("{") {
+        parser.skip_group();
+        return;
+    }
+    let keyword = parser.peek_identifier().map(|k| k.to_string());
+    match keyword.as_deref() {
+        Some("if") => {
+            parser.pos += 1;
+            parser.skip_group();
+            skip_statement(parser);
+            if parser.peek_identifier() == Some("else") {
+                parser.pos += 1;
+                skip_statement(parser);
+            }
+        }
+        Some("while" | "for" | "switch") => {
+            parser.pos += 1;
+            parser.skip_group();
+            skip_statement(parser);
+        }
+        Some("do") => {
+            parser.pos += 1;
+            skip_statement(parser);
+            parser.skip_declaration();
+        }
+        // Labels, eg cleanup:
+        _ if matches!(parser.peek_at(1), Some(CToken::Punct(":"))) => {
+            parser.pos += 2;
+            skip_statement(parser);
+        }
+        Some("case" | "default") => {
+            while !parser.eat_punct(":") && parser.peek().is_some() {
+                parser.pos += 1;
+            }
+        }
+        _ => parser.skip_declaration(),
+    }
+}
@@ -1,21 +1,66 @@
-use std::{fs::File, io::Write, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::Write,
+    rc::Rc,
+};
 
+use super::mangle::mangle;
 use crate::{
     traits,
-    tree_checker::ResolvedAstTree,
-    types::resolved_types::{IdentifierId, ResolvedChunk, ResolvedExpression, ResolvedStatement},
+    tree_checker::{ResolvedAstTree, TypeId, TypeNotes},
+    types::{
+        Attribute, AttributeTarget, Span, attribute_targets,
+        resolved_types::{
+            IdentifierId, IdentifierKind, ResolvedChunk, ResolvedExpression, ResolvedIdentifier,
+            ResolvedParam, ResolvedStatement, ResolvedType, SymbolKind,
+        },
+        spanned_types::BinaryOperation,
+    },
 };
 
-const PRIMITIVE_MAP: &[&str] = &["void", "bool", "unsigned int"];
-const PREAMBLE: &str = "typedef int bool;
-enum {
-    false,
-    true
-};\n\n";
+const PRIMITIVE_MAP: &[&str] = &["void", "bool", "unsigned int", "int", "unsigned char"];
+// How tightly C binds what is written for each expression, from C's precedence table. Operands
+// binding looser than their place needs are put in parentheses
+const C_PRIMARY: usize = 16;
+const C_POSTFIX: usize = 15;
+const C_UNARY: usize = 14;
+// How C writes each binary operator, and how tightly it binds
+fn c_binary(operation: BinaryOperation) -> (&'static str, usize) {
+    match operation {
+        BinaryOperation::Multiply => return ("*", 13),
+        BinaryOperation::Divide => return ("/", 13),
+        BinaryOperation::Add => return ("+", 12),
+        BinaryOperation::Subtract => return ("-", 12),
+        BinaryOperation::Equal => return ("==", 9),
+    }
+}
+// C's own bool, so the generated headers don't take bool, true and false from the C including
+// them
+pub(super) const PREAMBLE: &str = "#include <stdbool.h>\n\n";
+
+// What a function body needs to know to leave it, the statements deferred by each of the
+// scopes it is in, outermost first
+struct Frame<'a> {
+    rtype: &'a ResolvedType,
+    defers: Vec<Vec<&'a ResolvedStatement>>,
+}
 
 pub struct CCodeGen {
     target_file: File,
     source_file: Rc<str>,
+    names: Vec<Rc<str>>,
+    notes: TypeNotes,
+    // Optionals, results and tuples that are structs
+    generated: Vec<TypeId>,
+    // The C names of types and items, which locals can't take without hiding them
+    globals: HashSet<String>,
+    // Whether doc comments are written out as C comments
+    comments: bool,
+    // Locals and params already named. C doesn't allow one to shadow another in the same scope,
+    // so the names taken in the function being written are numbered after the first
+    bound: HashSet<IdentifierId>,
+    taken: HashSet<String>,
 }
 
 impl CCodeGen {
@@ -23,92 +68,492 @@ impl CCodeGen {
         return CCodeGen {
             source_file: input,
             target_file: file,
+            names: Vec::new(),
+            notes: TypeNotes::default(),
+            generated: Vec::new(),
+            globals: HashSet::new(),
+            comments: false,
+            bound: HashSet::new(),
+            taken: HashSet::new(),
         };
     }
+    // Where the type checker found optionals being made and unwrapped
+    pub fn with_notes(mut self, notes: TypeNotes) -> Self {
+        self.notes = notes;
+        return self;
+    }
+
+    // Carries /// doc comments into the C, for --keep-comments
+    pub fn keep_comments(mut self, keep: bool) -> Self {
+        self.comments = keep;
+        return self;
+    }
+
+    fn name(&self, id: &ResolvedIdentifier) -> String {
+        return self.names[id.id.0].to_string();
+    }
+    // Names a local or param where it is declared, eg the second x in a function becomes x_1
+    fn bind_local(&mut self, id: &ResolvedIdentifier) -> String {
+        if !self.bound.insert(id.id) {
+            return self.name(id);
+        }
+        let name = self.fresh(&self.names[id.id.0].clone());
+        self.names[id.id.0] = name.as_str().into();
+        return name;
+    }
+
+    // A name no other name in the function has, numbered after base if it is taken
+    fn fresh(&mut self, base: &str) -> String {
+        let mut name = base.to_string();
+        let mut n = 0;
+        while self.taken.contains(&name) {
+            n += 1;
+            name = format!("{}_{}", base, n);
+        }
+        self.taken.insert(name.clone());
+        return name;
+    }
 }
 
 impl traits::CodeGen<ResolvedAstTree> for CCodeGen {
     fn generate(mut self, ast: &ResolvedAstTree) -> Result<(), std::io::Error> {
+        self.names = c_names(ast, &self.source_file);
+        self.globals = ast
+            .symbols
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| {
+                matches!(
+                    s.kind,
+                    SymbolKind::Type
+                        | SymbolKind::Function
+                        | SymbolKind::Static
+                        | SymbolKind::Variant
+                )
+            })
+            .map(|(i, _)| self.names[i].to_string())
+            .collect();
+
+        // Headers requested by extern declarations, each included once. Quoted, so headers next
+        // to the program are found as well as system ones
+        let mut includes = Vec::new();
+        for chunk in &ast.body {
+            match chunk {
+                ResolvedChunk::ExternFunction {
+                    include: Some(header),
+                    ..
+                }
+                | ResolvedChunk::ExternStatic {
+                    include: Some(header),
+                    ..
+                }
+                | ResolvedChunk::Struct {
+                    include: Some(header),
+                    ..
+                }
+                | ResolvedChunk::Enum {
+                    include: Some(header),
+                    ..
+                } => {
+                    let header = &self.source_file[header.start + 1..header.end - 1];
+                    if !includes.contains(&header) {
+                        includes.push(header);
+                    }
+                }
+                _ => {}
+            }
+        }
+        for header in &includes {
+            write!(self.target_file, "#include \"{}\"\n", header)?;
+        }
+        if !includes.is_empty() {
+            write!(self.target_file, "\n")?;
+        }
+
         write!(self.target_file, "{}", PREAMBLE)?;
+
+        self.generated = generated_types(ast);
+        // The types of expressions aren't always written out, eg tuple literals
+        let mut nodes: Vec<_> = self.notes.expressions.keys().collect();
+        nodes.sort_by_key(|node| node.0);
+        for node in nodes {
+            let t = self
+                .notes
+                .coerced
+                .get(node)
+                .unwrap_or(&self.notes.expressions[node]);
+            find_generated(t, &mut self.generated);
+        }
+        self.generate_types(ast)?;
+
+        // Everything is declared before any body, so functions can be used before they are
+        // defined, and call each other
+        for chunk in &ast.body {
+            self.declare_chunk(chunk)?;
+        }
         for chunk in &ast.body {
             self.generate_chunk(chunk)?;
         }
 
+        // C starts at its own main, which runs the program's and returns the exit status. The
+        // arguments are argv as a []str
+        if let Some(ResolvedChunk::Function {
+            name,
+            params,
+            rtype,
+            ..
+        }) = ast.entry_point()
+        {
+            let main = &self.names[name.id.0];
+            let mut args = String::new();
+            if let Some(param) = params.first() {
+                args = format!(
+                    "({}){{(unsigned char **)argv, (unsigned int)argc}}",
+                    generated_name(&self.names, &TypeId::from(&param.ptype))
+                );
+            }
+            write!(
+                self.target_file,
+                "int main(int argc, char **argv){{\n(void)argc;\n(void)argv;\n"
+            )?;
+            match TypeId::from(rtype) {
+                TypeId::Void => write!(self.target_file, "{}({});\nreturn 0;\n}}\n", main, args)?,
+                _ => write!(self.target_file, "return {}({});\n}}\n", main, args)?,
+            }
+        }
+
         Ok(())
     }
 }
 impl CCodeGen {
-    fn generate_chunk(&mut self, chunk: &ResolvedChunk) -> Result<(), std::io::Error> {
+    // Structs are declared up front, so pointers to them can be used anywhere. The rest follow
+    // what they hold
+    fn generate_types(&mut self, ast: &ResolvedAstTree) -> Result<(), std::io::Error> {
+        let order = type_order(ast, &self.generated);
+        let mut chunks = HashMap::new();
+        for chunk in &ast.body {
+            if let ResolvedChunk::Struct { name, .. }
+            | ResolvedChunk::Enum { name, .. }
+            | ResolvedChunk::TypeAlias { name, .. } = chunk
+            {
+                chunks.insert(name.id, chunk);
+            }
+        }
+
+        let mut declared = false;
+        for t in &order {
+            if let TypeId::Named(id) = t
+                && let Some(declaration) = declare_struct(&self.names, chunks[id])
+            {
+                write!(self.target_file, "{}", declaration)?;
+                declared = true;
+            }
+        }
+        if declared {
+            write!(self.target_file, "\n")?;
+        }
+
+        for t in &order {
+            match t {
+                TypeId::Named(id) => {
+                    self.generate_docs(chunks[id])?;
+                    write!(
+                        self.target_file,
+                        "{}",
+                        define_type(&self.names, &self.source_file, chunks[id], self.comments)
+                            .unwrap()
+                    )?;
+                }
+                t => write!(self.target_file, "{}", define_generated(&self.names, t))?,
+            }
+        }
+
+        Ok(())
+    }
+    fn declare_chunk(&mut self, chunk: &ResolvedChunk) -> Result<(), std::io::Error> {
         match chunk {
+            // Declared by their header
+            ResolvedChunk::ExternFunction {
+                include: Some(_), ..
+            }
+            | ResolvedChunk::ExternStatic {
+                include: Some(_), ..
+            }
+            | ResolvedChunk::Struct {
+                include: Some(_), ..
+            }
+            | ResolvedChunk::Enum {
+                include: Some(_), ..
+            } => Ok(()),
+            ResolvedChunk::ExternFunction {
+                attributes,
+                name,
+                params,
+                variadic,
+                rtype,
+                ..
+            } => {
+                self.generate_docs(chunk)?;
+                let signature = signature(&self.names, &self.name(name), params, *variadic);
+                write!(
+                    self.target_file,
+                    "{}{};\n\n",
+                    declare(&self.names, rtype, &signature),
+                    c_attributes(
+                        &self.source_file,
+                        attributes,
+                        AttributeTarget::ExternFunction
+                    )
+                )
+            }
+            ResolvedChunk::ExternStatic {
+                attributes,
+                name,
+                vtype,
+                ..
+            } => {
+                self.generate_docs(chunk)?;
+                write!(
+                    self.target_file,
+                    "extern {}{};\n\n",
+                    declare(&self.names, vtype, &self.name(name)),
+                    c_attributes(&self.source_file, attributes, AttributeTarget::ExternStatic)
+                )
+            }
+            // An inline definition also needs an extern declaration, or C leaves the symbol out.
+            // Private functions are static, so only they are linked to
+            ResolvedChunk::Function {
+                attributes,
+                name,
+                params,
+                rtype,
+                ..
+            } => {
+                self.generate_docs(chunk)?;
+                let signature = signature(&self.names, &self.name(name), params, false);
+                let linkage;
+                if !linked(chunk) {
+                    linkage = "static ";
+                } else if attributes
+                    .iter()
+                    .any(|a| &self.source_file[a.name.clone()] == "inline")
+                {
+                    linkage = "extern ";
+                } else {
+                    linkage = "";
+                }
+                write!(
+                    self.target_file,
+                    "{}{};\n\n",
+                    linkage,
+                    declare(&self.names, rtype, &signature)
+                )
+            }
             ResolvedChunk::Constant => todo!(),
             ResolvedChunk::StaticVar => todo!(),
+            _ => Ok(()),
+        }
+    }
+    fn generate_chunk(&mut self, chunk: &ResolvedChunk) -> Result<(), std::io::Error> {
+        match chunk {
             ResolvedChunk::Function {
+                attributes,
                 name,
                 params,
                 rtype,
                 body,
                 ..
             } => {
-                write!(
-                    self.target_file,
-                    "{} {}(",
-                    resolve_type(&rtype.id),
-                    &self.source_file[name.span.clone()]
-                )?;
+                // Lambdas are lifted out in front of the function that uses them
+                self.lift_statement(body)?;
 
-                for i in 0..params.len() {
-                    write!(
-                        self.target_file,
-                        "{} {}",
-                        resolve_type(&params[i].ptype.id),
-                        &self.source_file[params[i].name.span.clone()]
-                    )?;
-                    if i < params.len() - 1 {
-                        write!(self.target_file, ", ")?;
-                    }
+                let name = self.name(name);
+                if !linked(chunk) {
+                    write!(self.target_file, "static ")?;
+                }
+                // Attributes go before the declarator of a definition
+                let c_attributes =
+                    c_attributes(&self.source_file, attributes, AttributeTarget::Function);
+                if !c_attributes.is_empty() {
+                    write!(self.target_file, "{} ", c_attributes.trim_start())?;
+                }
+                if c_attributes.contains("always_inline") {
+                    write!(self.target_file, "inline ")?;
                 }
+                self.generate_function(&name, params, rtype, body)
+            }
+            ResolvedChunk::EOF => panic!(),
+            _ => Ok(()),
+        }
+    }
+    fn generate_docs(&mut self, chunk: &ResolvedChunk) -> Result<(), std::io::Error> {
+        if self.comments {
+            write!(
+                self.target_file,
+                "{}",
+                c_docs(&self.source_file, chunk_docs(chunk), "")
+            )?;
+        }
 
-                write!(self.target_file, ")")?;
-                self.generate_statement(body)?;
-                write!(self.target_file, "\n")?;
+        Ok(())
+    }
+    fn generate_function(
+        &mut self,
+        name: &str,
+        params: &[ResolvedParam],
+        rtype: &ResolvedType,
+        body: &ResolvedStatement,
+    ) -> Result<(), std::io::Error> {
+        self.taken = self.globals.clone();
+        for param in params {
+            self.bind_local(&param.name);
+        }
+        let signature = signature(&self.names, name, params, false);
+        write!(
+            self.target_file,
+            "{}",
+            declare(&self.names, rtype, &signature)
+        )?;
+        let mut frame = Frame {
+            rtype,
+            defers: Vec::new(),
+        };
+        self.generate_statement(body, &mut frame)?;
+        write!(self.target_file, "\n")?;
 
-                Ok(())
+        Ok(())
+    }
+
+    // Lambda lifting
+    //
+    // Lambdas can't capture, so each one becomes a static function. Inner lambdas are lifted
+    // before the lambdas that contain them
+    fn lift_statement(&mut self, statement: &ResolvedStatement) -> Result<(), std::io::Error> {
+        match statement {
+            ResolvedStatement::Decleration { expression, .. } => {
+                self.lift_expression(expression)?
+            }
+            ResolvedStatement::Reassignment {
+                target, expression, ..
+            } => {
+                self.lift_expression(target)?;
+                self.lift_expression(expression)?;
+            }
+            ResolvedStatement::If {
+                condition,
+                statement,
+                ielse,
+                ..
+            } => {
+                self.lift_expression(condition)?;
+                self.lift_statement(statement)?;
+                if let Some(st) = ielse {
+                    self.lift_statement(st)?;
+                }
+            }
+            ResolvedStatement::IfLet {
+                expression,
+                statement,
+                ielse,
+                ..
+            } => {
+                self.lift_expression(expression)?;
+                self.lift_statement(statement)?;
+                if let Some(st) = ielse {
+                    self.lift_statement(st)?;
+                }
+            }
+            ResolvedStatement::Scope { body, .. } => {
+                for st in body {
+                    self.lift_statement(st)?;
+                }
+            }
+            ResolvedStatement::Return { expression, .. } => {
+                if let Some(expression) = expression {
+                    self.lift_expression(expression)?;
+                }
+            }
+            ResolvedStatement::Destructure { expression, .. }
+            | ResolvedStatement::Expression { expression, .. }
+            | ResolvedStatement::Discard { expression, .. } => self.lift_expression(expression)?,
+            ResolvedStatement::Defer { statement, .. }
+            | ResolvedStatement::Attributed { statement, .. } => self.lift_statement(statement)?,
+        }
+
+        Ok(())
+    }
+    fn lift_expression(&mut self, expression: &ResolvedExpression) -> Result<(), std::io::Error> {
+        match expression {
+            ResolvedExpression::Identifier(_, _)
+            | ResolvedExpression::Literal { .. }
+            | ResolvedExpression::StringLiteral { .. }
+            | ResolvedExpression::NoneLiteral { .. } => {}
+            ResolvedExpression::UnaryOperator { expression, .. }
+            | ResolvedExpression::TupleField {
+                tuple: expression, ..
+            }
+            | ResolvedExpression::Field { expression, .. }
+            | ResolvedExpression::Deref { expression, .. }
+            | ResolvedExpression::Try { expression, .. } => self.lift_expression(expression)?,
+            ResolvedExpression::BinaryOperator { left, right, .. }
+            | ResolvedExpression::Index {
+                expression: left,
+                index: right,
+                ..
+            }
+            | ResolvedExpression::Orelse { left, right, .. } => {
+                self.lift_expression(left)?;
+                self.lift_expression(right)?;
+            }
+            ResolvedExpression::Call {
+                params: elements, ..
+            }
+            | ResolvedExpression::Tuple { elements, .. } => {
+                for e in elements {
+                    self.lift_expression(e)?;
+                }
+            }
+            ResolvedExpression::Lambda {
+                id,
+                params,
+                rtype,
+                body,
+                ..
+            } => {
+                self.lift_statement(body)?;
+
+                write!(self.target_file, "static ")?;
+                self.generate_function(&lambda_name(*id), params, rtype, body)?;
             }
-            ResolvedChunk::EOF => panic!(),
         }
+
+        Ok(())
     }
-    fn generate_statement(&mut self, statemet: &ResolvedStatement) -> Result<(), std::io::Error> {
+    fn generate_statement<'a>(
+        &mut self,
+        statemet: &'a ResolvedStatement,
+        frame: &mut Frame<'a>,
+    ) -> Result<(), std::io::Error> {
         match statemet {
             ResolvedStatement::Decleration {
                 name,
                 expression,
                 rtype,
                 ..
-            } => {
-                write!(
-                    self.target_file,
-                    "{} {} = ",
-                    resolve_type(&rtype.id),
-                    &self.source_file[name.span.clone()]
-                )?;
-
-                self.generate_expression(expression)?;
-
-                write!(self.target_file, ";\n")?;
-            }
+            } => self.generate_decleration(name, expression, rtype, "", frame)?,
             ResolvedStatement::Reassignment {
-                name,
+                target,
                 expression,
-                span,
+                operation,
+                ..
             } => {
-                write!(
-                    self.target_file,
-                    "{} {} ",
-                    &self.source_file[name.span.clone()],
-                    &self.source_file[span.clone()]
-                )?;
+                self.generate_value(target, frame)?;
+                match operation {
+                    Some(operation) => write!(self.target_file, " {}= ", c_binary(*operation).0)?,
+                    None => write!(self.target_file, " = ")?,
+                }
 
-                self.generate_expression(expression)?;
+                self.generate_expression(expression, frame)?;
 
                 write!(self.target_file, ";\n")?;
             }
@@ -119,86 +564,1154 @@ impl CCodeGen {
                 ..
             } => {
                 write!(self.target_file, "if (")?;
-                self.generate_expression(condition)?;
+                self.generate_expression(condition, frame)?;
                 write!(self.target_file, ")")?;
-                self.generate_statement(statement)?;
+                self.generate_statement(statement, frame)?;
 
                 if let Some(st) = ielse {
                     write!(self.target_file, " else ")?;
-                    self.generate_statement(st)?;
+                    self.generate_statement(st, frame)?;
                 }
             }
-            ResolvedStatement::Scope { body, .. } => {
+            // Optional pointers are checked against null, other optionals and results have a flag
+            ResolvedStatement::IfLet {
+                name,
+                expression,
+                statement,
+                ielse,
+                ..
+            } => {
+                let unwrapped = self.notes.type_of(expression).clone();
+                let (inner, flag) = unwrap_parts(&unwrapped);
+                let name = self.bind_local(name);
+
                 write!(self.target_file, "{{\n")?;
-                for st in body {
-                    self.generate_statement(st)?;
+                if let TypeId::Optional(_) = unwrapped
+                    && inner.nullable()
+                {
+                    write!(
+                        self.target_file,
+                        "{} = ",
+                        declare_type(&self.names, &inner, &name)
+                    )?;
+                    self.generate_expression(expression, frame)?;
+                    write!(self.target_file, ";\nif ({})", name)?;
+                    self.generate_statement(statement, frame)?;
+                } else {
+                    write!(
+                        self.target_file,
+                        "{} notc_unwrap = ",
+                        generated_name(&self.names, &unwrapped)
+                    )?;
+                    self.generate_expression(expression, frame)?;
+                    write!(self.target_file, ";\nif (notc_unwrap.{}) {{\n", flag)?;
+                    // The value of a void!E is left out
+                    if inner != TypeId::Void {
+                        write!(
+                            self.target_file,
+                            "{} = notc_unwrap.value;\n",
+                            declare_type(&self.names, &inner, &name)
+                        )?;
+                    }
+                    self.generate_statement(statement, frame)?;
+                    write!(self.target_file, "}}")?;
+                }
+                if let Some(st) = ielse {
+                    write!(self.target_file, " else ")?;
+                    self.generate_statement(st, frame)?;
                 }
                 write!(self.target_file, "}}\n")?;
             }
-            ResolvedStatement::VoidCall { name, params, .. } => {
-                write!(
-                    self.target_file,
-                    "{}(",
-                    &self.source_file[name.span.clone()],
-                )?;
-                for i in 0..params.len() {
-                    self.generate_expression(&params[i])?;
-                    if i < params.len() - 1 {
-                        write!(self.target_file, ", ")?;
+            // Deferred statements are copied to every way out of the scope, in reverse order
+            ResolvedStatement::Scope { body, .. } => {
+                write!(self.target_file, "{{\n")?;
+                frame.defers.push(Vec::new());
+                for st in body {
+                    match unattributed(st) {
+                        ResolvedStatement::Defer { statement, .. } => {
+                            frame.defers.last_mut().unwrap().push(statement)
+                        }
+                        _ => self.generate_statement(st, frame)?,
+                    }
+                }
+                let defers = frame.defers.pop().unwrap();
+                // Already run by the return
+                if !matches!(
+                    body.last().map(unattributed),
+                    Some(ResolvedStatement::Return { .. })
+                ) {
+                    for st in defers.into_iter().rev() {
+                        self.generate_statement(st, frame)?;
+                    }
+                    // Reaching the end of a void!E function is a success
+                    let rtype = TypeId::from(frame.rtype);
+                    if frame.defers.is_empty() && matches!(rtype, TypeId::Result { .. }) {
+                        write!(
+                            self.target_file,
+                            "return ({}){{.ok = 1}};\n",
+                            generated_name(&self.names, &rtype)
+                        )?;
                     }
                 }
+                write!(self.target_file, "}}\n")?;
+            }
+            ResolvedStatement::Expression { expression, .. } => {
+                self.generate_expression(expression, frame)?;
+                write!(self.target_file, ";\n")?;
+            }
+            ResolvedStatement::Discard { expression, .. } => {
+                write!(self.target_file, "(void)(")?;
+                self.generate_expression(expression, frame)?;
                 write!(self.target_file, ");\n")?;
             }
             ResolvedStatement::Return { expression, .. } => {
-                write!(self.target_file, "return ")?;
+                // A bare return from a void!E function is a success
+                let rtype = TypeId::from(frame.rtype);
+                let bare = match rtype {
+                    TypeId::Result { .. } => {
+                        format!(
+                            "return ({}){{.ok = 1}};\n",
+                            generated_name(&self.names, &rtype)
+                        )
+                    }
+                    _ => "return;\n".to_string(),
+                };
+                if frame.defers.iter().all(|scope| scope.is_empty()) {
+                    match expression {
+                        Some(expression) => {
+                            write!(self.target_file, "return ")?;
+                            self.generate_expression(expression, frame)?;
+                            write!(self.target_file, ";\n")?;
+                        }
+                        None => write!(self.target_file, "{}", bare)?,
+                    }
+                    return Ok(());
+                }
+
+                // The value is worked out before the deferred statements run
+                write!(self.target_file, "{{\n")?;
+                let result = self.fresh("notc_result");
                 if let Some(expression) = expression {
-                    self.generate_expression(expression)?;
+                    write!(
+                        self.target_file,
+                        "{} = ",
+                        declare(&self.names, frame.rtype, &result)
+                    )?;
+                    self.generate_expression(expression, frame)?;
+                    write!(self.target_file, ";\n")?;
+                }
+                self.generate_defers(frame)?;
+                match expression {
+                    Some(_) => write!(self.target_file, "return {};\n}}\n", result)?,
+                    None => write!(self.target_file, "{}}}\n", bare)?,
                 }
+            }
+            // The names have to be in the enclosing scope, so the tuple is held in a fresh name
+            ResolvedStatement::Destructure {
+                names, expression, ..
+            } => {
+                let tuple = self.notes.type_of(expression).clone();
+                let tmp = self.fresh("notc_tuple");
+                write!(
+                    self.target_file,
+                    "{} {} = ",
+                    generated_name(&self.names, &tuple),
+                    tmp
+                )?;
+                self.generate_expression(expression, frame)?;
                 write!(self.target_file, ";\n")?;
+                if let TypeId::Tuple(elements) = tuple {
+                    for (i, (name, t)) in names.iter().zip(elements.iter()).enumerate() {
+                        let name = self.bind_local(name);
+                        write!(
+                            self.target_file,
+                            "{} = {}._{};\n",
+                            declare_type(&self.names, t, &name),
+                            tmp,
+                            i
+                        )?;
+                    }
+                }
+            }
+            // Not directly in a scope, like the branch of an if, so it is its own scope
+            ResolvedStatement::Defer { statement, .. } => {
+                self.generate_statement(statement, frame)?
             }
+            // Only let statements take attributes, the parser warns about the rest
+            ResolvedStatement::Attributed {
+                attributes,
+                statement,
+                ..
+            } => match statement.as_ref() {
+                ResolvedStatement::Decleration {
+                    name,
+                    expression,
+                    rtype,
+                    ..
+                } => {
+                    let c_attributes =
+                        c_attributes(&self.source_file, attributes, AttributeTarget::Let);
+                    self.generate_decleration(name, expression, rtype, &c_attributes, frame)?
+                }
+                st => self.generate_statement(st, frame)?,
+            },
         }
 
         Ok(())
     }
+    fn generate_decleration(
+        &mut self,
+        name: &ResolvedIdentifier,
+        expression: &ResolvedExpression,
+        rtype: &ResolvedType,
+        c_attributes: &str,
+        frame: &mut Frame,
+    ) -> Result<(), std::io::Error> {
+        let name = self.bind_local(name);
+        write!(
+            self.target_file,
+            "{}{} = ",
+            declare(&self.names, rtype, &name),
+            c_attributes
+        )?;
+        self.generate_expression(expression, frame)?;
+        write!(self.target_file, ";\n")?;
+
+        Ok(())
+    }
+    // Every deferred statement of the scopes being left, innermost first
+    fn generate_defers<'a>(&mut self, frame: &mut Frame<'a>) -> Result<(), std::io::Error> {
+        let defers: Vec<_> = frame
+            .defers
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .copied()
+            .collect();
+        for st in defers {
+            self.generate_statement(st, frame)?;
+        }
+
+        Ok(())
+    }
+    // A GNU C statement expression, which returns the error from the function if there is one
+    fn generate_try(
+        &mut self,
+        expression: &ResolvedExpression,
+        frame: &mut Frame,
+    ) -> Result<(), std::io::Error> {
+        let tried = self.notes.type_of(expression).clone();
+        let rtype = TypeId::from(frame.rtype);
+        write!(
+            self.target_file,
+            "({{ {} notc_try = ",
+            generated_name(&self.names, &tried)
+        )?;
+        self.generate_expression(expression, frame)?;
+        write!(self.target_file, ";\nif (!notc_try.ok) {{\n")?;
+        self.generate_defers(frame)?;
+        write!(
+            self.target_file,
+            "return ({}){{.ok = 0, .error = notc_try.error}};\n}}\n",
+            generated_name(&self.names, &rtype)
+        )?;
+        if !matches!(tried, TypeId::Result { ok, .. } if *ok == TypeId::Void) {
+            write!(self.target_file, "notc_try.value; ")?;
+        }
+        write!(self.target_file, "}})")?;
+
+        Ok(())
+    }
+    // Values used as optionals or results are wrapped up first
     fn generate_expression(
         &mut self,
         expression: &ResolvedExpression,
+        frame: &mut Frame,
+    ) -> Result<(), std::io::Error> {
+        let node = expression.get_node();
+        let wrapped;
+        let fields;
+        if let Some(t) = self.notes.ok.get(&node) {
+            wrapped = t.clone();
+            fields = ".ok = 1, .value = ";
+        } else if let Some(t) = self.notes.error.get(&node) {
+            wrapped = t.clone();
+            fields = ".ok = 0, .error = ";
+        } else {
+            match self.notes.wrapped.get(&node) {
+                Some(t @ TypeId::Optional(inner)) if !inner.nullable() => wrapped = t.clone(),
+                _ => return self.generate_value(expression, frame),
+            }
+            if let ResolvedExpression::NoneLiteral { .. } = expression {
+                let name = generated_name(&self.names, &wrapped);
+                return write!(self.target_file, "({}){{.present = 0}}", name);
+            }
+            fields = ".present = 1, .value = ";
+        }
+
+        let name = generated_name(&self.names, &wrapped);
+        write!(self.target_file, "({}){{{}", name, fields)?;
+        self.generate_value(expression, frame)?;
+        write!(self.target_file, "}}")?;
+
+        Ok(())
+    }
+    // An operand of an operator binding at `min`, in parentheses if it binds looser
+    fn generate_operand(
+        &mut self,
+        expression: &ResolvedExpression,
+        min: usize,
+        frame: &mut Frame,
+    ) -> Result<(), std::io::Error> {
+        if self.c_precedence(expression) >= min {
+            return self.generate_expression(expression, frame);
+        }
+        write!(self.target_file, "(")?;
+        self.generate_expression(expression, frame)?;
+        write!(self.target_file, ")")?;
+        Ok(())
+    }
+    // How tightly the C for an expression binds. Optionals and results made from a value are
+    // compound literals, which bind tightly enough to be any operand, so aren't looked for
+    fn c_precedence(&self, expression: &ResolvedExpression) -> usize {
+        match expression {
+            ResolvedExpression::UnaryOperator { .. } => return C_UNARY,
+            ResolvedExpression::BinaryOperator { operation, .. } => return c_binary(*operation).1,
+            ResolvedExpression::TupleField { tuple, .. }
+                if matches!(self.notes.type_of(tuple), TypeId::Named(_)) =>
+            {
+                return self.c_precedence(tuple);
+            }
+            // Making a distinct type is a cast, which is already in parentheses
+            ResolvedExpression::Call { name, .. } if name.kind == IdentifierKind::Type => {
+                return C_PRIMARY;
+            }
+            ResolvedExpression::Call { .. }
+            | ResolvedExpression::Tuple { .. }
+            | ResolvedExpression::TupleField { .. }
+            | ResolvedExpression::Field { .. }
+            | ResolvedExpression::Index { .. } => return C_POSTFIX,
+            _ => return C_PRIMARY,
+        }
+    }
+    fn generate_value(
+        &mut self,
+        expression: &ResolvedExpression,
+        frame: &mut Frame,
     ) -> Result<(), std::io::Error> {
         match expression {
-            ResolvedExpression::Identifier(resolved_identifier) => write!(
-                self.target_file,
-                "{}",
-                &self.source_file[resolved_identifier.span.clone()]
-            )?,
-            ResolvedExpression::Literal { span } => todo!(),
+            ResolvedExpression::Identifier(resolved_identifier, _) => {
+                write!(self.target_file, "{}", self.name(resolved_identifier))?
+            }
+            ResolvedExpression::Literal { span, .. }
+            | ResolvedExpression::StringLiteral { span, .. } => {
+                write!(self.target_file, "{}", &self.source_file[span.clone()])?
+            }
+            // Only reached for optional pointers
+            ResolvedExpression::NoneLiteral { .. } => write!(self.target_file, "((void *)0)")?,
             ResolvedExpression::UnaryOperator {
                 operation,
                 expression,
+                ..
             } => {
-                write!(self.target_file, "{}", &self.source_file[operation.clone()])?;
-                self.generate_expression(&expression)?;
+                let operator = &self.source_file[operation.clone()];
+                write!(self.target_file, "{}", operator)?;
+                // - -a, as --a would be a decrement
+                let mut min = C_UNARY;
+                if let ResolvedExpression::UnaryOperator { operation, .. } = expression.as_ref()
+                    && self.source_file[operation.clone()] == *operator
+                {
+                    min += 1;
+                }
+                self.generate_operand(expression, min, frame)?;
             }
+            // C groups operators of the same precedence from the left, so only the right
+            // operand is in parentheses at the same level, eg a - (b - c)
             ResolvedExpression::BinaryOperator {
-                left, span, right, ..
+                left,
+                operation,
+                right,
+                ..
+            } => {
+                let (operator, level) = c_binary(*operation);
+                self.generate_operand(left, level, frame)?;
+                write!(self.target_file, " {} ", operator)?;
+                self.generate_operand(right, level + 1, frame)?;
+            }
+            // Making a distinct type is a cast, eg Meters(5) is ((Meters)(5))
+            ResolvedExpression::Call { name, params, .. } if name.kind == IdentifierKind::Type => {
+                write!(self.target_file, "(({})(", self.name(name))?;
+                self.generate_expression(&params[0], frame)?;
+                write!(self.target_file, "))")?;
+            }
+            ResolvedExpression::Call { name, params, .. } => {
+                write!(self.target_file, "{}(", self.name(name),)?;
+                for i in 0..params.len() {
+                    self.generate_expression(&params[i], frame)?;
+                    if i < params.len() - 1 {
+                        write!(self.target_file, ", ")?;
+                    }
+                }
+                write!(self.target_file, ")")?;
+            }
+            ResolvedExpression::Tuple { elements, .. } => {
+                let name = generated_name(&self.names, self.notes.given_as(expression));
+                write!(self.target_file, "({}){{", name)?;
+                for i in 0..elements.len() {
+                    self.generate_expression(&elements[i], frame)?;
+                    if i < elements.len() - 1 {
+                        write!(self.target_file, ", ")?;
+                    }
+                }
+                write!(self.target_file, "}}")?;
+            }
+            // Only distinct values, the named types with a .0, are read like tuples. Distinct types
+            // are typedefs of what they are made from
+            ResolvedExpression::TupleField { tuple, .. }
+                if matches!(self.notes.type_of(tuple), TypeId::Named(_)) =>
+            {
+                self.generate_expression(tuple, frame)?
+            }
+            ResolvedExpression::TupleField { tuple, index, .. } => {
+                self.generate_operand(tuple, C_POSTFIX, frame)?;
+                write!(self.target_file, "._{}", index)?;
+            }
+            ResolvedExpression::Field {
+                expression, field, ..
+            } => {
+                self.generate_operand(expression, C_POSTFIX, frame)?;
+                let access = if let TypeId::Pointer(_) = self.notes.type_of(expression) {
+                    "->"
+                } else {
+                    "."
+                };
+                write!(self.target_file, "{}{}", access, self.name(field))?;
+            }
+            ResolvedExpression::Index {
+                expression, index, ..
             } => {
-                self.generate_expression(&left)?;
-                write!(self.target_file, " {} ", &self.source_file[span.clone()])?;
-                self.generate_expression(&right)?;
+                self.generate_operand(expression, C_POSTFIX, frame)?;
+                if let TypeId::Slice(_) = self.notes.type_of(expression) {
+                    write!(self.target_file, ".ptr")?;
+                }
+                write!(self.target_file, "[")?;
+                self.generate_expression(index, frame)?;
+                write!(self.target_file, "]")?;
+            }
+            ResolvedExpression::Deref { expression, .. } => {
+                write!(self.target_file, "(*")?;
+                self.generate_operand(expression, C_UNARY, frame)?;
+                write!(self.target_file, ")")?;
+            }
+            ResolvedExpression::Try { expression, .. } => self.generate_try(expression, frame)?,
+            // The default is only worked out when needed. Uses GNU C's ?: and statement
+            // expressions, so the optional is only worked out once
+            ResolvedExpression::Orelse { left, right, .. } => {
+                let unwrapped = self.notes.type_of(left).clone();
+                let (inner, flag) = unwrap_parts(&unwrapped);
+
+                if let TypeId::Optional(_) = unwrapped
+                    && inner.nullable()
+                {
+                    write!(self.target_file, "(")?;
+                    self.generate_expression(left, frame)?;
+                    write!(self.target_file, " ?: ")?;
+                    self.generate_expression(right, frame)?;
+                    write!(self.target_file, ")")?;
+                } else {
+                    write!(
+                        self.target_file,
+                        "({{ {} notc_unwrap = ",
+                        generated_name(&self.names, &unwrapped)
+                    )?;
+                    self.generate_expression(left, frame)?;
+                    write!(
+                        self.target_file,
+                        "; notc_unwrap.{} ? notc_unwrap.value : ",
+                        flag
+                    )?;
+                    self.generate_expression(right, frame)?;
+                    write!(self.target_file, "; }})")?;
+                }
+            }
+            ResolvedExpression::Lambda { id, .. } => {
+                write!(self.target_file, "{}", lambda_name(*id))?
             }
-            ResolvedExpression::Call => todo!(),
-            ResolvedExpression::Tmp => panic!(),
         }
 
         Ok(())
     }
 }
 
-fn resolve_type(id: &IdentifierId) -> &str {
-    match id {
-        IdentifierId(i) if i < &3 => return PRIMITIVE_MAP[*i],
+// Doc comments as C line comments, which keep the /// for tools like Doxygen
+pub(super) fn c_docs(source: &str, docs: &[Span], indent: &str) -> String {
+    return docs
+        .iter()
+        .map(|d| format!("{}///{}\n", indent, &source[d.clone()]))
+        .collect();
+}
+pub(super) fn chunk_docs(chunk: &ResolvedChunk) -> &[Span] {
+    return match chunk {
+        ResolvedChunk::ExternFunction { docs, .. }
+        | ResolvedChunk::ExternStatic { docs, .. }
+        | ResolvedChunk::Struct { docs, .. }
+        | ResolvedChunk::Enum { docs, .. }
+        | ResolvedChunk::TypeAlias { docs, .. }
+        | ResolvedChunk::Function { docs, .. } => docs,
+        _ => &[],
+    };
+}
+
+// The statement under any attributes
+fn unattributed(statement: &ResolvedStatement) -> &ResolvedStatement {
+    match statement {
+        ResolvedStatement::Attributed { statement, .. } => return unattributed(statement),
+        statement => return statement,
+    }
+}
+
+// The attributes that apply to `target`, as a GCC/Clang __attribute__ with a space in front,
+// eg " __attribute__((packed, aligned(16)))". Empty if there are none
+pub(super) fn c_attributes(
+    source: &str,
+    attributes: &[Attribute],
+    target: AttributeTarget,
+) -> String {
+    let mut c = Vec::new();
+    for attribute in attributes {
+        let name = &source[attribute.name.clone()];
+        if !attribute_targets(name).is_some_and(|(targets, _)| targets.contains(&target)) {
+            continue;
+        }
+        match (name, attribute.value.clone().map(|v| &source[v])) {
+            ("inline", _) => c.push("always_inline".to_string()),
+            ("align", Some(v)) => c.push(format!("aligned({})", v)),
+            (name, Some(v)) => c.push(format!("{}({})", name, v)),
+            (name, None) => c.push(name.to_string()),
+        }
+    }
+    if c.is_empty() {
+        return String::new();
+    }
+    return format!(" __attribute__(({}))", c.join(", "));
+}
+
+// Whether a function is seen outside the C written for it, being pub or given a C name
+fn linked(chunk: &ResolvedChunk) -> bool {
+    return matches!(
+        chunk,
+        ResolvedChunk::Function { public: true, .. }
+            | ResolvedChunk::Function {
+                export: Some(_),
+                ..
+            }
+    );
+}
+fn lambda_name(id: usize) -> String {
+    format!("notc_lambda_{}", id)
+}
+
+// The C name of every identifier. Module items are flattened into one translation unit, so
+// math::add becomes nc_math__add, unless it is given an #[export_name(...)]. Externs keep the
+// names C knows them by
+pub(super) fn c_names(ast: &ResolvedAstTree, source: &str) -> Vec<Rc<str>> {
+    let mut names: Vec<Rc<str>> = ast
+        .symbols
+        .iter()
+        .map(|s| mangle(&s.name, s.kind).into())
+        .collect();
+    for chunk in &ast.body {
+        match chunk {
+            ResolvedChunk::ExternFunction { name, .. }
+            | ResolvedChunk::ExternStatic { name, .. }
+            | ResolvedChunk::Struct {
+                external: true,
+                export: None,
+                name,
+                ..
+            }
+            | ResolvedChunk::Enum {
+                external: true,
+                export: None,
+                name,
+                ..
+            } => names[name.id.0] = ast.symbols[name.id.0].name.clone(),
+            ResolvedChunk::Function {
+                export: Some(export),
+                name,
+                ..
+            }
+            | ResolvedChunk::Struct {
+                export: Some(export),
+                name,
+                ..
+            }
+            | ResolvedChunk::Enum {
+                export: Some(export),
+                name,
+                ..
+            } => names[name.id.0] = source[export.start + 1..export.end - 1].into(),
+            _ => {}
+        }
+        // C enum constants aren't scoped by their enum
+        if let ResolvedChunk::Enum {
+            external: true,
+            variants,
+            ..
+        } = chunk
+        {
+            for variant in variants {
+                names[variant.id.0] = source[variant.span.clone()].into();
+            }
+        }
+    }
+    // Types and values share one namespace in C, so types named like a function or static
+    // are given a _t suffix
+    let values: HashSet<Rc<str>> = ast
+        .symbols
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| matches!(s.kind, SymbolKind::Function | SymbolKind::Static))
+        .map(|(i, _)| names[i].clone())
+        .collect();
+    for (i, symbol) in ast.symbols.iter().enumerate() {
+        if symbol.kind == SymbolKind::Type {
+            while values.contains(&names[i]) {
+                names[i] = format!("{}_t", names[i]).into();
+            }
+        }
+    }
+    return names;
+}
+
+// Builds a C declarator for `name`. Function pointers wrap the name inside out, so
+// fn(usize) fn(bool) void becomes `void (*(*name)(unsigned int))(bool)`
+pub(super) fn declare(names: &[Rc<str>], t: &ResolvedType, name: &str) -> String {
+    return declare_type(names, &TypeId::from(t), name);
+}
+pub(super) fn declare_type(names: &[Rc<str>], t: &TypeId, name: &str) -> String {
+    let base;
+    match t {
+        TypeId::Void => base = PRIMITIVE_MAP[0].to_string(),
+        TypeId::Bool => base = PRIMITIVE_MAP[1].to_string(),
+        TypeId::Usize => base = PRIMITIVE_MAP[2].to_string(),
+        TypeId::I32 => base = PRIMITIVE_MAP[3].to_string(),
+        TypeId::U8 => base = PRIMITIVE_MAP[4].to_string(),
+        // Structs and enums are typedefed to their own name
+        TypeId::Named(id) => base = names[id.0].to_string(),
+        TypeId::Fn {
+            params,
+            ret,
+            variadic,
+        } => {
+            let mut params: Vec<_> = params.iter().map(|p| declare_type(names, p, "")).collect();
+            if *variadic {
+                params.push("...".to_string());
+            }
+            let params = if params.is_empty() {
+                "void".to_string()
+            } else {
+                params.join(", ")
+            };
+            return declare_type(names, ret, &format!("(*{})({})", name, params));
+        }
+        TypeId::Pointer(inner) => return declare_type(names, inner, &format!("*{}", name)),
+        TypeId::Optional(inner) if inner.nullable() => return declare_type(names, inner, name),
+        TypeId::Optional(_) | TypeId::Result { .. } | TypeId::Tuple(_) | TypeId::Slice(_) => {
+            base = generated_name(names, t)
+        }
+        TypeId::None => panic!(),
+    }
+    if name.is_empty() {
+        return base;
+    }
+    return format!("{} {}", base, name);
+}
+
+// Optionals and results that aren't pointers are structs, as are tuples and slices, named after
+// the types in them, eg notc_optional_usize, notc_result_usize_Error or notc_tuple_usize_bool
+pub(super) fn generated_name(names: &[Rc<str>], t: &TypeId) -> String {
+    match t {
+        TypeId::Optional(inner) => return format!("notc_optional_{}", type_key(names, inner)),
+        TypeId::Result { ok, error } => {
+            return format!(
+                "notc_result_{}_{}",
+                type_key(names, ok),
+                type_key(names, error)
+            );
+        }
+        TypeId::Tuple(elements) => {
+            let keys: Vec<_> = elements.iter().map(|e| type_key(names, e)).collect();
+            return format!("notc_tuple_{}", keys.join("_"));
+        }
+        TypeId::Slice(inner) => return format!("notc_slice_{}", type_key(names, inner)),
+        _ => panic!(),
+    }
+}
+// A type spelled as part of a C identifier
+fn type_key(names: &[Rc<str>], t: &TypeId) -> String {
+    match t {
+        TypeId::Named(id) => return names[id.0].replace(' ', "_"),
+        TypeId::Pointer(inner) => return format!("ptr_{}", type_key(names, inner)),
+        TypeId::Optional(inner) => return format!("opt_{}", type_key(names, inner)),
+        TypeId::Slice(inner) => return format!("slice_{}", type_key(names, inner)),
+        TypeId::Result { ok, error } => {
+            return format!("res_{}_{}", type_key(names, ok), type_key(names, error));
+        }
+        TypeId::Fn { params, ret, .. } => {
+            let mut key = "fn".to_string();
+            for p in params.iter() {
+                key += &format!("_{}", type_key(names, p));
+            }
+            return format!("{}_ret_{}", key, type_key(names, ret));
+        }
+        // Counted, as tuples can be any length
+        TypeId::Tuple(elements) => {
+            let mut key = format!("tup{}", elements.len());
+            for e in elements.iter() {
+                key += &format!("_{}", type_key(names, e));
+            }
+            return key;
+        }
+        TypeId::None => panic!(),
+        primitive => {
+            let i = [
+                TypeId::Void,
+                TypeId::Bool,
+                TypeId::Usize,
+                TypeId::I32,
+                TypeId::U8,
+            ]
+            .iter()
+            .position(|p| p == primitive)
+            .unwrap();
+            return crate::PRIMATIVE_TYPES[i].to_string();
+        }
+    }
+}
+// Guarded like the preamble, as headers define them too. The value of a void!E is left out
+pub(super) fn define_generated(names: &[Rc<str>], t: &TypeId) -> String {
+    let name = generated_name(names, t);
+    let body;
+    match t {
+        TypeId::Optional(inner) => {
+            body = format!(
+                "    bool present;\n    {};\n",
+                declare_type(names, inner, "value")
+            );
+        }
+        TypeId::Result { ok, error } => {
+            let mut union = String::new();
+            for (t, field) in [(ok, "value"), (error, "error")] {
+                if **t != TypeId::Void {
+                    union += &format!("        {};\n", declare_type(names, t, field));
+                }
+            }
+            body = format!("    bool ok;\n    union {{\n{}    }};\n", union);
+        }
+        TypeId::Tuple(elements) => {
+            let mut fields = String::new();
+            for (i, e) in elements.iter().enumerate() {
+                fields += &format!("    {};\n", declare_type(names, e, &format!("_{}", i)));
+            }
+            body = fields;
+        }
+        TypeId::Slice(inner) => {
+            body = format!(
+                "    {};\n    unsigned int len;\n",
+                declare_type(names, &TypeId::Pointer(inner.clone()), "ptr")
+            );
+        }
+        _ => panic!(),
+    }
+    return format!(
+        "#ifndef {0}\n#define {0}\ntypedef struct {{\n{1}}} {2};\n#endif\n\n",
+        name.to_uppercase(),
+        body,
+        name
+    );
+}
+// The type inside an optional or result struct, and the flag saying it is there
+fn unwrap_parts(t: &TypeId) -> (TypeId, &'static str) {
+    match t {
+        TypeId::Optional(inner) => return ((**inner).clone(), "present"),
+        TypeId::Result { ok, .. } => return ((**ok).clone(), "ok"),
+        _ => panic!(),
+    }
+}
+
+// Every optional, result and tuple used that is a struct, after the ones inside it
+pub(super) fn generated_types(ast: &ResolvedAstTree) -> Vec<TypeId> {
+    let mut types = Vec::new();
+    for chunk in &ast.body {
+        match chunk {
+            ResolvedChunk::ExternFunction { params, rtype, .. } => {
+                params.iter().for_each(|p| types.push(&p.ptype));
+                types.push(rtype);
+            }
+            ResolvedChunk::ExternStatic { vtype, .. } => types.push(vtype),
+            ResolvedChunk::TypeAlias { target, .. } => types.push(target),
+            ResolvedChunk::Struct { fields, .. } => {
+                fields.iter().for_each(|f| types.push(&f.ftype));
+            }
+            ResolvedChunk::Function {
+                params,
+                rtype,
+                body,
+                ..
+            } => {
+                params.iter().for_each(|p| types.push(&p.ptype));
+                types.push(rtype);
+                statement_types(body, &mut types);
+            }
+            _ => {}
+        }
+    }
+
+    let mut generated = Vec::new();
+    for t in types {
+        find_generated(&TypeId::from(t), &mut generated);
+    }
+    return generated;
+}
+fn find_generated(t: &TypeId, out: &mut Vec<TypeId>) {
+    match t {
+        TypeId::Optional(inner) => {
+            find_generated(inner, out);
+            if !inner.nullable() && !out.contains(t) {
+                out.push(t.clone());
+            }
+        }
+        TypeId::Result { ok, error } => {
+            find_generated(ok, out);
+            find_generated(error, out);
+            if !out.contains(t) {
+                out.push(t.clone());
+            }
+        }
+        TypeId::Tuple(elements) => {
+            elements.iter().for_each(|e| find_generated(e, out));
+            if !out.contains(t) {
+                out.push(t.clone());
+            }
+        }
+        TypeId::Slice(inner) => {
+            find_generated(inner, out);
+            if !out.contains(t) {
+                out.push(t.clone());
+            }
+        }
+        TypeId::Pointer(inner) => find_generated(inner, out),
+        TypeId::Fn { params, ret, .. } => {
+            params.iter().for_each(|p| find_generated(p, out));
+            find_generated(ret, out);
+        }
         _ => {}
     }
-    "other"
 }
+// The structs and enums a type is made from
+pub(super) fn named_types(t: &TypeId) -> Vec<IdentifierId> {
+    match t {
+        TypeId::Named(id) => return vec![*id],
+        TypeId::Pointer(inner) | TypeId::Optional(inner) | TypeId::Slice(inner) => {
+            return named_types(inner);
+        }
+        TypeId::Result { ok, error } => {
+            let mut named = named_types(ok);
+            named.extend(named_types(error));
+            return named;
+        }
+        TypeId::Tuple(elements) => return elements.iter().flat_map(named_types).collect(),
+        TypeId::Fn { params, ret, .. } => {
+            let mut named = named_types(ret);
+            params.iter().for_each(|p| named.extend(named_types(p)));
+            return named;
+        }
+        _ => return Vec::new(),
+    }
+}
+fn statement_types<'a>(statement: &'a ResolvedStatement, out: &mut Vec<&'a ResolvedType>) {
+    match statement {
+        ResolvedStatement::Decleration {
+            expression, rtype, ..
+        } => {
+            out.push(rtype);
+            expression_types(expression, out);
+        }
+        ResolvedStatement::Reassignment {
+            target, expression, ..
+        } => {
+            expression_types(target, out);
+            expression_types(expression, out);
+        }
+        ResolvedStatement::If {
+            condition: expression,
+            statement,
+            ielse,
+            ..
+        }
+        | ResolvedStatement::IfLet {
+            expression,
+            statement,
+            ielse,
+            ..
+        } => {
+            expression_types(expression, out);
+            statement_types(statement, out);
+            if let Some(st) = ielse {
+                statement_types(st, out);
+            }
+        }
+        ResolvedStatement::Scope { body, .. } => {
+            body.iter().for_each(|st| statement_types(st, out));
+        }
+        ResolvedStatement::Return { expression, .. } => {
+            if let Some(expression) = expression {
+                expression_types(expression, out);
+            }
+        }
+        ResolvedStatement::Destructure { expression, .. }
+        | ResolvedStatement::Expression { expression, .. }
+        | ResolvedStatement::Discard { expression, .. } => expression_types(expression, out),
+        ResolvedStatement::Defer { statement, .. }
+        | ResolvedStatement::Attributed { statement, .. } => statement_types(statement, out),
+    }
+}
+fn expression_types<'a>(expression: &'a ResolvedExpression, out: &mut Vec<&'a ResolvedType>) {
+    match expression {
+        ResolvedExpression::UnaryOperator { expression, .. }
+        | ResolvedExpression::TupleField {
+            tuple: expression, ..
+        }
+        | ResolvedExpression::Field { expression, .. }
+        | ResolvedExpression::Deref { expression, .. }
+        | ResolvedExpression::Try { expression, .. } => expression_types(expression, out),
+        ResolvedExpression::BinaryOperator { left, right, .. }
+        | ResolvedExpression::Index {
+            expression: left,
+            index: right,
+            ..
+        }
+        | ResolvedExpression::Orelse { left, right, .. } => {
+            expression_types(left, out);
+            expression_types(right, out);
+        }
+        ResolvedExpression::Call {
+            params: elements, ..
+        }
+        | ResolvedExpression::Tuple { elements, .. } => {
+            elements.iter().for_each(|e| expression_types(e, out));
+        }
+        ResolvedExpression::Lambda {
+            params,
+            rtype,
+            body,
+            ..
+        } => {
+            params.iter().for_each(|p| out.push(&p.ptype));
+            out.push(rtype);
+            statement_types(body, out);
+        }
+        _ => {}
+    }
+}
+// The types notc defines, each after the ones it is made from: its own structs, enums and
+// distinct types, followed by the optionals, results and tuples that are structs. Types can only
+// hold each other through pointers, which need no more than the declarations of structs, so
+// cycles are broken anywhere
+pub(super) fn type_order(ast: &ResolvedAstTree, generated: &[TypeId]) -> Vec<TypeId> {
+    let mut held = HashMap::new();
+    for chunk in &ast.body {
+        match chunk {
+            // Types from a C header are defined by it
+            ResolvedChunk::Struct {
+                include: Some(_), ..
+            }
+            | ResolvedChunk::Enum {
+                include: Some(_), ..
+            } => {}
+            ResolvedChunk::Struct { name, fields, .. } => {
+                let fields = fields.iter().map(|f| TypeId::from(&f.ftype)).collect();
+                held.insert(TypeId::Named(name.id), fields);
+            }
+            ResolvedChunk::Enum { name, .. } => {
+                held.insert(TypeId::Named(name.id), Vec::new());
+            }
+            ResolvedChunk::TypeAlias { name, target, .. } => {
+                held.insert(TypeId::Named(name.id), vec![TypeId::from(target)]);
+            }
+            _ => {}
+        }
+    }
+    let mut types: Vec<TypeId> = ast
+        .body
+        .iter()
+        .filter_map(|chunk| match chunk {
+            ResolvedChunk::Struct { name, .. }
+            | ResolvedChunk::Enum { name, .. }
+            | ResolvedChunk::TypeAlias { name, .. } => Some(TypeId::Named(name.id)),
+            _ => None,
+        })
+        .filter(|t| held.contains_key(t))
+        .collect();
+    for t in generated {
+        let inner = match t {
+            TypeId::Optional(inner) | TypeId::Slice(inner) => vec![(**inner).clone()],
+            TypeId::Result { ok, error } => vec![(**ok).clone(), (**error).clone()],
+            TypeId::Tuple(elements) => elements.to_vec(),
+            _ => Vec::new(),
+        };
+        held.insert(t.clone(), inner);
+        types.push(t.clone());
+    }
 
-//
+    let mut order = Vec::new();
+    let mut visiting = Vec::new();
+    for t in &types {
+        visit_type(t, &held, &mut visiting, &mut order);
+    }
+    return order;
+}
+fn visit_type(
+    t: &TypeId,
+    held: &HashMap<TypeId, Vec<TypeId>>,
+    visiting: &mut Vec<TypeId>,
+    order: &mut Vec<TypeId>,
+) {
+    if order.contains(t) || visiting.contains(t) {
+        return;
+    }
+    visiting.push(t.clone());
+    for inner in &held[t] {
+        let mut needed: Vec<TypeId> = named_types(inner).into_iter().map(TypeId::Named).collect();
+        find_generated(inner, &mut needed);
+        for n in needed {
+            if held.contains_key(&n) {
+                visit_type(&n, held, visiting, order);
+            }
+        }
+    }
+    visiting.pop();
+    order.push(t.clone());
+}
+// Declares a struct by its typedef, so pointers to it can come before it is defined. Structs
+// exported as a tag don't need it
+pub(super) fn declare_struct(names: &[Rc<str>], chunk: &ResolvedChunk) -> Option<String> {
+    match chunk {
+        ResolvedChunk::Struct { name, .. } if !names[name.id.0].contains(' ') => {
+            return Some(format!("typedef struct {0} {0};\n", names[name.id.0]));
+        }
+        _ => return None,
+    }
+}
+
+// The return type has to wrap the whole signature in case it is a function pointer, so this
+// is passed to declare as the name
+pub(super) fn signature(
+    names: &[Rc<str>],
+    name: &str,
+    params: &[ResolvedParam],
+    variadic: bool,
+) -> String {
+    if params.is_empty() && !variadic {
+        return format!("{}(void)", name);
+    }
+
+    let mut signature = format!("{}(", name);
+    for i in 0..params.len() {
+        signature += &declare(names, &params[i].ptype, &names[params[i].name.id.0]);
+        if i < params.len() - 1 {
+            signature += ", ";
+        }
+    }
+    if variadic {
+        signature += ", ...";
+    }
+    signature += ")";
+    return signature;
+}
+
+// typedefs for structs and enums, so they can be used by name like in notc. Types exported as
+// a tag, eg "struct tm", are defined under that tag instead
+pub(super) fn define_type(
+    names: &[Rc<str>],
+    source: &str,
+    chunk: &ResolvedChunk,
+    comments: bool,
+) -> Option<String> {
+    match chunk {
+        ResolvedChunk::Struct {
+            attributes,
+            name,
+            fields,
+            ..
+        } => {
+            let name = &names[name.id.0];
+            let attributes = c_attributes(source, attributes, AttributeTarget::Struct);
+            let mut out;
+            let end;
+            match name.split_once(' ') {
+                Some((_, tag)) => {
+                    out = format!("struct{} {} {{\n", attributes, tag);
+                    end = "};\n\n".to_string();
+                }
+                None => {
+                    out = format!("typedef struct{} {} {{\n", attributes, name);
+                    end = format!("}} {};\n\n", name);
+                }
+            }
+            for field in fields {
+                if comments {
+                    out += &c_docs(source, &field.docs, "    ");
+                }
+                out += &format!(
+                    "    {}{};\n",
+                    declare(names, &field.ftype, &names[field.name.id.0]),
+                    c_attributes(source, &field.attributes, AttributeTarget::Field)
+                );
+            }
+            out += &end;
+            return Some(out);
+        }
+        ResolvedChunk::Enum {
+            attributes,
+            name,
+            variants,
+            ..
+        } => {
+            let name = &names[name.id.0];
+            let attributes = c_attributes(source, attributes, AttributeTarget::Enum);
+            let mut out;
+            let end;
+            match name.split_once(' ') {
+                Some((_, tag)) => {
+                    out = format!("enum{} {} {{\n", attributes, tag);
+                    end = "};\n\n".to_string();
+                }
+                None => {
+                    out = format!("typedef enum{} {} {{\n", attributes, name);
+                    end = format!("}} {};\n\n", name);
+                }
+            }
+            for variant in variants {
+                out += &format!("    {},\n", names[variant.id.0]);
+            }
+            out += &end;
+            return Some(out);
+        }
+        // Distinct types, as the others have been replaced by what they name
+        ResolvedChunk::TypeAlias {
+            attributes,
+            name,
+            target,
+            ..
+        } => {
+            return Some(format!(
+                "typedef {}{};\n\n",
+                declare(names, target, &names[name.id.0]),
+                c_attributes(source, attributes, AttributeTarget::TypeAlias)
+            ));
+        }
+        _ => return None,
+    }
+}
@@ -0,0 +1,181 @@
+use std::{collections::HashMap, fs::File, io::Write, rc::Rc};
+
+use super::c::{
+    PREAMBLE, c_attributes, c_docs, c_names, chunk_docs, declare, declare_struct, define_generated,
+    define_type, generated_types, named_types, signature, type_order,
+};
+use crate::{
+    traits,
+    tree_checker::{ResolvedAstTree, TypeId},
+    types::{AttributeTarget, resolved_types::ResolvedChunk},
+};
+
+// Writes a header declaring the pub items of a program, so it can be called from C
+pub struct CHeaderGen {
+    target_file: File,
+    source_file: Rc<str>,
+    guard: String,
+    // Whether doc comments are written out as C comments
+    comments: bool,
+}
+
+impl CHeaderGen {
+    // The include guard is made from the header's name, eg math.h guards with MATH_H
+    pub fn new(input: Rc<str>, file: File, header_name: &str) -> Self {
+        let guard = header_name
+            .chars()
+            .map(|c| match c {
+                c if c.is_ascii_alphanumeric() => c.to_ascii_uppercase(),
+                _ => '_',
+            })
+            .collect();
+
+        return CHeaderGen {
+            source_file: input,
+            target_file: file,
+            guard,
+            comments: false,
+        };
+    }
+    // Carries /// doc comments into the header, for --keep-comments
+    pub fn keep_comments(mut self, keep: bool) -> Self {
+        self.comments = keep;
+        return self;
+    }
+}
+
+impl traits::CodeGen<ResolvedAstTree> for CHeaderGen {
+    fn generate(mut self, ast: &ResolvedAstTree) -> Result<(), std::io::Error> {
+        let names = c_names(ast, &self.source_file);
+
+        write!(
+            self.target_file,
+            "#ifndef {0}\n#define {0}\n\n{1}",
+            self.guard, PREAMBLE
+        )?;
+
+        // Types first, as the prototypes can use them, in the same order as the C. Private types
+        // the public items use are defined too, or C couldn't name them
+        let mut types = HashMap::new();
+        let mut reached = Vec::new();
+        for chunk in &ast.body {
+            match chunk {
+                ResolvedChunk::Struct { public, name, .. }
+                | ResolvedChunk::Enum { public, name, .. }
+                | ResolvedChunk::TypeAlias { public, name, .. } => {
+                    types.insert(name.id, chunk);
+                    if *public {
+                        reached.push(name.id);
+                    }
+                }
+                ResolvedChunk::Function {
+                    public: true,
+                    params,
+                    rtype,
+                    ..
+                } => {
+                    for t in params.iter().map(|p| &p.ptype).chain([rtype]) {
+                        reached.extend(named_types(&TypeId::from(t)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut public = HashMap::new();
+        while let Some(id) = reached.pop() {
+            let Some(chunk) = types.get(&id) else {
+                continue;
+            };
+            if public.insert(id, *chunk).is_some() {
+                continue;
+            }
+            match chunk {
+                ResolvedChunk::Struct { fields, .. } => {
+                    for f in fields {
+                        reached.extend(named_types(&TypeId::from(&f.ftype)));
+                    }
+                }
+                ResolvedChunk::TypeAlias { target, .. } => {
+                    reached.extend(named_types(&TypeId::from(target)));
+                }
+                _ => {}
+            }
+        }
+        let private: Vec<_> = types.keys().filter(|id| !public.contains_key(id)).collect();
+        let order = type_order(ast, &generated_types(ast));
+        let mut declarations = String::new();
+        for t in &order {
+            if let TypeId::Named(id) = t
+                && let Some(chunk) = public.get(id)
+                && let Some(declaration) = declare_struct(&names, chunk)
+            {
+                declarations += &declaration;
+            }
+        }
+        if !declarations.is_empty() {
+            writeln!(self.target_file, "{}", declarations)?;
+        }
+        for t in &order {
+            match t {
+                TypeId::Named(id) => {
+                    let Some(chunk) = public.get(id) else {
+                        continue;
+                    };
+                    if self.comments {
+                        write!(
+                            self.target_file,
+                            "{}",
+                            c_docs(&self.source_file, chunk_docs(chunk), "")
+                        )?;
+                    }
+                    write!(
+                        self.target_file,
+                        "{}",
+                        define_type(&names, &self.source_file, chunk, self.comments).unwrap()
+                    )?;
+                }
+                // Optionals and results of private types can't be in the prototypes either
+                t => {
+                    if !named_types(t).iter().any(|id| private.contains(&id)) {
+                        write!(self.target_file, "{}", define_generated(&names, t))?;
+                    }
+                }
+            }
+        }
+
+        for chunk in &ast.body {
+            if let ResolvedChunk::Function {
+                public: true,
+                attributes,
+                name,
+                params,
+                rtype,
+                ..
+            } = chunk
+            {
+                if self.comments {
+                    write!(
+                        self.target_file,
+                        "{}",
+                        c_docs(&self.source_file, chunk_docs(chunk), "")
+                    )?;
+                }
+                let signature = signature(&names, &names[name.id.0], params, false);
+                // To the C code including the header, these are extern functions
+                write!(
+                    self.target_file,
+                    "{}{};\n",
+                    declare(&names, rtype, &signature),
+                    c_attributes(
+                        &self.source_file,
+                        attributes,
+                        AttributeTarget::ExternFunction
+                    )
+                )?;
+            }
+        }
+
+        write!(self.target_file, "\n#endif\n")?;
+        Ok(())
+    }
+}
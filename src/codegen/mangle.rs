@@ -0,0 +1,141 @@
+use crate::types::resolved_types::SymbolKind;
+
+// C names
+//
+// Module items share one C namespace with the C library and whatever headers bring in, so each
+// is written after nc_, with its path joined by __, eg math::add is nc_math__add. Names that
+// wouldn't split back apart are spelled out with their lengths instead, eg a::_b is nc_1a2_b.
+// Names starting nc_ or notc_, which the generated C uses, are escaped the same way wherever
+// they are written
+const PREFIX: &str = "nc_";
+const GENERATED: &str = "notc_";
+
+const C_KEYWORDS: &[&str] = &[
+    "asm",
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    // C23, and the preamble's bool
+    "alignas",
+    "alignof",
+    "bool",
+    "constexpr",
+    "false",
+    "nullptr",
+    "static_assert",
+    "thread_local",
+    "true",
+    "typeof",
+    "typeof_unqual",
+];
+// Whether one segment of a path can be written as it is
+fn plain(segment: &str) -> bool {
+    return segment.starts_with(|c: char| c.is_ascii_alphabetic())
+        && segment
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !segment.ends_with('_')
+        && !segment.contains("__")
+        && !segment.starts_with(PREFIX)
+        && !segment.starts_with(GENERATED)
+        && !C_KEYWORDS.contains(&segment);
+}
+
+// The C name of a symbol. Fields are only kept from keywords, as each struct is a namespace of
+// its own, and locals can hide the C library without harm
+pub fn mangle(name: &str, kind: SymbolKind) -> String {
+    let segments: Vec<&str> = name.split("::").collect();
+    match kind {
+        SymbolKind::Field if !C_KEYWORDS.contains(&name) => return name.to_string(),
+        SymbolKind::Local | SymbolKind::Param if plain(name) => return name.to_string(),
+        SymbolKind::Field | SymbolKind::Local | SymbolKind::Param => {}
+        _ if segments.iter().all(|s| plain(s)) => {
+            return format!("{}{}", PREFIX, segments.join("__"));
+        }
+        _ => {}
+    }
+
+    let mut out = PREFIX.to_string();
+    for segment in segments {
+        out += &format!("{}{}", segment.len(), segment);
+    }
+    return out;
+}
+
+// The notc path behind a C name, or None if it isn't one mangle could have written
+pub fn demangle(name: &str) -> Option<String> {
+    if let Some(mut rest) = name.strip_prefix(PREFIX)
+        && rest.starts_with(|c: char| c.is_ascii_digit())
+    {
+        let mut segments = Vec::new();
+        while !rest.is_empty() {
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let len: usize = rest[..digits].parse().ok()?;
+            rest = &rest[digits..];
+            let segment = rest.get(..len).filter(|s| !s.is_empty())?;
+            segments.push(segment);
+            rest = &rest[len..];
+        }
+        return Some(segments.join("::"));
+    }
+
+    let segments: Vec<&str> = name.strip_prefix(PREFIX)?.split("__").collect();
+    if !segments.iter().all(|s| plain(s)) {
+        return None;
+    }
+    return Some(segments.join("::"));
+}
+
+// Demangles every name in some text, eg a backtrace, leaving the rest as it is
+pub fn demangle_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut word = String::new();
+    for c in text.chars().chain(std::iter::once('\n')) {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+            continue;
+        }
+        match demangle(&word) {
+            Some(path) => out += &path,
+            None => out += &word,
+        }
+        word.clear();
+        out.push(c);
+    }
+    // Without the newline added to end the last word
+    out.pop();
+    return out;
+}
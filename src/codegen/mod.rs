@@ -1,2 +1,4 @@
 pub mod c;
+pub mod header;
 pub mod llvm;
+pub mod mangle;
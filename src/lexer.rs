@@ -4,7 +4,7 @@ use crate::{
 };
 
 use crate::{SYMBOL_MATCHES, TEXT_MATCHES};
-use std::rc::Rc;
+use std::{ops::Range, rc::Rc};
 
 #[derive(Debug, Clone)]
 pub struct Lexer {
@@ -30,9 +30,23 @@ impl Lexer {
 #[allow(refining_impl_trait)]
 impl LexerTrait<Token> for Lexer {
     fn next_token(&mut self) -> Token {
+        // Whitespace and comments
         while self.index < self.text.len() {
             if self.peek_char().unwrap().is_whitespace() {
                 self.index += 1;
+            } else if self.text[self.index..].starts_with("//") {
+                self.index = self.text[self.index..]
+                    .find('\n')
+                    .map_or(self.text.len(), |e| self.index + e);
+            } else if self.text[self.index..].starts_with("/*") {
+                match block_comment_end(&self.text, self.index) {
+                    Some(end) => self.index = end,
+                    None => {
+                        let start = self.index;
+                        self.index = self.text.len();
+                        return Token::Unknown(start..self.index);
+                    }
+                }
             } else {
                 break;
             }
@@ -47,10 +61,18 @@ impl LexerTrait<Token> for Lexer {
 
         if c.is_alphabetic() || c == '_' {
             self.index += 1;
-            // Grab whole identifer
+            // Grab whole identifer, including any module path, eg math::add
             while self.index < self.text.len() {
                 let c = self.peek_char().unwrap();
-                if !c.is_alphabetic() && c != '_' && !c.is_digit(10) {
+                if c == ':'
+                    && self.text[self.index..].starts_with("::")
+                    && self.text[self.index + 2..]
+                        .starts_with(|c: char| c.is_alphabetic() || c == '_')
+                {
+                    self.index += 2;
+                    continue;
+                }
+                if !c.is_alphabetic() && c != '_' && !c.is_ascii_digit() {
                     break;
                 }
                 self.index += 1;
@@ -66,11 +88,11 @@ impl LexerTrait<Token> for Lexer {
             });
         }
 
-        if c.is_digit(10) {
+        if c.is_ascii_digit() {
             self.index += 1;
             while self.index < self.text.len() {
                 let c = self.peek_char().unwrap();
-                if !c.is_digit(10) && c != '.' {
+                if !c.is_ascii_digit() && c != '.' {
                     break;
                 }
                 self.index += 1;
@@ -85,7 +107,25 @@ impl LexerTrait<Token> for Lexer {
             }
         }
 
-        // TODO: String and character literals
+        if c == '"' {
+            self.index += 1;
+            while self.index < self.text.len() {
+                match self.peek_char().unwrap() {
+                    '"' => {
+                        self.index += 1;
+                        return Token::StringLiteral(start..self.index);
+                    }
+                    // Skip over whatever is escaped, it is passed through to C as is
+                    '\\' => self.index += 1,
+                    _ => {}
+                }
+                self.index += self.peek_char().map_or(0, |c| c.len_utf8());
+            }
+            // Unterminated
+            return Token::Unknown(start..self.index);
+        }
+
+        // TODO: Character literals
         return Token::Unknown(start..self.index);
     }
 
@@ -96,7 +136,7 @@ impl LexerTrait<Token> for Lexer {
         return (token, i);
     }
 
-    fn go_to(&mut self, i: usize) -> () {
+    fn go_to(&mut self, i: usize) {
         self.index = i;
     }
 }
@@ -118,3 +158,47 @@ impl DebugLexerTrait<Token> for Lexer {
         return self.index;
     }
 }
+
+// The end of the block comment starting at `start`, if it is closed. Block comments nest, so
+// /* a /* b */ c */ is one comment
+fn block_comment_end(text: &str, start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = start;
+    while i < text.len() {
+        if text[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if text[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += text[i..].chars().next().unwrap().len_utf8();
+        }
+    }
+    return None;
+}
+
+// The /// doc comments in the whitespace and comments of `gap`, as the text after each ///
+pub fn doc_comments(text: &str, gap: Range<usize>) -> Vec<Range<usize>> {
+    let mut docs = Vec::new();
+    let mut i = gap.start;
+    while i < gap.end {
+        let rest = &text[i..gap.end];
+        let line_end = rest.find('\n').map_or(gap.end, |e| i + e);
+        if rest.starts_with("///") && !rest.starts_with("////") {
+            let line = text[i + 3..line_end].trim_end_matches('\r');
+            docs.push(i + 3..i + 3 + line.len());
+            i = line_end;
+        } else if rest.starts_with("//") {
+            i = line_end;
+        } else if rest.starts_with("/*") {
+            i = block_comment_end(text, i).unwrap_or(gap.end);
+        } else {
+            i += rest.chars().next().unwrap().len_utf8();
+        }
+    }
+    return docs;
+}
@@ -1,3 +1,4 @@
+pub mod cfront;
 pub mod codegen;
 pub mod lexer;
 pub mod parsing;
@@ -10,7 +11,15 @@ pub mod traits;
 //
 use types::{Token, spanned_types::SpannedOperator};
 
-pub const TEXT_MATCHES: &[(&'static str, Token)] = &[
+pub const TEXT_MATCHES: &[(&str, Token)] = &[
+    ("import", Token::Import(0..0)),
+    ("import_c", Token::ImportC(0..0)),
+    ("pub", Token::Pub(0..0)),
+    ("extern", Token::Extern(0..0)),
+    ("struct", Token::Struct(0..0)),
+    ("enum", Token::Enum(0..0)),
+    ("type", Token::Type(0..0)),
+    ("distinct", Token::Distinct(0..0)),
     ("let", Token::Let(0..0)),
     ("if", Token::If(0..0)),
     ("fn", Token::Fn(0..0)),
@@ -18,8 +27,11 @@ pub const TEXT_MATCHES: &[(&'static str, Token)] = &[
     ("static", Token::Static(0..0)),
     ("else", Token::Else(0..0)),
     ("return", Token::Return(0..0)),
+    ("defer", Token::Defer(0..0)),
+    ("none", Token::NoneLiteral(0..0)),
+    ("orelse", Token::Orelse(0..0)),
 ];
-pub const SYMBOL_MATCHES: &[(&'static str, Token)] = &[
+pub const SYMBOL_MATCHES: &[(&str, Token)] = &[
     //Symbols
     (",", Token::Comma(0..0)),
     (":", Token::Colon(0..0)),
@@ -29,88 +41,30 @@ pub const SYMBOL_MATCHES: &[(&'static str, Token)] = &[
     ("{", Token::LBrace(0..0)),
     ("}", Token::RBrace(0..0)),
     ("->", Token::LArrow(0..0)),
+    ("|", Token::Pipe(0..0)),
+    ("...", Token::Ellipsis(0..0)),
+    (".", Token::Dot(0..0)),
+    ("#", Token::Hash(0..0)),
+    ("[", Token::LBracket(0..0)),
+    ("]", Token::RBracket(0..0)),
+    ("?", Token::Question(0..0)),
     //
     // Operators
     //
     // Boolean
-    (
-        "==",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 1,
-        }),
-    ),
-    (
-        "!",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 5,
-        }),
-    ),
+    ("==", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("!", Token::Operator(SpannedOperator { span: 0..0 })),
     // Assignments
-    (
-        "=",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 0,
-        }),
-    ),
-    (
-        "+=",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 0,
-        }),
-    ),
-    (
-        "-=",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 0,
-        }),
-    ),
-    (
-        "*=",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 0,
-        }),
-    ),
-    (
-        "/=",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 0,
-        }),
-    ),
+    ("=", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("+=", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("-=", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("*=", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("/=", Token::Operator(SpannedOperator { span: 0..0 })),
     // Binary Operations
-    (
-        "+",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 2,
-        }),
-    ),
-    (
-        "-",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 2,
-        }),
-    ),
-    (
-        "*",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 3,
-        }),
-    ),
-    (
-        "/",
-        Token::Operator(SpannedOperator {
-            span: 0..0,
-            presedence: 3,
-        }),
-    ),
+    ("+", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("-", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("*", Token::Operator(SpannedOperator { span: 0..0 })),
+    ("/", Token::Operator(SpannedOperator { span: 0..0 })),
 ];
-pub const PRIMATIVE_TYPES: &[&str] = &["void", "bool", "usize"];
+// str is another name for *u8, the strings C takes
+pub const PRIMATIVE_TYPES: &[&str] = &["void", "bool", "usize", "i32", "u8", "str"];
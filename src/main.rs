@@ -1,25 +1,111 @@
+use notc::cfront::translate as from_c;
 use notc::codegen::c::CCodeGen;
-use notc::lexer::Lexer;
-use notc::parsing::SpannedAstTree;
+use notc::codegen::header::CHeaderGen;
+use notc::codegen::mangle;
+use notc::parsing::ModuleTree;
 use notc::traits::{CodeGen, TreeChecker};
 use notc::tree_checker::{NameResolver, TypeChecker};
-use std::rc::Rc;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// The first line of every file notc writes. Files without it are the user's, and are never
+// overwritten, eg the C a .nc file was translated from
+const GENERATED: &str = "// Generated by notc\n";
+
+fn create_generated(path: &Path) -> File {
+    if let Ok(existing) = std::fs::read(path)
+        && !existing.starts_with(GENERATED.as_bytes())
+    {
+        eprintln!(
+            "{} wasn't written by notc, so it is left as it is. Write to another file with --out=<file>",
+            path.display()
+        );
+        std::process::exit(1);
+    }
+    let mut file = File::create(path).expect("Error writing to file");
+    file.write_all(GENERATED.as_bytes())
+        .expect("Error writing to file");
+    return file;
+}
 
 fn main() {
-    let source_file: Rc<str> = std::fs::read_to_string("input.nc").unwrap().into();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (flags, files): (Vec<&String>, Vec<&String>) =
+        args.iter().partition(|a| a.starts_with("--"));
+    // Also write a header declaring the pub items, for calling them from C
+    let header = flags.iter().any(|f| *f == "--header");
+    // Carry /// doc comments into the C as comments
+    let keep_comments = flags.iter().any(|f| *f == "--keep-comments");
+    // Where the C is written, instead of next to the root file. A header goes next to it
+    let out = flags
+        .iter()
+        .find_map(|f| f.strip_prefix("--out="))
+        .map(PathBuf::from);
 
-    let mut lexer = Lexer::from_rc_str(source_file.clone());
-    let mut ast = SpannedAstTree::from_rc_str(source_file.clone());
+    // notc from-c file.c, writes file.nc, or the --out file
+    if files.first().is_some_and(|f| *f == "from-c") {
+        let file = PathBuf::from(files.get(1).expect("Please give a C file to translate"));
+        let c = std::fs::read_to_string(&file).expect("Error reading file");
+        let translation = from_c::translate(&c);
+        let mut nc = create_generated(&out.unwrap_or(file.with_extension("nc")));
+        nc.write_all(translation.source.as_bytes())
+            .expect("Error writing to file");
+        if translation.todos > 0 {
+            eprintln!("{} TODOs left to translate by hand", translation.todos);
+        }
+        from_c::check(&translation.source).expect("Translation doesn't parse");
+        return;
+    }
+    // notc demangle [names], or demangles every name in stdin, eg a backtrace
+    if files.first().is_some_and(|f| *f == "demangle") {
+        if files.len() > 1 {
+            for name in &files[1..] {
+                println!("{}", mangle::demangle(name).unwrap_or(name.to_string()));
+            }
+            return;
+        }
+        let text = std::io::read_to_string(std::io::stdin()).expect("Error reading stdin");
+        print!("{}", mangle::demangle_text(&text));
+        return;
+    }
+    let root = PathBuf::from(files.first().map_or("input.nc", |f| f.as_str()));
 
-    let r = ast.parse_all(&mut lexer).expect("Parsing error");
+    // Loads the root file and every module it imports
+    let tree = ModuleTree::load(&root).expect("Parsing error");
+    for warning in &tree.warnings {
+        eprintln!("warning: {}", warning);
+    }
+    let source_file = tree.source.clone();
 
     let mut nr = NameResolver::from_rc_str(source_file.clone()).pre_intern(notc::PRIMATIVE_TYPES);
-    let ast = dbg!(nr.resolve(ast));
+    let resolved = nr.resolve_modules(tree);
+    if resolved.is_err() {
+        NameResolver::debug_resolve_result(&resolved, source_file.clone());
+        std::process::exit(1);
+    }
+    let ast = dbg!(resolved.unwrap());
 
-    TypeChecker::new().check(&ast).expect("Type error");
+    let mut checker = TypeChecker::new();
+    let checked = checker.check(&ast);
+    if checked.is_err() {
+        TypeChecker::debug_check_result(&checked, source_file.clone());
+        std::process::exit(1);
+    }
 
-    let out = std::fs::File::create("input.c").unwrap();
-    CCodeGen::new(source_file.clone(), out)
+    let c = out.unwrap_or(root.with_extension("c"));
+    CCodeGen::new(source_file.clone(), create_generated(&c))
+        .with_notes(checker.notes)
+        .keep_comments(keep_comments)
         .generate(&ast)
         .expect("Error writing to file");
+
+    if header {
+        let path = c.with_extension("h");
+        let name = path.file_name().unwrap().to_string_lossy();
+        CHeaderGen::new(source_file.clone(), create_generated(&path), &name)
+            .keep_comments(keep_comments)
+            .generate(&ast)
+            .expect("Error writing to file");
+    }
 }
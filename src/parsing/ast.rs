@@ -8,6 +8,8 @@ pub use std::{ops::Range, rc::Rc};
 pub struct SpannedAstTree {
     pub(super) input: Rc<str>,
     pub(crate) body: Vec<SpannedChunk>,
+    // Attributes that are unknown or in the wrong place, with where they are
+    pub(crate) warnings: Vec<(Range<usize>, String)>,
 }
 
 impl SpannedAstTree {
@@ -15,12 +17,13 @@ impl SpannedAstTree {
         return SpannedAstTree {
             input,
             body: Vec::new(),
+            warnings: Vec::new(),
         };
     }
 
-    pub fn debug_ast_result(&self, r: &Result<(), ParseError>) -> () {
+    pub fn debug_ast_result(&self, r: &Result<(), ParseError>) {
         match r {
-            Ok(()) => _ = {},
+            Ok(()) => {}
             Err(e) => match e {
                 ParseError::UnknownToken(s) => println!("{}", &self.input[s.clone()]),
                 ParseError::BadSyntax(b, msg) => {
@@ -44,7 +47,7 @@ impl SpannedAstTree {
         }
     }
 
-    pub(super) fn resolve_span<'a>(&'a self, span: Range<usize>) -> &'a str {
+    pub(super) fn resolve_span(&self, span: Range<usize>) -> &str {
         return &self.input[span];
     }
 }
@@ -1,5 +1,7 @@
 mod ast;
+mod modules;
 mod parser;
 
 pub use ast::SpannedAstTree;
+pub use modules::{LoadError, Module, ModuleTree};
 pub use parser::ParseError;
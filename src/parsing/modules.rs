@@ -0,0 +1,166 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use super::{ParseError, SpannedAstTree};
+use crate::{
+    cfront::header::import_header,
+    lexer::Lexer,
+    traits::LexerTrait,
+    types::{Span, spanned_types::SpannedChunk},
+};
+
+// Where import_c looks for headers, after the importing file's own directory
+const INCLUDE_DIRS: &[&str] = &["/usr/local/include", "/usr/include"];
+
+// A whole program, made of a root file and every module it imports. All of the files are loaded
+// into one source string, so spans stay unique across modules
+#[derive(Debug)]
+pub struct ModuleTree {
+    pub source: Rc<str>,
+    pub modules: Vec<Module>,
+    // Declarations left out of imported C headers, and attributes that are ignored
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Module {
+    // Path of the module, eg "math::vec". The root module has an empty path
+    pub path: Rc<str>,
+    pub file: PathBuf,
+    pub span: Span,
+    pub ast: SpannedAstTree,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, std::io::Error),
+    Parse(PathBuf, ParseError),
+}
+
+impl ModuleTree {
+    // Module paths map onto files relative to the root file, so math::vec is in math/vec.nc
+    pub fn load(root: &Path) -> Result<ModuleTree, LoadError> {
+        let dir = root.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        let mut source = String::new();
+        let mut modules: Vec<Module> = Vec::new();
+        let mut warnings = Vec::new();
+        let mut seen = HashSet::new();
+        let mut queue = vec![(Rc::<str>::from(""), root.to_path_buf())];
+
+        let mut i = 0;
+        while i < queue.len() {
+            let (path, file) = queue[i].clone();
+            i += 1;
+
+            let text =
+                std::fs::read_to_string(&file).map_err(|e| LoadError::Io(file.clone(), e))?;
+            let start = source.len();
+            source += &text;
+            source.push('\n');
+
+            // Parse against everything loaded so far. Later files are only ever appended, so
+            // these spans stay valid in the final source
+            let snapshot: Rc<str> = source.as_str().into();
+            let mut lexer = Lexer::from_rc_str(snapshot.clone());
+            lexer.go_to(start);
+            let mut ast = SpannedAstTree::from_rc_str(snapshot);
+            ast.parse_all(&mut lexer)
+                .map_err(|e| LoadError::Parse(file.clone(), e))?;
+
+            // A chunk's attributes are checked after its body, so sort them back into order
+            ast.warnings.sort_by_key(|(span, _)| span.start);
+            for (span, warning) in &ast.warnings {
+                let line = source[start..span.start].matches('\n').count() + 1;
+                warnings.push(format!("{}:{}: {}", file.display(), line, warning));
+            }
+            let span = start..source.len();
+
+            // C headers are read into extern declarations, appended to the source like any
+            // other file and parsed into this module
+            let headers: Vec<String> = ast
+                .body
+                .iter()
+                .filter_map(|chunk| match chunk {
+                    SpannedChunk::ImportC { header, .. } => {
+                        Some(source[header.start + 1..header.end - 1].to_string())
+                    }
+                    _ => None,
+                })
+                .collect();
+            for header in headers {
+                let local = file.parent().unwrap_or(Path::new("")).join(&header);
+                let found = std::iter::once(local)
+                    .chain(INCLUDE_DIRS.iter().map(|d| Path::new(d).join(&header)))
+                    .find(|f| f.is_file())
+                    .unwrap_or(PathBuf::from(&header));
+                let text =
+                    std::fs::read_to_string(&found).map_err(|e| LoadError::Io(found.clone(), e))?;
+
+                let import = import_header(&text, &header);
+                for warning in import.warnings {
+                    warnings.push(format!("{}: {}", header, warning));
+                }
+
+                let start = source.len();
+                source += &import.source;
+                let snapshot: Rc<str> = source.as_str().into();
+                let mut lexer = Lexer::from_rc_str(snapshot.clone());
+                lexer.go_to(start);
+                let mut imported = SpannedAstTree::from_rc_str(snapshot);
+                imported
+                    .parse_all(&mut lexer)
+                    .map_err(|e| LoadError::Parse(found.clone(), e))?;
+                ast.body.append(&mut imported.body);
+            }
+
+            for chunk in &ast.body {
+                if let SpannedChunk::Import { path, .. } = chunk {
+                    let path = &source[path.span.clone()];
+                    if seen.insert(path.to_string()) {
+                        let mut file = dir.clone();
+                        file.extend(path.split("::"));
+                        file.set_extension("nc");
+                        queue.push((path.into(), file));
+                    }
+                }
+            }
+
+            modules.push(Module {
+                path,
+                file,
+                span,
+                ast,
+            });
+        }
+
+        return Ok(ModuleTree {
+            source: source.into(),
+            modules,
+            warnings,
+        });
+    }
+
+    pub fn from_ast(source: Rc<str>, ast: SpannedAstTree) -> ModuleTree {
+        return ModuleTree {
+            modules: vec![Module {
+                path: "".into(),
+                file: PathBuf::new(),
+                span: 0..source.len(),
+                ast,
+            }],
+            source,
+            warnings: Vec::new(),
+        };
+    }
+
+    // Finds the file and line a position in the source came from
+    pub fn locate(&self, i: usize) -> Option<(&Path, usize)> {
+        let module = self.modules.iter().find(|m| m.span.contains(&i))?;
+        let line = self.source[module.span.start..i].matches('\n').count() + 1;
+        return Some((&module.file, line));
+    }
+}
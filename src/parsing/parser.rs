@@ -2,11 +2,13 @@ pub use super::SpannedAstTree;
 use std::{fmt::Debug, ops::Range};
 
 use crate::{
+    lexer::doc_comments,
     traits,
     types::{
-        Token,
+        Attribute, AttributeTarget, AttributeValue, Token, attribute_targets,
         spanned_types::{
-            SpannedChunk, SpannedExpression, SpannedIdentifier, SpannedParam, SpannedStatement,
+            BinaryOperation, SpannedChunk, SpannedExpression, SpannedField, SpannedIdentifier,
+            SpannedParam, SpannedStatement, SpannedType,
         },
     },
 };
@@ -27,22 +29,50 @@ macro_rules! parse_error {
 }
 
 fn is_unary_ooperator(t: &str) -> bool {
-    return match t {
-        "!" => true,
-        "-" => true,
-        _ => false,
-    };
+    return matches!(t, "!" | "-" | "*");
+}
+
+// Which way a chain of binary operators of the same power groups. Comparisons don't chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Associativity {
+    Left,
+    Right,
+    None,
 }
+// Binary operators, by how tightly they bind. a - b - c is (a - b) - c, and a orelse b orelse c
+// is a orelse (b orelse c), where b is another optional. orelse is a node of its own
+const BINARY_OPERATORS: &[(&str, Option<BinaryOperation>, usize, Associativity)] = &[
+    ("==", Some(BinaryOperation::Equal), 1, Associativity::None),
+    ("orelse", None, 2, Associativity::Right),
+    ("+", Some(BinaryOperation::Add), 3, Associativity::Left),
+    ("-", Some(BinaryOperation::Subtract), 3, Associativity::Left),
+    ("*", Some(BinaryOperation::Multiply), 4, Associativity::Left),
+    ("/", Some(BinaryOperation::Divide), 4, Associativity::Left),
+];
+// Assignments bind looser than everything, and are statements rather than operators. Compound
+// ones do their operation on the place first
+const ASSIGNMENT_OPERATORS: &[(&str, Option<BinaryOperation>)] = &[
+    ("=", None),
+    ("+=", Some(BinaryOperation::Add)),
+    ("-=", Some(BinaryOperation::Subtract)),
+    ("*=", Some(BinaryOperation::Multiply)),
+    ("/=", Some(BinaryOperation::Divide)),
+];
 
 impl SpannedAstTree {
     pub fn parse_all(
         &mut self,
-        lexer: &mut (impl traits::LexerTrait<Token> + traits::DebugLexerTrait<Token> + Debug),
+        lexer: &mut (impl traits::DebugLexerTrait<Token> + Debug),
     ) -> Result<(), ParseError> {
         loop {
+            // Doc comments are between the last chunk and the start of this one
+            let gap = lexer.get_index()..lexer.peek_next().0.get_span().start;
             match self.parse_chunk(lexer)? {
                 SpannedChunk::EOF => break,
-                chunk => {
+                mut chunk => {
+                    if let Some(docs) = chunk.docs_mut() {
+                        *docs = doc_comments(&self.input, gap);
+                    }
                     self.body.push(chunk);
                 }
             }
@@ -55,9 +85,462 @@ impl SpannedAstTree {
     //
     pub fn parse_chunk(
         &mut self,
-        lexer: &mut (impl traits::LexerTrait<Token> + traits::DebugLexerTrait<Token>),
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
     ) -> Result<SpannedChunk, ParseError> {
         match lexer.next_token() {
+            // Imports, eg import path::to::module;
+            Token::Import(s) => {
+                let path;
+                match lexer.next_token() {
+                    Token::Identifier(i) => path = i,
+                    b => {
+                        return parse_error!(b, "Please give the path of the module to import");
+                    }
+                }
+
+                match lexer.next_token() {
+                    Token::EOL(e) => {
+                        return Ok(SpannedChunk::Import {
+                            path,
+                            span: s.start..e.end,
+                        });
+                    }
+                    b => return parse_error!(b, "Please end imports with ';'"),
+                }
+            }
+            // C headers, eg import_c "stdio.h";
+            Token::ImportC(s) => {
+                let header;
+                match lexer.next_token() {
+                    Token::StringLiteral(h) => header = h,
+                    b => return parse_error!(b, "Please give the header to import as a string"),
+                }
+
+                match lexer.next_token() {
+                    Token::EOL(e) => {
+                        return Ok(SpannedChunk::ImportC {
+                            header,
+                            span: s.start..e.end,
+                        });
+                    }
+                    b => return parse_error!(b, "Please end imports with ';'"),
+                }
+            }
+            // Items are private to their module unless marked pub
+            Token::Pub(s) => {
+                let mut chunk = self.parse_chunk(lexer)?;
+                match &mut chunk {
+                    SpannedChunk::Function { public, .. }
+                    | SpannedChunk::ExternFunction { public, .. }
+                    | SpannedChunk::ExternStatic { public, .. }
+                    | SpannedChunk::Struct { public, .. }
+                    | SpannedChunk::Enum { public, .. }
+                    | SpannedChunk::TypeAlias { public, .. } => *public = true,
+                    _ => {
+                        return parse_error!(
+                            Token::Pub(s),
+                            "Only functions, types and externs can be made public"
+                        );
+                    }
+                }
+                return Ok(chunk);
+            }
+            // Attributes, eg #[inline] or #[align(16)]. #[include("header.h")] on externs uses
+            // the header's declaration, #[export_name("symbol")] picks the C name and #[must_use]
+            // is checked by notc itself
+            Token::Hash(s) => {
+                let attribute = self.parse_attribute(s.clone(), lexer)?;
+                let value = attribute.value.clone();
+
+                let mut chunk = self.parse_chunk(lexer)?;
+                match (self.resolve_span(attribute.name.clone()), &mut chunk) {
+                    ("include", SpannedChunk::ExternFunction { include, .. })
+                    | ("include", SpannedChunk::ExternStatic { include, .. })
+                    | (
+                        "include",
+                        SpannedChunk::Struct {
+                            external: true,
+                            include,
+                            ..
+                        },
+                    )
+                    | (
+                        "include",
+                        SpannedChunk::Enum {
+                            external: true,
+                            include,
+                            ..
+                        },
+                    ) => *include = Some(self.string_value(s, value)?),
+                    ("export_name", SpannedChunk::Function { export, .. })
+                    | ("export_name", SpannedChunk::Struct { export, .. })
+                    | ("export_name", SpannedChunk::Enum { export, .. }) => {
+                        *export = Some(self.string_value(s, value)?)
+                    }
+                    ("must_use", SpannedChunk::Function { must_use, .. })
+                    | ("must_use", SpannedChunk::ExternFunction { must_use, .. })
+                        if value.is_none() =>
+                    {
+                        *must_use = true
+                    }
+                    ("must_use", _) => {
+                        return parse_error!(
+                            Token::Hash(s),
+                            "Only functions can be marked #[must_use], which takes no value"
+                        );
+                    }
+                    ("include", _) => {
+                        return parse_error!(
+                            Token::Hash(s),
+                            "Only extern declarations can be included from a header"
+                        );
+                    }
+                    ("export_name", _) => {
+                        return parse_error!(
+                            Token::Hash(s),
+                            "Only functions and types can be given an export name"
+                        );
+                    }
+                    (_, chunk) => {
+                        let target;
+                        let attributes;
+                        match chunk {
+                            SpannedChunk::Function { attributes: a, .. } => {
+                                target = AttributeTarget::Function;
+                                attributes = a;
+                            }
+                            SpannedChunk::ExternFunction { attributes: a, .. } => {
+                                target = AttributeTarget::ExternFunction;
+                                attributes = a;
+                            }
+                            SpannedChunk::ExternStatic { attributes: a, .. } => {
+                                target = AttributeTarget::ExternStatic;
+                                attributes = a;
+                            }
+                            SpannedChunk::Struct { attributes: a, .. } => {
+                                target = AttributeTarget::Struct;
+                                attributes = a;
+                            }
+                            SpannedChunk::Enum { attributes: a, .. } => {
+                                target = AttributeTarget::Enum;
+                                attributes = a;
+                            }
+                            SpannedChunk::TypeAlias { attributes: a, .. } => {
+                                target = AttributeTarget::TypeAlias;
+                                attributes = a;
+                            }
+                            _ => {
+                                return parse_error!(
+                                    Token::Hash(s),
+                                    "Only functions, types and externs can have attributes"
+                                );
+                            }
+                        }
+                        self.check_attribute(&attribute, target)?;
+                        // Later attributes were parsed first
+                        attributes.insert(0, attribute);
+                    }
+                }
+                return Ok(chunk);
+            }
+            // Type definitions, eg struct Point { x: usize, y: usize }
+            Token::Struct(s) => {
+                let name;
+                match lexer.next_token() {
+                    Token::Identifier(i) => name = i,
+                    b => return parse_error!(b, "Structs need an identifier"),
+                }
+                match lexer.next_token() {
+                    Token::LBrace(_) => {}
+                    b => return parse_error!(b, "Please list the struct's fields in braces"),
+                }
+
+                let mut fields = Vec::new();
+                loop {
+                    let gap = lexer.get_index()..lexer.peek_next().0.get_span().start;
+                    let docs = doc_comments(&self.input, gap);
+                    // Field attributes, eg #[align(8)] count: usize
+                    let mut attributes = Vec::new();
+                    while let (Token::Hash(h), i) = lexer.peek_next() {
+                        lexer.go_to(i);
+                        let attribute = self.parse_attribute(h, lexer)?;
+                        self.check_attribute(&attribute, AttributeTarget::Field)?;
+                        attributes.push(attribute);
+                    }
+
+                    match lexer.next_token() {
+                        Token::RBrace(e) if attributes.is_empty() => {
+                            return Ok(SpannedChunk::Struct {
+                                public: false,
+                                attributes: Vec::new(),
+                                docs: Vec::new(),
+                                external: false,
+                                include: None,
+                                export: None,
+                                name,
+                                fields,
+                                span: s.start..e.end,
+                            });
+                        }
+                        Token::Identifier(field) => {
+                            match lexer.next_token() {
+                                Token::Colon(_) => {}
+                                b => {
+                                    return parse_error!(
+                                        b,
+                                        "Struct fields require types, eg a: Type"
+                                    );
+                                }
+                            }
+                            let ftype = self.parse_type(lexer)?;
+                            fields.push(SpannedField {
+                                name: field,
+                                ftype,
+                                attributes,
+                                docs,
+                            });
+                        }
+                        b => return parse_error!(b, "Struct fields are made of characters or _"),
+                    }
+
+                    match lexer.next_token() {
+                        Token::Comma(_) => {}
+                        Token::RBrace(e) => {
+                            return Ok(SpannedChunk::Struct {
+                                public: false,
+                                attributes: Vec::new(),
+                                docs: Vec::new(),
+                                external: false,
+                                include: None,
+                                export: None,
+                                name,
+                                fields,
+                                span: s.start..e.end,
+                            });
+                        }
+                        b => {
+                            return parse_error!(
+                                b,
+                                "Struct fields are seperated by commas and ended by a closing brace"
+                            );
+                        }
+                    }
+                }
+            }
+            // Aliases, eg type Handle = usize; or distinct type Meters = usize;
+            Token::Distinct(s) => match lexer.next_token() {
+                Token::Type(_) => {
+                    let mut chunk = self.parse_type_alias(s, lexer)?;
+                    if let SpannedChunk::TypeAlias { distinct, .. } = &mut chunk {
+                        *distinct = true;
+                    }
+                    return Ok(chunk);
+                }
+                b => return parse_error!(b, "Only types can be distinct, eg distinct type T = U;"),
+            },
+            Token::Type(s) => return self.parse_type_alias(s, lexer),
+            // eg enum Colour { Red, Green }, with variants used as Colour::Red
+            Token::Enum(s) => {
+                let name;
+                match lexer.next_token() {
+                    Token::Identifier(i) => name = i,
+                    b => return parse_error!(b, "Enums need an identifier"),
+                }
+                match lexer.next_token() {
+                    Token::LBrace(_) => {}
+                    b => return parse_error!(b, "Please list the enum's variants in braces"),
+                }
+
+                let mut variants = Vec::new();
+                loop {
+                    match lexer.next_token() {
+                        Token::RBrace(e) => {
+                            return Ok(SpannedChunk::Enum {
+                                public: false,
+                                attributes: Vec::new(),
+                                docs: Vec::new(),
+                                external: false,
+                                include: None,
+                                export: None,
+                                name,
+                                variants,
+                                span: s.start..e.end,
+                            });
+                        }
+                        Token::Identifier(variant) => variants.push(variant),
+                        b => return parse_error!(b, "Enum variants are made of characters or _"),
+                    }
+
+                    match lexer.next_token() {
+                        Token::Comma(_) => {}
+                        Token::RBrace(e) => {
+                            return Ok(SpannedChunk::Enum {
+                                public: false,
+                                attributes: Vec::new(),
+                                docs: Vec::new(),
+                                external: false,
+                                include: None,
+                                export: None,
+                                name,
+                                variants,
+                                span: s.start..e.end,
+                            });
+                        }
+                        b => {
+                            return parse_error!(
+                                b,
+                                "Enum variants are seperated by commas and ended by a closing brace"
+                            );
+                        }
+                    }
+                }
+            }
+            // C declarations, eg extern fn puts(s: *u8) i32; or extern static errno: i32;
+            Token::Extern(s) => {
+                let mut next = lexer.next_token();
+                if let Token::StringLiteral(abi) = &next {
+                    if self.resolve_span(abi.clone()) != "\"C\"" {
+                        return parse_error!(next, "Only extern \"C\" is supported");
+                    }
+                    next = lexer.next_token();
+                }
+
+                match next {
+                    Token::Fn(_) => {
+                        let name;
+                        match lexer.next_token() {
+                            Token::Identifier(i) => name = i,
+                            b => return parse_error!(b, "Function Needs Identifier"),
+                        }
+                        match lexer.next_token() {
+                            Token::LParen(_) => {}
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Function Definition requires parentheses, expected LParen"
+                                );
+                            }
+                        }
+
+                        // Parameters, where C variadics end the list with ...
+                        let mut params = Vec::new();
+                        let mut variadic = false;
+                        match lexer.peek_next() {
+                            (Token::RParen(_), i) => lexer.go_to(i),
+                            _ => loop {
+                                match lexer.next_token() {
+                                    Token::Identifier(name) => {
+                                        match lexer.next_token() {
+                                            Token::Colon(_) => {}
+                                            b => {
+                                                return parse_error!(
+                                                    b,
+                                                    "Function parameters require types, eg(a: Type)"
+                                                );
+                                            }
+                                        }
+                                        let ptype = self.parse_type(lexer)?;
+                                        params.push(SpannedParam { name, ptype });
+                                    }
+                                    Token::Ellipsis(_) => variadic = true,
+                                    b => {
+                                        return parse_error!(
+                                            b,
+                                            "Extern parameters are either name: Type or ..."
+                                        );
+                                    }
+                                }
+
+                                match lexer.next_token() {
+                                    Token::RParen(_) => break,
+                                    Token::Comma(_) if !variadic => {}
+                                    b => {
+                                        return parse_error!(
+                                            b,
+                                            "Function parameters are seperated by commas, with ... last"
+                                        );
+                                    }
+                                }
+                            },
+                        }
+
+                        let rtype = self.parse_type(lexer)?;
+                        match lexer.next_token() {
+                            Token::EOL(e) => {
+                                return Ok(SpannedChunk::ExternFunction {
+                                    public: false,
+                                    attributes: Vec::new(),
+                                    docs: Vec::new(),
+                                    must_use: false,
+                                    name,
+                                    params,
+                                    variadic,
+                                    rtype,
+                                    include: None,
+                                    span: s.start..e.end,
+                                });
+                            }
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Extern functions have no body, end them with ';'"
+                                );
+                            }
+                        }
+                    }
+                    Token::Static(_) => {
+                        let name;
+                        match lexer.next_token() {
+                            Token::Identifier(i) => name = i,
+                            b => return parse_error!(b, "Extern statics need an identifier"),
+                        }
+                        match lexer.next_token() {
+                            Token::Colon(_) => {}
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Please provide type for definition 'v: type'"
+                                );
+                            }
+                        }
+                        let vtype = self.parse_type(lexer)?;
+                        match lexer.next_token() {
+                            Token::EOL(e) => {
+                                return Ok(SpannedChunk::ExternStatic {
+                                    public: false,
+                                    attributes: Vec::new(),
+                                    docs: Vec::new(),
+                                    name,
+                                    vtype,
+                                    include: None,
+                                    span: s.start..e.end,
+                                });
+                            }
+                            b => return parse_error!(b, "Please end statements with ';'"),
+                        }
+                    }
+                    // C types, eg extern struct tm { tm_sec: i32 }
+                    Token::Struct(t) | Token::Enum(t) => {
+                        lexer.go_to(t.start);
+                        let mut chunk = self.parse_chunk(lexer)?;
+                        match &mut chunk {
+                            SpannedChunk::Struct { external, span, .. }
+                            | SpannedChunk::Enum { external, span, .. } => {
+                                *external = true;
+                                span.start = s.start;
+                            }
+                            _ => unreachable!(),
+                        }
+                        return Ok(chunk);
+                    }
+                    b => {
+                        return parse_error!(
+                            b,
+                            "Extern declarations are either fn, static, struct or enum"
+                        );
+                    }
+                }
+            }
             // TODO:
             Token::Const(_s) => todo!(),
             // TODO:
@@ -98,16 +581,7 @@ impl SpannedAstTree {
                             }
                         }
 
-                        let ptype;
-                        match lexer.next_token() {
-                            Token::Identifier(i) => ptype = i,
-                            b => {
-                                return parse_error!(
-                                    b,
-                                    "Function parameters require types, eg(a: Type)"
-                                );
-                            }
-                        }
+                        let ptype = self.parse_type(lexer)?;
 
                         params.push(SpannedParam { name, ptype });
 
@@ -142,16 +616,7 @@ impl SpannedAstTree {
                         );
                     }
                 }
-                let rtype;
-                match lexer.next_token() {
-                    Token::Identifier(id) => rtype = id,
-                    b => {
-                        return parse_error!(
-                            b,
-                            "Please put a return type at the end of function declarations"
-                        );
-                    }
-                }
+                let rtype = self.parse_type(lexer)?;
 
                 match lexer.next_token() {
                     Token::LArrow(_) => {}
@@ -167,6 +632,11 @@ impl SpannedAstTree {
                 let body = self.parse_statement(lexer)?;
 
                 return Ok(SpannedChunk::Function {
+                    public: false,
+                    attributes: Vec::new(),
+                    docs: Vec::new(),
+                    export: None,
+                    must_use: false,
                     name: fn_identifier,
                     params,
                     rtype,
@@ -185,16 +655,370 @@ impl SpannedAstTree {
         }
     }
 
+    //
+    // Parse Types
+    //
+    pub fn parse_type(
+        &mut self,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedType, ParseError> {
+        let ok = self.parse_base_type(lexer)?;
+        // Results, eg usize!Error. ?T and *T bind tighter, so ?usize!Error is an optional result
+        match lexer.peek_next() {
+            (Token::Operator(op), i) if self.resolve_span(op.span.clone()) == "!" => {
+                lexer.go_to(i);
+                let error = self.parse_base_type(lexer)?;
+                let span = ok.get_span().start..error.get_span().end;
+                return Ok(SpannedType::Result {
+                    ok: Box::new(ok),
+                    error: Box::new(error),
+                    span,
+                });
+            }
+            _ => return Ok(ok),
+        }
+    }
+    fn parse_base_type(
+        &mut self,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedType, ParseError> {
+        match lexer.next_token() {
+            Token::Identifier(i) => return Ok(SpannedType::Named(i)),
+            // Pointers, eg *u8
+            Token::Operator(op) if self.resolve_span(op.span.clone()) == "*" => {
+                let inner = self.parse_base_type(lexer)?;
+                let e = inner.get_span().end;
+                return Ok(SpannedType::Pointer {
+                    inner: Box::new(inner),
+                    span: op.span.start..e,
+                });
+            }
+            // Optionals, eg ?*u8
+            Token::Question(s) => {
+                let inner = self.parse_base_type(lexer)?;
+                if let SpannedType::Optional { .. } = inner {
+                    return parse_error!(Token::Question(s), "Optionals can't be nested, eg ??T");
+                }
+                let e = inner.get_span().end;
+                return Ok(SpannedType::Optional {
+                    inner: Box::new(inner),
+                    span: s.start..e,
+                });
+            }
+            // Function pointer types, eg fn(usize, bool) usize
+            Token::Fn(s) => {
+                match lexer.next_token() {
+                    Token::LParen(_) => {}
+                    b => {
+                        return parse_error!(
+                            b,
+                            "Function types list their parameters, eg fn(Type) Type"
+                        );
+                    }
+                }
+
+                let mut params = Vec::new();
+                match lexer.peek_next() {
+                    (Token::RParen(_), i) => lexer.go_to(i),
+                    _ => loop {
+                        params.push(self.parse_type(lexer)?);
+                        match lexer.next_token() {
+                            Token::Comma(_) => {}
+                            Token::RParen(_) => break,
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Function type parameters are seperated by commas and ended by a closing parenthesis"
+                                );
+                            }
+                        }
+                    },
+                }
+
+                let ret = self.parse_type(lexer)?;
+                let e = ret.get_span().end;
+
+                return Ok(SpannedType::Fn {
+                    params,
+                    ret: Box::new(ret),
+                    span: s.start..e,
+                });
+            }
+            // Slices, eg []str, a pointer to the first element and how many there are
+            Token::LBracket(s) => {
+                match lexer.next_token() {
+                    Token::RBracket(_) => {}
+                    b => return parse_error!(b, "Slice types are written []Type"),
+                }
+                let inner = self.parse_base_type(lexer)?;
+                let e = inner.get_span().end;
+                return Ok(SpannedType::Slice {
+                    inner: Box::new(inner),
+                    span: s.start..e,
+                });
+            }
+            // Tuples, eg (usize, *u8)
+            Token::LParen(s) => {
+                let mut elements = Vec::new();
+                let e;
+                loop {
+                    elements.push(self.parse_type(lexer)?);
+                    match lexer.next_token() {
+                        Token::Comma(_) => {}
+                        Token::RParen(r) => {
+                            e = r.end;
+                            break;
+                        }
+                        b => {
+                            return parse_error!(
+                                b,
+                                "Tuple elements are seperated by commas and ended by a closing parenthesis"
+                            );
+                        }
+                    }
+                }
+                if elements.len() < 2 {
+                    return parse_error!(Token::LParen(s), "Tuples have at least two elements");
+                }
+
+                return Ok(SpannedType::Tuple {
+                    elements,
+                    span: s.start..e,
+                });
+            }
+            b => {
+                return parse_error!(b, "Please provide a type, eg usize or fn(Type) Type");
+            }
+        }
+    }
+
+    // The rest of a type alias, after the type keyword
+    fn parse_type_alias(
+        &mut self,
+        s: Range<usize>,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedChunk, ParseError> {
+        let name;
+        match lexer.next_token() {
+            Token::Identifier(i) => name = i,
+            b => return parse_error!(b, "Type aliases need an identifier"),
+        }
+        match lexer.next_token() {
+            Token::Operator(o) if self.resolve_span(o.span.clone()) == "=" => {}
+            b => return parse_error!(b, "Please give the aliased type using '='"),
+        }
+
+        let target = self.parse_type(lexer)?;
+
+        match lexer.next_token() {
+            Token::EOL(e) => {
+                return Ok(SpannedChunk::TypeAlias {
+                    public: false,
+                    attributes: Vec::new(),
+                    docs: Vec::new(),
+                    distinct: false,
+                    name,
+                    target,
+                    span: s.start..e.end,
+                });
+            }
+            b => return parse_error!(b, "Please end type aliases with ';'"),
+        }
+    }
+
+    // The rest of an attribute after the #, eg [align(16)]
+    fn parse_attribute(
+        &mut self,
+        s: Range<usize>,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<Attribute, ParseError> {
+        match lexer.next_token() {
+            Token::LBracket(_) => {}
+            b => return parse_error!(b, "Attributes are written #[name] or #[name(value)]"),
+        }
+        let name;
+        match lexer.next_token() {
+            Token::Identifier(i) => name = i.span,
+            b => return parse_error!(b, "Attributes are named, eg #[inline]"),
+        }
+
+        let mut value = None;
+        if let (Token::LParen(_), i) = lexer.peek_next() {
+            lexer.go_to(i);
+            match lexer.next_token() {
+                Token::StringLiteral(v) | Token::NumericLiteral(v) => value = Some(v),
+                b => return parse_error!(b, "Attribute values are strings or numbers"),
+            }
+            match lexer.next_token() {
+                Token::RParen(_) => {}
+                b => return parse_error!(b, "Please close the attribute's parentheses"),
+            }
+        }
+
+        match lexer.next_token() {
+            Token::RBracket(e) => {
+                return Ok(Attribute {
+                    name,
+                    value,
+                    span: s.start..e.end,
+                });
+            }
+            b => return parse_error!(b, "Please close the attribute with ']'"),
+        }
+    }
+    // The string given to #[include(...)] or #[export_name(...)]
+    fn string_value(
+        &self,
+        s: Range<usize>,
+        value: Option<Range<usize>>,
+    ) -> Result<Range<usize>, ParseError> {
+        match value {
+            Some(v) if self.resolve_span(v.clone()).starts_with('"') => return Ok(v),
+            _ => return parse_error!(Token::Hash(s), "Attributes are written #[name(\"...\")]"),
+        }
+    }
+    // Unknown attributes, and ones on something they don't apply to, are warned about and left
+    // for the backend to ignore
+    fn check_attribute(
+        &mut self,
+        attribute: &Attribute,
+        target: AttributeTarget,
+    ) -> Result<(), ParseError> {
+        let text = self.resolve_span(attribute.span.clone()).to_string();
+        let targets;
+        let expected;
+        match attribute_targets(self.resolve_span(attribute.name.clone())) {
+            Some((t, v)) => {
+                targets = t;
+                expected = v;
+            }
+            None => {
+                self.warnings.push((
+                    attribute.span.clone(),
+                    format!("unknown attribute {}, ignored", text),
+                ));
+                return Ok(());
+            }
+        }
+
+        let value = attribute.value.clone().map(|v| self.resolve_span(v));
+        let valid = match (expected, value) {
+            (AttributeValue::None | AttributeValue::Optional, None) => true,
+            (AttributeValue::Optional, Some(v)) => v.starts_with('"'),
+            (AttributeValue::Number, Some(v)) => v.chars().all(|c| c.is_ascii_digit()),
+            _ => false,
+        };
+        if !valid {
+            return parse_error!(
+                Token::Hash(attribute.span.clone()),
+                "Wrong attribute value, eg #[inline], #[align(16)] or #[deprecated(\"reason\")]"
+            );
+        }
+
+        if !targets.contains(&target) {
+            let on: Vec<_> = targets.iter().map(|t| t.describe()).collect();
+            self.warnings.push((
+                attribute.span.clone(),
+                format!(
+                    "{} only applies to {}, not {}, ignored",
+                    text,
+                    on.join(", "),
+                    target.describe()
+                ),
+            ));
+        }
+        return Ok(());
+    }
+
     //
     // Parse Statements
     //
     pub fn parse_statement(
         &mut self,
-        lexer: &mut (impl traits::LexerTrait<Token> + traits::DebugLexerTrait<Token>),
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
     ) -> Result<SpannedStatement, ParseError> {
+        let start = lexer.get_index();
         match lexer.next_token() {
+            // Statements with attributes, eg #[align(16)] let buf: usize = 0;. The span covers
+            // the attributes
+            Token::Hash(s) => {
+                let attribute = self.parse_attribute(s, lexer)?;
+                let mut statement = self.parse_statement(lexer)?;
+                if let SpannedStatement::Attributed {
+                    attributes, span, ..
+                } = &mut statement
+                {
+                    attributes.insert(0, attribute.clone());
+                    *span = attribute.span.start..span.end;
+                } else {
+                    statement = SpannedStatement::Attributed {
+                        attributes: vec![attribute.clone()],
+                        statement: Box::new(statement),
+                        span: attribute.span.clone(),
+                    };
+                }
+
+                let target;
+                match &statement {
+                    SpannedStatement::Attributed { statement, .. }
+                        if matches!(**statement, SpannedStatement::Decleration { .. }) =>
+                    {
+                        target = AttributeTarget::Let
+                    }
+                    _ => target = AttributeTarget::Statement,
+                }
+                self.check_attribute(&attribute, target)?;
+                return Ok(statement);
+            }
             // Declaration
             Token::Let(span) => {
+                // Destructuring a tuple, eg let (q, r) = divmod(7, 2);
+                if let (Token::LParen(_), i) = lexer.peek_next() {
+                    lexer.go_to(i);
+                    let mut names = Vec::new();
+                    loop {
+                        match lexer.next_token() {
+                            Token::Identifier(i) => names.push(i),
+                            b => return parse_error!(b, "Please name each element of the tuple"),
+                        }
+                        match lexer.next_token() {
+                            Token::Comma(_) => {}
+                            Token::RParen(_) => break,
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Names are seperated by commas and ended by a closing parenthesis"
+                                );
+                            }
+                        }
+                    }
+
+                    match lexer.next_token() {
+                        Token::Operator(o) if self.resolve_span(o.span.clone()) == "=" => {}
+                        b => {
+                            return parse_error!(
+                                b,
+                                "Please give the tuple to destructure using '='"
+                            );
+                        }
+                    }
+
+                    let expression = Box::new(self.parse_expression(lexer)?);
+
+                    match lexer.next_token() {
+                        Token::EOL(_) => {}
+                        b => {
+                            return parse_error!(b, "Please end statement with ';'");
+                        }
+                    }
+
+                    return Ok(SpannedStatement::Destructure {
+                        names,
+                        expression,
+                        span,
+                    });
+                }
+
                 let name;
                 match lexer.next_token() {
                     Token::Identifier(i) => name = i,
@@ -208,13 +1032,7 @@ impl SpannedAstTree {
                     b => return parse_error!(b, "Please provide type for definition 'v: type'"),
                 }
 
-                let rtype;
-                match lexer.next_token() {
-                    Token::Identifier(i) => rtype = i,
-                    b => {
-                        return parse_error!(b, "Please annotate with type");
-                    }
-                }
+                let rtype = self.parse_type(lexer)?;
 
                 match lexer.next_token() {
                     Token::Operator(o) if self.resolve_span(o.span.clone()) == "=" => {}
@@ -249,6 +1067,22 @@ impl SpannedAstTree {
                     }
                 }
 
+                // Unwrapping an optional, eg if (let v = opt)
+                let mut binding = None;
+                if let (Token::Let(_), i) = lexer.peek_next() {
+                    lexer.go_to(i);
+                    match lexer.next_token() {
+                        Token::Identifier(i) => binding = Some(i),
+                        b => return parse_error!(b, "Let condition followed by identifier"),
+                    }
+                    match lexer.next_token() {
+                        Token::Operator(o) if self.resolve_span(o.span.clone()) == "=" => {}
+                        b => {
+                            return parse_error!(b, "Please give the optional to unwrap using '='");
+                        }
+                    }
+                }
+
                 let condition = self.parse_expression(lexer)?;
                 let condition = Box::new(condition);
 
@@ -263,25 +1097,30 @@ impl SpannedAstTree {
                 let statement = Box::new(statement);
 
                 // Else Statements
+                let ielse;
                 match lexer.peek_next() {
                     (Token::Else(_), i) => {
                         lexer.go_to(i);
+                        ielse = Some(Box::new(self.parse_statement(lexer)?));
+                    }
+                    _ => ielse = None,
+                }
 
-                        let ielse = self.parse_statement(lexer)?;
-                        let ielse = Some(Box::new(ielse));
-
-                        return Ok(SpannedStatement::If {
-                            condition,
+                match binding {
+                    Some(name) => {
+                        return Ok(SpannedStatement::IfLet {
+                            name,
+                            expression: condition,
                             statement,
                             ielse,
                             span: s.start..e,
                         });
                     }
-                    _ => {
+                    None => {
                         return Ok(SpannedStatement::If {
                             condition,
                             statement,
-                            ielse: None,
+                            ielse,
                             span: s.start..e,
                         });
                     }
@@ -306,71 +1145,6 @@ impl SpannedAstTree {
                 });
             }
             // Identifier Branch
-            Token::Identifier(name) => {
-                // Collect the statement
-                let st;
-                match lexer.next_token() {
-                    // Function Call
-                    Token::LParen(_) => {
-                        let mut params = Vec::new();
-                        let e;
-                        match lexer.peek_next() {
-                            (Token::RParen(s), i) => {
-                                e = s.end;
-                                lexer.go_to(i);
-                            }
-                            _ => loop {
-                                params.push(self.parse_expression(lexer)?);
-                                match lexer.next_token() {
-                                    Token::Comma(_) => {}
-                                    Token::RParen(s) => {
-                                        e = s.end;
-                                        break;
-                                    }
-                                    b => {
-                                        return parse_error!(
-                                            b,
-                                            "Either terminate parameter calls, or seperate them with a comma"
-                                        );
-                                    }
-                                }
-                            },
-                        }
-
-                        st = Ok(SpannedStatement::VoidCall {
-                            span: name.span.start..e,
-                            name: Box::new(name),
-                            params,
-                        });
-                    }
-                    // Reassignments
-                    Token::Operator(op)
-                        if ["=", "+=", "-=", "*=", "/="]
-                            .contains(&self.resolve_span(op.span.clone())) =>
-                    {
-                        let expression = Box::new(self.parse_expression(lexer)?);
-                        st = Ok(SpannedStatement::Reassignment {
-                            name,
-                            expression,
-                            span: op.span,
-                        });
-                    }
-                    b => {
-                        return parse_error!(
-                            b,
-                            "Invalid token after identifier, either make function call or reassign variable"
-                        );
-                    }
-                }
-                // Return if it ends with an EOL
-                match lexer.next_token() {
-                    Token::EOL(_) => return st,
-                    b => {
-                        return parse_error!(b, "Please end statements in ';'");
-                    }
-                }
-                // End of identifier branch
-            }
             Token::Return(s1) => {
                 let expr;
                 match self.parse_expression(lexer) {
@@ -393,9 +1167,32 @@ impl SpannedAstTree {
                     b => return parse_error!(b, "Please end statements in ';'"),
                 }
             }
-            // Miss
+            // eg defer free(p);
+            Token::Defer(span) => {
+                let statement = Box::new(self.parse_statement(lexer)?);
+                return Ok(SpannedStatement::Defer { statement, span });
+            }
+            // Anything else is an expression, or an assignment to one, eg increment(c); or p.x = 1;
             b => {
-                return parse_error!(b, "Invalid statement start");
+                let s = b.get_span().start;
+                lexer.go_to(start);
+                let expression = self.parse_expression(lexer)?;
+                if self.is_assignment(&lexer.peek_next().0) {
+                    let st = self.parse_assignment(expression, lexer)?;
+                    match lexer.next_token() {
+                        Token::EOL(_) => return Ok(st),
+                        b => return parse_error!(b, "Please end statements in ';'"),
+                    }
+                }
+                match lexer.next_token() {
+                    Token::EOL(e) => {
+                        return Ok(SpannedStatement::Expression {
+                            expression: Box::new(expression),
+                            span: s..e.end,
+                        });
+                    }
+                    b => return parse_error!(b, "Please end statements in ';'"),
+                }
             }
         }
     }
@@ -405,45 +1202,133 @@ impl SpannedAstTree {
     //
     pub fn parse_expression(
         &mut self,
-        lexer: &mut (impl traits::LexerTrait<Token> + traits::DebugLexerTrait<Token>),
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
     ) -> Result<SpannedExpression, ParseError> {
-        // First value in expression
-        let left;
-        match lexer.next_token() {
-            Token::Operator(op) => match self.resolve_span(op.span.clone()) {
-                // Complicated Logic
-                // Want to make the ! operator have less presedence than any binary operator. May
-                // do for all unary operators, just have to think about it
-                t if is_unary_ooperator(t) => {
-                    // Get a mutable expression
-                    let mut expression = self.parse_expression(lexer)?;
-                    // Get a mutable reference to it
-                    let mut node = &mut expression;
-
-                    // Get the left most child as a mutable reference
-                    while let SpannedExpression::BinaryOperator { left, .. } = node {
-                        node = left.as_mut();
-                    }
-
-                    // Swip swap a temp in as the value
-                    let old = std::mem::replace(node, SpannedExpression::Tmp);
-
-                    // Replace the temp with the new, wrapped value!!!
-                    *node = SpannedExpression::UnaryOperator {
-                        operation: op.span,
-                        expression: Box::new(old),
-                    };
+        return self.parse_binary(1, lexer);
+    }
+    // Precedence climbing. An operand, then every binary operator after it binding at least as
+    // tightly as `min`, each taking as its right side whatever binds tighter than it does
+    fn parse_binary(
+        &mut self,
+        min: usize,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedExpression, ParseError> {
+        let mut left = self.parse_unary(lexer)?;
+        loop {
+            let (token, i) = lexer.peek_next();
+            let operation;
+            let power;
+            let associativity;
+            match self.binary_operator(&token) {
+                Some((o, p, a)) if p >= min => {
+                    operation = o;
+                    power = p;
+                    associativity = a;
+                }
+                // Assignments end the expression, for the statement to take
+                _ => return Ok(left),
+            }
+            lexer.go_to(i);
 
-                    // Was really lifetime tricky to implement
-                    return Ok(expression);
+            let right;
+            match associativity {
+                Associativity::Right => right = self.parse_binary(power, lexer)?,
+                Associativity::Left | Associativity::None => {
+                    right = self.parse_binary(power + 1, lexer)?
                 }
-                _ => {
-                    return parse_error!(
-                        Token::Operator(op),
-                        "Invalid operator to start expression"
-                    );
+            }
+            if associativity == Associativity::None
+                && let (next, _) = lexer.peek_next()
+                && self
+                    .binary_operator(&next)
+                    .is_some_and(|(_, p, _)| p == power)
+            {
+                return parse_error!(next, "Comparisons can't be chained, eg a == b == c");
+            }
+
+            let left_box = Box::new(left);
+            let right = Box::new(right);
+            match (token, operation) {
+                (Token::Orelse(span), None) => {
+                    left = SpannedExpression::Orelse {
+                        left: left_box,
+                        right,
+                        span,
+                    };
                 }
-            },
+                (Token::Operator(op), Some(operation)) => {
+                    left = SpannedExpression::BinaryOperator {
+                        left: left_box,
+                        span: op.span,
+                        operation,
+                        right,
+                    };
+                }
+                (b, _) => return parse_error!(b, "Expected a binary operator"),
+            }
+        }
+    }
+    // What a binary operator does and how tightly it binds, if the token is one
+    fn binary_operator(
+        &self,
+        token: &Token,
+    ) -> Option<(Option<BinaryOperation>, usize, Associativity)> {
+        let text;
+        match token {
+            Token::Orelse(_) => text = "orelse",
+            Token::Operator(op) => text = self.resolve_span(op.span.clone()),
+            _ => return None,
+        }
+        return BINARY_OPERATORS
+            .iter()
+            .find(|(o, _, _, _)| *o == text)
+            .map(|(_, operation, power, associativity)| (*operation, *power, *associativity));
+    }
+    // What an assignment operator does before assigning, if the token is one
+    fn assignment_operator(&self, token: &Token) -> Option<Option<BinaryOperation>> {
+        match token {
+            Token::Operator(op) => {
+                let text = self.resolve_span(op.span.clone());
+                return ASSIGNMENT_OPERATORS
+                    .iter()
+                    .find(|(o, _)| *o == text)
+                    .map(|(_, operation)| *operation);
+            }
+            _ => return None,
+        }
+    }
+    fn is_assignment(&self, token: &Token) -> bool {
+        return self.assignment_operator(token).is_some();
+    }
+    // Unary operators bind tighter than any binary operator, and postfixes tighter still, so
+    // -a * b is (-a) * b and *p.x is *(p.x)
+    fn parse_unary(
+        &mut self,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedExpression, ParseError> {
+        if let (Token::Operator(op), i) = lexer.peek_next()
+            && is_unary_ooperator(self.resolve_span(op.span.clone()))
+        {
+            lexer.go_to(i);
+            let expression = Box::new(self.parse_unary(lexer)?);
+            if self.resolve_span(op.span.clone()) == "*" {
+                return Ok(SpannedExpression::Deref {
+                    expression,
+                    span: op.span,
+                });
+            }
+            return Ok(SpannedExpression::UnaryOperator {
+                operation: op.span,
+                expression,
+            });
+        }
+
+        // First value in expression
+        let left;
+        match lexer.next_token() {
+            Token::Operator(op) => {
+                return parse_error!(Token::Operator(op), "Invalid operator to start expression");
+            }
             // TODO: Expression function calls
             Token::Identifier(identifier) => {
                 match lexer.peek_next() {
@@ -486,25 +1371,108 @@ impl SpannedAstTree {
                     _ => left = SpannedExpression::Identifier(identifier),
                 }
             }
-            // TODO: Numeric Literals
-            Token::NumericLiteral(range) => {
-                todo!()
+            Token::NumericLiteral(span) => left = SpannedExpression::Literal { span },
+            Token::StringLiteral(span) => left = SpannedExpression::StringLiteral { span },
+            Token::NoneLiteral(span) => left = SpannedExpression::NoneLiteral { span },
+            // Lambdas, eg |a: usize| usize -> { return a; }
+            Token::Pipe(s) => {
+                let mut params = Vec::new();
+                match lexer.next_token() {
+                    Token::Pipe(_) => {}
+                    Token::Identifier(mut name) => loop {
+                        match lexer.next_token() {
+                            Token::Colon(_) => {}
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Lambda parameters require types, eg |a: Type|"
+                                );
+                            }
+                        }
+
+                        let ptype = self.parse_type(lexer)?;
+                        params.push(SpannedParam { name, ptype });
+
+                        match lexer.next_token() {
+                            Token::Pipe(_) => break,
+                            Token::Comma(_) => {}
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Lambda parameters are seperated by commas and ended by a '|'"
+                                );
+                            }
+                        }
+
+                        match lexer.next_token() {
+                            Token::Identifier(i) => name = i,
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "lambda parameters are made of characters or _"
+                                );
+                            }
+                        }
+                    },
+                    b => {
+                        return parse_error!(
+                            b,
+                            "If no parameters, close the lambda with '|', else, list parameters"
+                        );
+                    }
+                }
+
+                let rtype = self.parse_type(lexer)?;
+                let e = rtype.get_span().end;
+
+                match lexer.next_token() {
+                    Token::LArrow(_) => {}
+                    b => {
+                        return parse_error!(
+                            b,
+                            "Please put '-> between return type and lambda body"
+                        );
+                    }
+                }
+
+                let body = Box::new(self.parse_statement(lexer)?);
+
+                left = SpannedExpression::Lambda {
+                    params,
+                    rtype,
+                    body,
+                    span: s.start..e,
+                };
             }
             Token::LParen(s) => {
                 let expression = self.parse_expression(lexer)?;
-                let expression = Box::new(expression);
 
                 let e;
-                match lexer.next_token() {
-                    Token::RParen(s) => e = s.end,
-                    b => {
-                        return parse_error!(b, "No matching closing parentheses");
+                let mut elements = Vec::new();
+                loop {
+                    match lexer.next_token() {
+                        Token::RParen(s) => {
+                            e = s.end;
+                            break;
+                        }
+                        // A comma makes it a tuple, eg (q, r)
+                        Token::Comma(_) => elements.push(self.parse_expression(lexer)?),
+                        b => {
+                            return parse_error!(b, "No matching closing parentheses");
+                        }
                     }
                 }
-                left = SpannedExpression::UnaryOperator {
-                    operation: s.start..e,
-                    expression,
-                };
+
+                // Parentheses only group, which the shape of the tree already shows
+                if elements.is_empty() {
+                    left = expression;
+                } else {
+                    elements.insert(0, expression);
+                    left = SpannedExpression::Tuple {
+                        elements,
+                        span: s.start..e,
+                    };
+                }
             }
             // Miss
             b => {
@@ -512,61 +1480,146 @@ impl SpannedAstTree {
             }
         }
 
-        // We want to collapse operators now. If there is an operator here, then we check if it is
-        // valid, then check if it is the only one, else we reorder for presedence
-        let left_operation;
-        match lexer.peek_next() {
-            (Token::Operator(op), i) => {
-                lexer.go_to(i);
-                left_operation = op;
-            }
-            _ => {
-                return Ok(left);
-            }
-        }
-        let left = Box::new(left);
-
-        match self.parse_expression(lexer)? {
-            SpannedExpression::BinaryOperator {
-                left: mid,
-                span,
-                presedence,
-                right,
-            } => {
-                if left_operation.presedence < presedence {
-                    return Ok(SpannedExpression::BinaryOperator {
-                        left,
+        return self.parse_postfix(left, lexer);
+    }
+    // Postfixes on a value, eg parse(s)?, pair.0, p.x or ptr[i]
+    fn parse_postfix(
+        &mut self,
+        mut left: SpannedExpression,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedExpression, ParseError> {
+        loop {
+            match lexer.peek_next() {
+                (Token::Question(span), i) => {
+                    lexer.go_to(i);
+                    left = SpannedExpression::Try {
+                        expression: Box::new(left),
                         span,
-                        presedence,
-                        right: Box::new(SpannedExpression::BinaryOperator {
-                            left: mid,
-                            span: left_operation.span,
-                            presedence: left_operation.presedence,
-                            right,
-                        }),
-                    });
-                } else {
-                    return Ok(SpannedExpression::BinaryOperator {
-                        left: Box::new(SpannedExpression::BinaryOperator {
-                            left,
+                    };
+                }
+                (Token::Dot(_), i) => {
+                    lexer.go_to(i);
+                    let number;
+                    match lexer.next_token() {
+                        Token::NumericLiteral(n) => number = n,
+                        Token::Identifier(field) => {
+                            left = SpannedExpression::Field {
+                                expression: Box::new(left),
+                                span: field.span.clone(),
+                                field,
+                            };
+                            continue;
+                        }
+                        b => {
+                            return parse_error!(
+                                b,
+                                "Fields are named or numbered, eg p.x or pair.0"
+                            );
+                        }
+                    }
+
+                    // pair.0.1 lexes the fields as one number, and pair.0.x lexes 0. as one
+                    let text = self.resolve_span(number.clone());
+                    let named = text.ends_with('.');
+                    let mut start = number.start;
+                    for field in text.trim_end_matches('.').split('.') {
+                        let span = start..start + field.len();
+                        let index;
+                        match field.parse() {
+                            Ok(i) => index = i,
+                            Err(_) => {
+                                return parse_error!(
+                                    Token::NumericLiteral(span),
+                                    "Tuple fields are numbers, eg pair.0"
+                                );
+                            }
+                        }
+                        left = SpannedExpression::TupleField {
+                            tuple: Box::new(left),
+                            index,
                             span,
-                            presedence,
-                            right: mid,
-                        }),
-                        span: left_operation.span,
-                        presedence,
-                        right,
-                    });
+                        };
+                        start += field.len() + 1;
+                    }
+                    if named {
+                        match lexer.next_token() {
+                            Token::Identifier(field) => {
+                                left = SpannedExpression::Field {
+                                    expression: Box::new(left),
+                                    span: field.span.clone(),
+                                    field,
+                                };
+                            }
+                            b => {
+                                return parse_error!(
+                                    b,
+                                    "Fields are named or numbered, eg p.x or pair.0"
+                                );
+                            }
+                        }
+                    }
+                }
+                (Token::LBracket(s), i) => {
+                    lexer.go_to(i);
+                    let index = self.parse_expression(lexer)?;
+                    match lexer.next_token() {
+                        Token::RBracket(e) => {
+                            left = SpannedExpression::Index {
+                                expression: Box::new(left),
+                                index: Box::new(index),
+                                span: s.start..e.end,
+                            };
+                        }
+                        b => return parse_error!(b, "Please close the index with ']'"),
+                    }
                 }
+                _ => break,
             }
-            right => {
-                return Ok(SpannedExpression::BinaryOperator {
-                    left,
-                    span: left_operation.span,
-                    presedence: left_operation.presedence,
-                    right: Box::new(right),
+        }
+        return Ok(left);
+    }
+    // The rest of an assignment to `target`, eg += 2
+    fn parse_assignment(
+        &mut self,
+        target: SpannedExpression,
+        lexer: &mut impl traits::DebugLexerTrait<Token>,
+    ) -> Result<SpannedStatement, ParseError> {
+        let token = lexer.next_token();
+        let operation = self.assignment_operator(&token);
+        match (token, operation) {
+            (Token::Operator(op), Some(operation)) => {
+                let expression = Box::new(self.parse_expression(lexer)?);
+                // Assignments are statements, so a = b = c has no value to give a
+                if let (next, _) = lexer.peek_next()
+                    && self.is_assignment(&next)
+                {
+                    return parse_error!(
+                        next,
+                        "Assignments can't be chained, assign each on its own"
+                    );
+                }
+                // _ = expr; throws the value away
+                if let SpannedExpression::Identifier(id) = &target
+                    && self.resolve_span(id.span.clone()) == "_"
+                {
+                    if operation.is_some() {
+                        return parse_error!(Token::Operator(op), "Values are discarded with _ =");
+                    }
+                    return Ok(SpannedStatement::Discard {
+                        expression,
+                        span: id.span.start..op.span.end,
+                    });
+                }
+                return Ok(SpannedStatement::Reassignment {
+                    target: Box::new(target),
+                    expression,
+                    operation,
+                    span: op.span,
                 });
             }
+            (b, _) => {
+                return parse_error!(b, "Assignments are written with =, +=, -=, *= or /=");
+            }
         }
     }
 }
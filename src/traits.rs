@@ -6,7 +6,7 @@ where
 {
     fn next_token(&mut self) -> T;
     fn peek_next(&self) -> (T, usize);
-    fn go_to(&mut self, i: usize) -> ();
+    fn go_to(&mut self, i: usize);
 }
 pub trait DebugLexerTrait<T>: LexerTrait<T>
 where
@@ -2,5 +2,9 @@ mod name_resolver;
 mod type_checker;
 
 pub use name_resolver::NameResolver;
+pub use name_resolver::ResolveError;
 pub use name_resolver::ResolvedAstTree;
 pub use type_checker::TypeChecker;
+pub use type_checker::TypeError;
+pub use type_checker::TypeId;
+pub use type_checker::TypeNotes;
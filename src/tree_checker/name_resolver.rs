@@ -1,131 +1,735 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 
-use crate::parsing::SpannedAstTree;
+use crate::parsing::{ModuleTree, SpannedAstTree};
 use crate::traits;
+use crate::types::Span;
 use crate::types::resolved_types::{
-    IdentifierId, ResolvedChunk, ResolvedExpression, ResolvedIdentifier, ResolvedParam,
-    ResolvedStatement,
+    ExpressionId, IdentifierId, IdentifierKind, ResolvedChunk, ResolvedExpression, ResolvedField,
+    ResolvedIdentifier, ResolvedParam, ResolvedStatement, ResolvedType, Symbol, SymbolKind,
 };
 use crate::types::spanned_types::{
-    SpannedChunk, SpannedExpression, SpannedIdentifier, SpannedStatement,
+    SpannedChunk, SpannedExpression, SpannedIdentifier, SpannedStatement, SpannedType,
 };
 
 #[derive(Debug)]
 pub struct ResolvedAstTree {
     pub body: Vec<ResolvedChunk>,
+    // What each IdentifierId is bound to, indexed by the id
+    pub symbols: Vec<Symbol>,
 }
 impl traits::AstNodeTrait for ResolvedAstTree {}
+impl ResolvedAstTree {
+    // The main function of the root module, where the program starts. Libraries have none
+    pub fn entry_point(&self) -> Option<&ResolvedChunk> {
+        return self.body.iter().find(|chunk| {
+            matches!(chunk, ResolvedChunk::Function { name, .. }
+                if *self.symbols[name.id.0].name == *"main")
+        });
+    }
+}
+
+#[derive(Debug)]
+pub enum ResolveError {
+    // A value that isn't a local, param or item in scope
+    Undefined(Span),
+    UndefinedType(Span),
+    // An item defined twice in one module. Only extern declarations can be repeated, as in C
+    Duplicate(Span),
+    DuplicateParam(Span),
+    // A value where a type is expected, eg let x: main = 1, whether an item, param or local
+    NotAType(Span),
+    // Aliases that are made from themselves, eg type A = B; type B = A;
+    AliasCycle(Span),
+    // A type where a value is expected. Distinct types can still be called to make one
+    NotAValue(Span),
+}
+
+// What a module can see: its own items, and the modules it imports by their last path segment
+#[derive(Debug, Default)]
+struct ModuleScope {
+    types: HashMap<Rc<str>, Item>,
+    values: HashMap<Rc<str>, Item>,
+    imports: HashMap<Rc<str>, Rc<str>>,
+}
+impl ModuleScope {
+    fn items(&self, namespace: IdentifierKind) -> &HashMap<Rc<str>, Item> {
+        match namespace {
+            IdentifierKind::Type => return &self.types,
+            _ => return &self.values,
+        }
+    }
+    fn items_mut(&mut self, namespace: IdentifierKind) -> &mut HashMap<Rc<str>, Item> {
+        match namespace {
+            IdentifierKind::Type => return &mut self.types,
+            _ => return &mut self.values,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Item {
+    public: bool,
+    // C symbols live in one global namespace, so they don't get a module path
+    external: bool,
+}
 
 #[derive(Debug)]
 pub struct NameResolver {
     input: Rc<str>,
-    map: HashMap<Rc<str>, IdentifierId>,
+    symbols: Vec<Symbol>,
+    // Module items by their full path, kept apart so a type and a value can share a name
+    types: HashMap<Rc<str>, IdentifierId>,
+    values: HashMap<Rc<str>, IdentifierId>,
+    fields: HashMap<Rc<str>, IdentifierId>,
+    // Extern declarations, which can be repeated
+    externs: HashSet<IdentifierId>,
+    // Distinct types, which are called like functions to make one
+    distinct: HashSet<IdentifierId>,
+    // The locals and params visible from here, innermost last. Empty outside of functions
+    scopes: Vec<HashMap<Rc<str>, IdentifierId>>,
+    lambdas: usize,
+    // How many expressions have been numbered
+    expressions: usize,
+    modules: HashMap<Rc<str>, ModuleScope>,
+    module: Rc<str>,
+    // Aliases that aren't distinct, by their full path, with the module they are written in
+    aliases: HashMap<Rc<str>, (Rc<str>, SpannedType)>,
+    // The aliases being replaced, so an alias of itself isn't followed forever
+    expanding: Vec<Rc<str>>,
 }
 
 impl NameResolver {
     pub fn from_rc_str(input: Rc<str>) -> Self {
         return NameResolver {
             input,
-            map: HashMap::new(),
+            symbols: Vec::new(),
+            types: HashMap::new(),
+            values: HashMap::new(),
+            fields: HashMap::new(),
+            externs: HashSet::new(),
+            distinct: HashSet::new(),
+            scopes: Vec::new(),
+            lambdas: 0,
+            expressions: 0,
+            modules: HashMap::new(),
+            module: "".into(),
+            aliases: HashMap::new(),
+            expanding: Vec::new(),
         };
     }
 
     pub fn dbg_ids(&self) {
-        _ = dbg!(&self.map);
+        _ = dbg!(&self.symbols);
     }
 
+    // The primitive types, which take the first ids
     pub fn pre_intern(mut self, pre_intern: &[&str]) -> Self {
         for s in pre_intern {
-            let id = IdentifierId(self.map.len());
-            self.map.insert((*s).into(), id);
+            let id = self.new_symbol((*s).into(), SymbolKind::Type, None);
+            self.types.insert((*s).into(), id);
         }
 
         return self;
     }
 
-    pub fn resolve(&mut self, ast: SpannedAstTree) -> ResolvedAstTree {
+    pub fn debug_resolve_result(r: &Result<ResolvedAstTree, ResolveError>, input: Rc<str>) {
+        let s;
+        match r {
+            Ok(_) => {
+                println!("Ok");
+                return;
+            }
+            Err(e) => s = e,
+        }
+        match s {
+            ResolveError::Undefined(range) => {
+                println!(
+                    "Err: Nothing by this name is in scope: {}",
+                    &input[range.clone()]
+                )
+            }
+            ResolveError::UndefinedType(range) => {
+                println!(
+                    "Err: No type by this name is in scope: {}",
+                    &input[range.clone()]
+                )
+            }
+            ResolveError::Duplicate(range) => {
+                println!(
+                    "Err: This name is already defined in the module: {}",
+                    &input[range.clone()]
+                )
+            }
+            ResolveError::DuplicateParam(range) => {
+                println!(
+                    "Err: Params need different names: {}",
+                    &input[range.clone()]
+                )
+            }
+            ResolveError::NotAType(range) => {
+                println!("Err: A value is used as a type: {}", &input[range.clone()])
+            }
+            ResolveError::AliasCycle(range) => {
+                println!(
+                    "Err: This alias is made from itself, so is never a type: {}",
+                    &input[range.clone()]
+                )
+            }
+            ResolveError::NotAValue(range) => {
+                println!(
+                    "Err: A type is used as a value, only distinct types can be called: {}",
+                    &input[range.clone()]
+                )
+            }
+        }
+    }
+
+    pub fn resolve(&mut self, ast: SpannedAstTree) -> Result<ResolvedAstTree, ResolveError> {
+        return self.resolve_modules(ModuleTree::from_ast(self.input.clone(), ast));
+    }
+
+    // Resolves every module into one tree
+    pub fn resolve_modules(&mut self, tree: ModuleTree) -> Result<ResolvedAstTree, ResolveError> {
+        self.input = tree.source;
+
+        let input = self.input.clone();
+        // Find every module's items up front, so they can be used before they are defined
+        for module in &tree.modules {
+            let mut scope = ModuleScope::default();
+            for chunk in &module.ast.body {
+                let public;
+                let external;
+                let kind;
+                let name;
+                match chunk {
+                    SpannedChunk::Import { path, .. } => {
+                        let path: Rc<str> = input[path.span.clone()].into();
+                        let alias = path.rsplit("::").next().unwrap().into();
+                        scope.imports.insert(alias, path);
+                        continue;
+                    }
+                    SpannedChunk::Function {
+                        public: p, name: n, ..
+                    } => {
+                        public = *p;
+                        external = false;
+                        kind = SymbolKind::Function;
+                        name = n;
+                    }
+                    SpannedChunk::ExternFunction {
+                        public: p, name: n, ..
+                    } => {
+                        public = *p;
+                        external = true;
+                        kind = SymbolKind::Function;
+                        name = n;
+                    }
+                    SpannedChunk::ExternStatic {
+                        public: p, name: n, ..
+                    } => {
+                        public = *p;
+                        external = true;
+                        kind = SymbolKind::Static;
+                        name = n;
+                    }
+                    SpannedChunk::Struct {
+                        public: p,
+                        external: e,
+                        name: n,
+                        ..
+                    } => {
+                        public = *p;
+                        external = *e;
+                        kind = SymbolKind::Type;
+                        name = n;
+                    }
+                    // Aliases that aren't distinct are replaced by their target, so only need
+                    // to be visible
+                    SpannedChunk::TypeAlias {
+                        public: p,
+                        distinct,
+                        name: n,
+                        target,
+                        ..
+                    } => {
+                        let item = Item {
+                            public: *p,
+                            external: false,
+                        };
+                        let written = &input[n.span.clone()];
+                        scope.types.insert(written.into(), item);
+                        let path = item_path(&module.path, written, &item);
+                        if !*distinct {
+                            if self.types.contains_key(&path) || self.aliases.contains_key(&path) {
+                                return Err(ResolveError::Duplicate(n.span.clone()));
+                            }
+                            self.aliases
+                                .insert(path, (module.path.clone(), target.clone()));
+                            continue;
+                        }
+                        let id = self.define_item(path, SymbolKind::Type, n.span.clone(), false)?;
+                        self.distinct.insert(id);
+                        continue;
+                    }
+                    // Variants are items of their own, named through the enum, eg Colour::Red
+                    SpannedChunk::Enum {
+                        public: p,
+                        external: e,
+                        name: n,
+                        variants,
+                        ..
+                    } => {
+                        public = *p;
+                        external = *e;
+                        kind = SymbolKind::Type;
+                        name = n;
+
+                        let item = Item { public, external };
+                        let enum_name = &input[name.span.clone()];
+                        for variant in variants {
+                            let written =
+                                format!("{}::{}", enum_name, &input[variant.span.clone()]);
+                            let path = item_path(&module.path, &written, &item);
+                            self.define_item(
+                                path,
+                                SymbolKind::Variant,
+                                variant.span.clone(),
+                                external,
+                            )?;
+                            scope.values.insert(written.into(), item);
+                        }
+                    }
+                    _ => continue,
+                }
+                let item = Item { public, external };
+                let written = &input[name.span.clone()];
+                let path = item_path(&module.path, written, &item);
+                self.define_item(path, kind, name.span.clone(), external)?;
+                scope
+                    .items_mut(kind.namespace())
+                    .insert(written.into(), item);
+            }
+            self.modules.insert(module.path.clone(), scope);
+        }
+
+        // Imported modules are loaded after their importers, so go backwards to put them first
         let mut new_body = Vec::new();
-        for chunk in ast.body {
-            new_body.push(self.resolve_chunk(chunk));
+        for module in tree.modules.into_iter().rev() {
+            self.module = module.path;
+            for chunk in module.ast.body {
+                if !matches!(
+                    chunk,
+                    SpannedChunk::Import { .. }
+                        | SpannedChunk::ImportC { .. }
+                        | SpannedChunk::TypeAlias {
+                            distinct: false,
+                            ..
+                        }
+                ) {
+                    new_body.push(self.resolve_chunk(chunk)?);
+                }
+            }
         }
 
-        return ResolvedAstTree { body: new_body };
+        return Ok(ResolvedAstTree {
+            body: new_body,
+            symbols: std::mem::take(&mut self.symbols),
+        });
     }
 
-    fn intern(&mut self, identifier: SpannedIdentifier) -> ResolvedIdentifier {
+    fn new_symbol(&mut self, name: Rc<str>, kind: SymbolKind, span: Option<Span>) -> IdentifierId {
+        self.symbols.push(Symbol { name, kind, span });
+        return IdentifierId(self.symbols.len() - 1);
+    }
+    fn items(&self, namespace: IdentifierKind) -> &HashMap<Rc<str>, IdentifierId> {
+        match namespace {
+            IdentifierKind::Type => return &self.types,
+            _ => return &self.values,
+        }
+    }
+    // Repeated extern declarations keep the first id
+    fn define_item(
+        &mut self,
+        path: Rc<str>,
+        kind: SymbolKind,
+        span: Span,
+        external: bool,
+    ) -> Result<IdentifierId, ResolveError> {
+        let namespace = kind.namespace();
+        if let Some(id) = self.items(namespace).get(&path) {
+            if external && self.externs.contains(id) {
+                return Ok(*id);
+            }
+            return Err(ResolveError::Duplicate(span));
+        }
+        if namespace == IdentifierKind::Type && self.aliases.contains_key(&path) {
+            return Err(ResolveError::Duplicate(span));
+        }
+        let id = self.new_symbol(path.clone(), kind, Some(span));
+        match namespace {
+            IdentifierKind::Type => self.types.insert(path, id),
+            _ => self.values.insert(path, id),
+        };
+        if external {
+            self.externs.insert(id);
+        }
+        return Ok(id);
+    }
+
+    // Binds a new local or param in the innermost scope, hiding any other of the same name
+    fn bind(
+        &mut self,
+        identifier: SpannedIdentifier,
+        kind: SymbolKind,
+    ) -> Result<ResolvedIdentifier, ResolveError> {
+        let name: Rc<str> = self.input[identifier.span.clone()].into();
+        let scope = self.scopes.last().unwrap();
+        if kind == SymbolKind::Param && scope.contains_key(&name) {
+            return Err(ResolveError::DuplicateParam(identifier.span));
+        }
+        let id = self.new_symbol(name.clone(), kind, Some(identifier.span.clone()));
+        self.scopes.last_mut().unwrap().insert(name, id);
+        return Ok(ResolvedIdentifier {
+            span: identifier.span,
+            id,
+            kind: IdentifierKind::Value,
+        });
+    }
+    // A value, which is either a local or a module item. Calls can also make a distinct type
+    fn lookup(
+        &self,
+        identifier: SpannedIdentifier,
+        call: bool,
+    ) -> Result<ResolvedIdentifier, ResolveError> {
         let name = &self.input[identifier.span.clone()];
-        if let Some(id) = self.map.get(name) {
-            return ResolvedIdentifier {
+        for scope in self.scopes.iter().rev() {
+            if let Some(id) = scope.get(name) {
+                return Ok(ResolvedIdentifier {
+                    span: identifier.span,
+                    id: *id,
+                    kind: IdentifierKind::Value,
+                });
+            }
+        }
+
+        if let Some(id) = self
+            .qualify(name, IdentifierKind::Value)
+            .and_then(|name| self.values.get(&name))
+        {
+            return Ok(ResolvedIdentifier {
                 span: identifier.span,
                 id: *id,
-            };
+                kind: IdentifierKind::Value,
+            });
+        }
+        match self
+            .qualify(name, IdentifierKind::Type)
+            .and_then(|name| self.types.get(&name))
+        {
+            Some(id) if call && self.distinct.contains(id) => {
+                return Ok(ResolvedIdentifier {
+                    span: identifier.span,
+                    id: *id,
+                    kind: IdentifierKind::Type,
+                });
+            }
+            Some(_) => return Err(ResolveError::NotAValue(identifier.span)),
+            None => return Err(ResolveError::Undefined(identifier.span)),
+        }
+    }
+    // The name of a definition, which the first pass has always found
+    fn item(&self, identifier: SpannedIdentifier, namespace: IdentifierKind) -> ResolvedIdentifier {
+        let name = &self.input[identifier.span.clone()];
+        return self.item_as(name, identifier.span, namespace);
+    }
+    fn item_as(&self, name: &str, span: Span, namespace: IdentifierKind) -> ResolvedIdentifier {
+        let id = self.items(namespace)[&self.qualify(name, namespace).unwrap()];
+        return ResolvedIdentifier {
+            span,
+            id,
+            kind: namespace,
+        };
+    }
+    fn field(&mut self, identifier: SpannedIdentifier) -> ResolvedIdentifier {
+        let name: Rc<str> = self.input[identifier.span.clone()].into();
+        let id;
+        match self.fields.get(&name) {
+            Some(i) => id = *i,
+            None => {
+                id = self.new_symbol(
+                    name.clone(),
+                    SymbolKind::Field,
+                    Some(identifier.span.clone()),
+                );
+                self.fields.insert(name, id);
+            }
         }
-
-        let id = IdentifierId(self.map.len());
-        self.map.insert(name.into(), id);
-
         return ResolvedIdentifier {
             span: identifier.span,
             id,
+            kind: IdentifierKind::Field,
         };
     }
+
+    // Gives module items their full path, or None for paths that don't lead to a visible item.
+    // Other names are left as written, as the primitive types are
+    fn qualify(&self, name: &str, namespace: IdentifierKind) -> Option<Rc<str>> {
+        let scope = &self.modules[&self.module];
+        if let Some(item) = scope.items(namespace).get(name) {
+            return Some(item_path(&self.module, name, item));
+        }
+
+        if let Some((head, rest)) = name.split_once("::")
+            && let Some(target) = scope.imports.get(head)
+        {
+            // The item is either in the imported module, or one of its submodules
+            let mut candidates = vec![(target.to_string(), rest)];
+            if let Some((sub, item)) = rest.rsplit_once("::") {
+                candidates.push((format!("{}::{}", target, sub), item));
+            }
+            for (module, item) in candidates {
+                if let Some(found) = self
+                    .modules
+                    .get(module.as_str())
+                    .and_then(|m| m.items(namespace).get(item))
+                    && (found.public || *module == *self.module)
+                {
+                    return Some(item_path(&module, item, found));
+                }
+            }
+        }
+
+        if name.contains("::") {
+            return None;
+        }
+        return Some(name.into());
+    }
+}
+
+fn item_path(module: &str, name: &str, item: &Item) -> Rc<str> {
+    if module.is_empty() || item.external {
+        return name.into();
+    }
+    return format!("{}::{}", module, name).into();
 }
 
 impl NameResolver {
-    fn resolve_chunk(&mut self, chunk: SpannedChunk) -> ResolvedChunk {
+    fn resolve_chunk(&mut self, chunk: SpannedChunk) -> Result<ResolvedChunk, ResolveError> {
         match chunk {
+            SpannedChunk::Import { .. } | SpannedChunk::ImportC { .. } => panic!(),
+            SpannedChunk::ExternFunction {
+                public,
+                attributes,
+                docs,
+                must_use,
+                name,
+                params,
+                variadic,
+                rtype,
+                include,
+                span,
+            } => {
+                let name = self.item(name, IdentifierKind::Value);
+                self.scopes.push(HashMap::new());
+                let mut new_params = Vec::new();
+                for param in params {
+                    let name = self.bind(param.name, SymbolKind::Param)?;
+                    let ptype = self.resolve_type(param.ptype)?;
+                    new_params.push(ResolvedParam { name, ptype });
+                }
+                self.scopes.pop();
+
+                return Ok(ResolvedChunk::ExternFunction {
+                    public,
+                    attributes,
+                    docs,
+                    must_use,
+                    name,
+                    params: new_params,
+                    variadic,
+                    rtype: self.resolve_type(rtype)?,
+                    include,
+                    span,
+                });
+            }
+            SpannedChunk::ExternStatic {
+                public,
+                attributes,
+                docs,
+                name,
+                vtype,
+                include,
+                span,
+            } => {
+                return Ok(ResolvedChunk::ExternStatic {
+                    public,
+                    attributes,
+                    docs,
+                    name: self.item(name, IdentifierKind::Value),
+                    vtype: self.resolve_type(vtype)?,
+                    include,
+                    span,
+                });
+            }
+            SpannedChunk::Struct {
+                public,
+                attributes,
+                docs,
+                external,
+                include,
+                export,
+                name,
+                fields,
+                span,
+            } => {
+                let name = self.item(name, IdentifierKind::Type);
+                let mut new_fields = Vec::new();
+                for field in fields {
+                    new_fields.push(ResolvedField {
+                        name: self.field(field.name),
+                        ftype: self.resolve_type(field.ftype)?,
+                        attributes: field.attributes,
+                        docs: field.docs,
+                    });
+                }
+
+                return Ok(ResolvedChunk::Struct {
+                    public,
+                    attributes,
+                    docs,
+                    external,
+                    include,
+                    export,
+                    name,
+                    fields: new_fields,
+                    span,
+                });
+            }
+            SpannedChunk::Enum {
+                public,
+                attributes,
+                docs,
+                external,
+                include,
+                export,
+                name,
+                variants,
+                span,
+            } => {
+                let enum_name = self.input[name.span.clone()].to_string();
+                let name = self.item(name, IdentifierKind::Type);
+
+                // Found through the enum, the same way they are used
+                let mut new_variants = Vec::new();
+                for variant in variants {
+                    let path = format!("{}::{}", enum_name, &self.input[variant.span.clone()]);
+                    new_variants.push(self.item_as(&path, variant.span, IdentifierKind::Value));
+                }
+
+                return Ok(ResolvedChunk::Enum {
+                    public,
+                    attributes,
+                    docs,
+                    external,
+                    include,
+                    export,
+                    name,
+                    variants: new_variants,
+                    span,
+                });
+            }
+            SpannedChunk::TypeAlias {
+                public,
+                attributes,
+                docs,
+                distinct,
+                name,
+                target,
+                span,
+            } => {
+                return Ok(ResolvedChunk::TypeAlias {
+                    public,
+                    attributes,
+                    docs,
+                    distinct,
+                    name: self.item(name, IdentifierKind::Type),
+                    target: self.resolve_type(target)?,
+                    span,
+                });
+            }
             SpannedChunk::Constant => todo!(),
             SpannedChunk::StaticVar => todo!(),
             SpannedChunk::Function {
+                public,
+                attributes,
+                docs,
+                export,
+                must_use,
                 name,
                 params,
                 rtype,
                 body,
                 span,
             } => {
-                let name = self.intern(name);
+                let name = self.item(name, IdentifierKind::Value);
+                self.scopes.push(HashMap::new());
                 let mut new_params = Vec::new();
                 for param in params {
-                    let name = self.intern(param.name);
-                    let ptype = self.intern(param.ptype);
+                    let name = self.bind(param.name, SymbolKind::Param)?;
+                    let ptype = self.resolve_type(param.ptype)?;
                     new_params.push(ResolvedParam { name, ptype });
                 }
-                let rtype = self.intern(rtype);
-                let body = self.resolve_statement(body);
+                let rtype = self.resolve_type(rtype)?;
+                let body = self.resolve_statement(body)?;
+                self.scopes.pop();
 
-                return ResolvedChunk::Function {
+                return Ok(ResolvedChunk::Function {
+                    public,
+                    attributes,
+                    docs,
+                    export,
+                    must_use,
                     name,
                     params: new_params,
                     rtype,
                     body,
                     span,
-                };
+                });
             }
             SpannedChunk::EOF => panic!(),
         }
     }
 
-    fn resolve_statement(&mut self, st: SpannedStatement) -> ResolvedStatement {
-        match st {
+    fn resolve_statement(
+        &mut self,
+        st: SpannedStatement,
+    ) -> Result<ResolvedStatement, ResolveError> {
+        return Ok(match st {
             SpannedStatement::Decleration {
                 name,
                 expression,
                 rtype,
                 span,
-            } => ResolvedStatement::Decleration {
-                name: self.intern(name),
-                expression: Box::new(self.resolve_expression(*expression)),
-                rtype: self.intern(rtype),
-                span,
-            },
+            } => {
+                // The value can't see the name it is given, eg let x = x + 1 uses the old x
+                let expression = Box::new(self.resolve_expression(*expression)?);
+                ResolvedStatement::Decleration {
+                    rtype: self.resolve_type(rtype)?,
+                    name: self.bind(name, SymbolKind::Local)?,
+                    expression,
+                    span,
+                }
+            }
             SpannedStatement::Reassignment {
-                name,
+                target,
                 expression,
+                operation,
                 span,
             } => ResolvedStatement::Reassignment {
-                name: self.intern(name),
-                expression: Box::new(self.resolve_expression(*expression)),
+                target: Box::new(self.resolve_expression(*target)?),
+                expression: Box::new(self.resolve_expression(*expression)?),
+                operation,
                 span,
             },
             SpannedStatement::If {
@@ -134,71 +738,303 @@ impl NameResolver {
                 ielse,
                 span,
             } => ResolvedStatement::If {
-                condition: Box::new(self.resolve_expression(*condition)),
-                statement: Box::new(self.resolve_statement(*statement)),
-                ielse: ielse.map(|s| Box::new(self.resolve_statement(*s))),
+                condition: Box::new(self.resolve_expression(*condition)?),
+                statement: Box::new(self.resolve_statement(*statement)?),
+                ielse: match ielse {
+                    Some(s) => Some(Box::new(self.resolve_statement(*s)?)),
+                    None => None,
+                },
                 span,
             },
+            SpannedStatement::IfLet {
+                name,
+                expression,
+                statement,
+                ielse,
+                span,
+            } => {
+                // The unwrapped value is only seen by the first branch
+                let expression = Box::new(self.resolve_expression(*expression)?);
+                self.scopes.push(HashMap::new());
+                let name = self.bind(name, SymbolKind::Local)?;
+                let statement = Box::new(self.resolve_statement(*statement)?);
+                self.scopes.pop();
+                ResolvedStatement::IfLet {
+                    name,
+                    expression,
+                    statement,
+                    ielse: match ielse {
+                        Some(s) => Some(Box::new(self.resolve_statement(*s)?)),
+                        None => None,
+                    },
+                    span,
+                }
+            }
             SpannedStatement::Scope { body, span } => {
+                self.scopes.push(HashMap::new());
                 let mut new_body = Vec::new();
                 for st in body {
-                    new_body.push(self.resolve_statement(st));
+                    new_body.push(self.resolve_statement(st)?);
                 }
+                self.scopes.pop();
 
                 ResolvedStatement::Scope {
                     body: new_body,
                     span,
                 }
             }
-            SpannedStatement::VoidCall { name, params, span } => {
-                let mut new_params = Vec::new();
-                for p in params {
-                    new_params.push(self.resolve_expression(p));
-                }
-
-                ResolvedStatement::VoidCall {
-                    name: self.intern(*name),
-                    params: new_params,
-                    span,
-                }
-            }
+            SpannedStatement::Expression { expression, span } => ResolvedStatement::Expression {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                span,
+            },
+            SpannedStatement::Discard { expression, span } => ResolvedStatement::Discard {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                span,
+            },
             SpannedStatement::Return { expr, span } => ResolvedStatement::Return {
                 expression: match expr {
+                    Some(expr) => Some(Box::new(self.resolve_expression(*expr)?)),
                     None => None,
-                    Some(expr) => Some(Box::new(self.resolve_expression(*expr))),
                 },
                 span,
             },
-        }
+            SpannedStatement::Destructure {
+                names,
+                expression,
+                span,
+            } => {
+                let expression = Box::new(self.resolve_expression(*expression)?);
+                ResolvedStatement::Destructure {
+                    names: names
+                        .into_iter()
+                        .map(|n| self.bind(n, SymbolKind::Local))
+                        .collect::<Result<_, _>>()?,
+                    expression,
+                    span,
+                }
+            }
+            SpannedStatement::Defer { statement, span } => ResolvedStatement::Defer {
+                statement: Box::new(self.resolve_statement(*statement)?),
+                span,
+            },
+            SpannedStatement::Attributed {
+                attributes,
+                statement,
+                span,
+            } => ResolvedStatement::Attributed {
+                attributes,
+                statement: Box::new(self.resolve_statement(*statement)?),
+                span,
+            },
+        });
+    }
+
+    fn resolve_type(&mut self, t: SpannedType) -> Result<ResolvedType, ResolveError> {
+        return Ok(match t {
+            // Aliases are replaced by their target, resolved in the module the alias is in
+            SpannedType::Named(id) => {
+                let written = self.input[id.span.clone()].to_string();
+                let name = self.qualify(&written, IdentifierKind::Type);
+                if let Some(name) = &name
+                    && let Some((module, target)) = self.aliases.get(name).cloned()
+                {
+                    if self.expanding.contains(name) {
+                        return Err(ResolveError::AliasCycle(id.span));
+                    }
+                    self.expanding.push(name.clone());
+                    let outer = std::mem::replace(&mut self.module, module);
+                    let resolved = self.resolve_type(target)?;
+                    self.module = outer;
+                    self.expanding.pop();
+                    return Ok(resolved);
+                }
+                match name.and_then(|name| self.types.get(&name)) {
+                    Some(found) => ResolvedType::Named(ResolvedIdentifier {
+                        span: id.span,
+                        id: *found,
+                        kind: IdentifierKind::Type,
+                    }),
+                    None if self
+                        .scopes
+                        .iter()
+                        .any(|scope| scope.contains_key(written.as_str()))
+                        || self
+                            .qualify(&written, IdentifierKind::Value)
+                            .is_some_and(|name| self.values.contains_key(&name)) =>
+                    {
+                        return Err(ResolveError::NotAType(id.span));
+                    }
+                    None => return Err(ResolveError::UndefinedType(id.span)),
+                }
+            }
+            SpannedType::Fn { params, ret, span } => ResolvedType::Fn {
+                params: params
+                    .into_iter()
+                    .map(|p| self.resolve_type(p))
+                    .collect::<Result<_, _>>()?,
+                ret: Box::new(self.resolve_type(*ret)?),
+                span,
+            },
+            SpannedType::Pointer { inner, span } => ResolvedType::Pointer {
+                inner: Box::new(self.resolve_type(*inner)?),
+                span,
+            },
+            SpannedType::Optional { inner, span } => ResolvedType::Optional {
+                inner: Box::new(self.resolve_type(*inner)?),
+                span,
+            },
+            SpannedType::Slice { inner, span } => ResolvedType::Slice {
+                inner: Box::new(self.resolve_type(*inner)?),
+                span,
+            },
+            SpannedType::Result { ok, error, span } => ResolvedType::Result {
+                ok: Box::new(self.resolve_type(*ok)?),
+                error: Box::new(self.resolve_type(*error)?),
+                span,
+            },
+            SpannedType::Tuple { elements, span } => ResolvedType::Tuple {
+                elements: elements
+                    .into_iter()
+                    .map(|e| self.resolve_type(e))
+                    .collect::<Result<_, _>>()?,
+                span,
+            },
+        });
     }
 
-    fn resolve_expression(&mut self, ex: SpannedExpression) -> ResolvedExpression {
-        match ex {
-            SpannedExpression::Identifier(id) => ResolvedExpression::Identifier(self.intern(id)),
-            SpannedExpression::Literal { span } => {
-                todo!("{:?}", span);
-                // ResolvedExpression::Literal { span }
+    fn resolve_expression(
+        &mut self,
+        ex: SpannedExpression,
+    ) -> Result<ResolvedExpression, ResolveError> {
+        let node = ExpressionId(self.expressions);
+        self.expressions += 1;
+        return Ok(match ex {
+            SpannedExpression::Identifier(id) => {
+                ResolvedExpression::Identifier(self.lookup(id, false)?, node)
+            }
+            SpannedExpression::Literal { span } => ResolvedExpression::Literal { span, node },
+            SpannedExpression::StringLiteral { span } => {
+                ResolvedExpression::StringLiteral { span, node }
+            }
+            SpannedExpression::NoneLiteral { span } => {
+                ResolvedExpression::NoneLiteral { span, node }
             }
             SpannedExpression::UnaryOperator {
                 operation,
                 expression,
             } => ResolvedExpression::UnaryOperator {
                 operation,
-                expression: Box::new(self.resolve_expression(*expression)),
+                expression: Box::new(self.resolve_expression(*expression)?),
+                node,
             },
             SpannedExpression::BinaryOperator {
                 left,
                 span,
-                presedence,
+                operation,
                 right,
             } => ResolvedExpression::BinaryOperator {
-                left: Box::new(self.resolve_expression(*left)),
+                left: Box::new(self.resolve_expression(*left)?),
                 span,
-                presedence,
-                right: Box::new(self.resolve_expression(*right)),
+                operation,
+                right: Box::new(self.resolve_expression(*right)?),
+                node,
             },
-            SpannedExpression::Call { name, params, span } => todo!(),
-            SpannedExpression::Tmp => panic!(),
-        }
+            SpannedExpression::Call { name, params, span } => {
+                let mut new_params = Vec::new();
+                for p in params {
+                    new_params.push(self.resolve_expression(p)?);
+                }
+
+                ResolvedExpression::Call {
+                    name: self.lookup(*name, true)?,
+                    params: new_params,
+                    span,
+                    node,
+                }
+            }
+            SpannedExpression::Tuple { elements, span } => ResolvedExpression::Tuple {
+                elements: elements
+                    .into_iter()
+                    .map(|e| self.resolve_expression(e))
+                    .collect::<Result<_, _>>()?,
+                span,
+                node,
+            },
+            SpannedExpression::TupleField { tuple, index, span } => {
+                ResolvedExpression::TupleField {
+                    tuple: Box::new(self.resolve_expression(*tuple)?),
+                    index,
+                    span,
+                    node,
+                }
+            }
+            SpannedExpression::Field {
+                expression,
+                field,
+                span,
+            } => ResolvedExpression::Field {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                field: self.field(field),
+                span,
+                node,
+            },
+            SpannedExpression::Index {
+                expression,
+                index,
+                span,
+            } => ResolvedExpression::Index {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                index: Box::new(self.resolve_expression(*index)?),
+                span,
+                node,
+            },
+            SpannedExpression::Deref { expression, span } => ResolvedExpression::Deref {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                span,
+                node,
+            },
+            SpannedExpression::Try { expression, span } => ResolvedExpression::Try {
+                expression: Box::new(self.resolve_expression(*expression)?),
+                span,
+                node,
+            },
+            SpannedExpression::Orelse { left, right, span } => ResolvedExpression::Orelse {
+                left: Box::new(self.resolve_expression(*left)?),
+                right: Box::new(self.resolve_expression(*right)?),
+                span,
+                node,
+            },
+            SpannedExpression::Lambda {
+                params,
+                rtype,
+                body,
+                span,
+            } => {
+                let id = self.lambdas;
+                self.lambdas += 1;
+
+                // Lambdas still see the locals around them, for the type checker to say they
+                // can't be captured
+                self.scopes.push(HashMap::new());
+                let mut new_params = Vec::new();
+                for param in params {
+                    let name = self.bind(param.name, SymbolKind::Param)?;
+                    let ptype = self.resolve_type(param.ptype)?;
+                    new_params.push(ResolvedParam { name, ptype });
+                }
+                let rtype = self.resolve_type(rtype)?;
+                let body = Box::new(self.resolve_statement(*body)?);
+                self.scopes.pop();
+
+                ResolvedExpression::Lambda {
+                    id,
+                    params: new_params,
+                    rtype,
+                    body,
+                    span,
+                    node,
+                }
+            }
+        });
     }
 }
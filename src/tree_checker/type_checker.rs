@@ -1,21 +1,53 @@
-use std::{collections::HashMap, ops::Range, rc::Rc};
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
 
 use crate::{
     traits::TreeChecker,
     tree_checker::name_resolver::ResolvedAstTree,
     types::{
         Span,
-        resolved_types::{IdentifierId, ResolvedChunk, ResolvedExpression, ResolvedStatement},
+        resolved_types::{
+            ExpressionId, IdentifierId, IdentifierKind, ResolvedChunk, ResolvedExpression,
+            ResolvedIdentifier, ResolvedParam, ResolvedStatement, ResolvedType, SymbolKind,
+        },
+        spanned_types::BinaryOperation,
     },
 };
 
+#[derive(Default)]
 pub struct TypeChecker {
     scope: Scope,
+    // Return type of the function or lambda currently being checked
+    rtype: TypeId,
+    // Whether the statement being checked is deferred, where returning isn't allowed
+    deferring: bool,
+    // The type each distinct type is made from
+    distinct: HashMap<IdentifierId, TypeId>,
+    // The fields of each struct, by name
+    structs: HashMap<IdentifierId, HashMap<IdentifierId, TypeId>>,
+    // Functions and enum variants, which can't be assigned to
+    fixed: HashSet<IdentifierId>,
+    // Functions marked #[must_use]
+    must_use: HashSet<IdentifierId>,
+    // The field every slice has, if any field is called len
+    len: Option<IdentifierId>,
+    // What was found, for the backends to read
+    pub notes: TypeNotes,
 }
 impl TypeChecker {
     pub fn new() -> Self {
         return TypeChecker {
             scope: Scope::new(),
+            rtype: TypeId::Void,
+            deferring: false,
+            distinct: HashMap::new(),
+            structs: HashMap::new(),
+            fixed: HashSet::new(),
+            must_use: HashSet::new(),
+            len: None,
+            notes: TypeNotes::default(),
         };
     }
     pub fn debug_check_result(r: &Result<(), TypeError>, input: Rc<str>) {
@@ -45,6 +77,12 @@ impl TypeChecker {
                     &input[range.clone()]
                 )
             }
+            TypeError::UnusedResult(range) => {
+                println!(
+                    "Err: The result of a #[must_use] function is not used, discard it with _ =: {}",
+                    &input[range.clone()]
+                )
+            }
             TypeError::TypeMismatch(range) => {
                 println!(
                     "Err: Types do not match, if statements require booleans: {}",
@@ -63,23 +101,122 @@ impl TypeChecker {
                     &input[range.clone()]
                 )
             }
+            TypeError::Capture(range) => {
+                println!(
+                    "Err: Closures cannot capture local variables: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::NotOptional(range) => {
+                println!(
+                    "Err: Only optionals and results can be unwrapped: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::Unchecked(range) => {
+                println!(
+                    "Err: Optionals and results have to be unwrapped before use, with if (let v = opt), orelse or ?: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::BadTry(range) => {
+                println!(
+                    "Err: ? passes errors up to a function returning a result with the same error type: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::IgnoredError(range) => {
+                println!(
+                    "Err: Errors can't be ignored, pass them up with ?: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::NoField(range) => {
+                println!(
+                    "Err: Only structs have named fields, and tuples and distinct types numbered ones up to their length: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::NotAssignable(range) => {
+                println!(
+                    "Err: Only variables, fields, indexes and dereferences can be assigned to: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::EntryPoint(range) => {
+                println!(
+                    "Err: main must be fn main() void, fn main() i32 or fn main(args: []str) i32: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::SameResultTypes(range) => {
+                println!(
+                    "Err: A result's error type has to differ from its ok type, or values can't tell which side they are: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::RecursiveType(range) => {
+                println!(
+                    "Err: A type can only hold itself through a pointer: {}",
+                    &input[range.clone()]
+                )
+            }
+            TypeError::DeferReturn(range) => {
+                println!(
+                    "Err: Deferred statements cannot return: {}",
+                    &input[range.clone()]
+                )
+            }
         }
     }
 
+    // The types a struct or distinct type is made from
+    fn held(&self, id: IdentifierId) -> impl Iterator<Item = &TypeId> {
+        let fields = self.structs.get(&id).into_iter().flat_map(|f| f.values());
+        return fields.chain(self.distinct.get(&id));
+    }
+    // Whether a value of the type contains the target, not counting through pointers
+    fn holds(&self, t: &TypeId, target: IdentifierId, seen: &mut HashSet<IdentifierId>) -> bool {
+        match t {
+            TypeId::Named(id) if *id == target => return true,
+            TypeId::Named(id) => {
+                if !seen.insert(*id) {
+                    return false;
+                }
+                let held: Vec<&TypeId> = self.held(*id).collect();
+                return held.into_iter().any(|t| self.holds(t, target, seen));
+            }
+            TypeId::Optional(inner) => return self.holds(inner, target, seen),
+            TypeId::Result { ok, error } => {
+                return self.holds(ok, target, seen) || self.holds(error, target, seen);
+            }
+            TypeId::Tuple(elements) => {
+                return elements.iter().any(|e| self.holds(e, target, seen));
+            }
+            _ => return false,
+        }
+    }
     fn insert(&mut self, k: IdentifierId, v: TypeId) {
+        self.notes.bindings.insert(k, v.clone());
         self.scope.variables.insert(k, v);
     }
-    fn get(&self, k: &IdentifierId) -> Option<&TypeId> {
-        return self.scope.get(k);
+    fn get(&self, k: &ResolvedIdentifier) -> Result<TypeId, TypeError> {
+        match self.scope.get(&k.id, false) {
+            Some((_, true)) => return Err(TypeError::Capture(k.span.clone())),
+            Some((v, false)) => return Ok(v.clone()),
+            None => return Err(TypeError::NotDeclared(k.span.clone())),
+        }
     }
 }
 
 // Scopes
 //
-#[derive(Debug)]
+#[derive(Debug, Default)]
 struct Scope {
     variables: HashMap<IdentifierId, TypeId>,
     parent: Option<Box<Scope>>,
+    // Lambda bodies can't see the locals of the scopes around them
+    lambda: bool,
 }
 
 impl Scope {
@@ -87,6 +224,7 @@ impl Scope {
         return Scope {
             variables: HashMap::new(),
             parent: None,
+            lambda: false,
         };
     }
 
@@ -94,6 +232,10 @@ impl Scope {
         let parent = std::mem::replace(self, Scope::new());
         self.parent = Some(Box::new(parent));
     }
+    fn push_lambda(&mut self) {
+        self.push();
+        self.lambda = true;
+    }
     fn pop(&mut self) -> Option<()> {
         if let Some(s) = self.parent.take() {
             *self = *s;
@@ -101,11 +243,22 @@ impl Scope {
         }
         return None;
     }
-    fn get(&self, k: &IdentifierId) -> Option<&TypeId> {
+    // Whether the name is only declared in the root scope, and not shadowed by a local
+    fn global(&self, k: &IdentifierId) -> bool {
+        match (self.variables.contains_key(k), &self.parent) {
+            (found, None) => return found,
+            (true, Some(_)) => return false,
+            (false, Some(p)) => return p.global(k),
+        }
+    }
+    // Also returns whether the variable was found past a lambda boundary. Globals live in the
+    // root scope, so are never captured
+    fn get(&self, k: &IdentifierId, crossed: bool) -> Option<(&TypeId, bool)> {
         match (self.variables.get(k), &self.parent) {
             (None, None) => return None,
-            (None, Some(p)) => return p.get(k),
-            (v, _) => return v,
+            (None, Some(p)) => return p.get(k, crossed || self.lambda),
+            (Some(v), None) => return Some((v, false)),
+            (Some(v), Some(_)) => return Some((v, crossed)),
         }
     }
 }
@@ -118,30 +271,97 @@ pub enum TypeError {
     AssignmentMatch(Span),
     NotDeclared(Span),
     NotVoid(Span),
+    UnusedResult(Span),
     TypeMismatch(Span),
     ParamTypes(Span),
     BadReturnType(Span),
+    Capture(Span),
+    NotOptional(Span),
+    Unchecked(Span),
+    DeferReturn(Span),
+    BadTry(Span),
+    IgnoredError(Span),
+    NoField(Span),
+    NotAssignable(Span),
+    EntryPoint(Span),
+    SameResultTypes(Span),
+    RecursiveType(Span),
+}
+
+// TypeNotes
+//
+// What the type checker found, so backends don't have to work it out again. The type of every
+// expression and binding, and where values are given as another type. Expressions are keyed by
+// their ExpressionId
+#[derive(Debug, Default)]
+pub struct TypeNotes {
+    // The type of every expression, from the expression alone
+    pub expressions: HashMap<ExpressionId, TypeId>,
+    // The type of every item, param and local, by the id it is bound to
+    pub bindings: HashMap<IdentifierId, TypeId>,
+    // Expressions given as a type that is written the same in C, with the type they are given
+    // as. Literals given as i32 or u8, pointers to and from *void, and tuple literals given as a
+    // tuple type, eg (5, none) given as a (usize, ?usize)
+    pub coerced: HashMap<ExpressionId, TypeId>,
+    // Values and nones that become an optional, with the optional type they become
+    pub wrapped: HashMap<ExpressionId, TypeId>,
+    // Values that become the ok or error side of a result, with the result type they become
+    pub ok: HashMap<ExpressionId, TypeId>,
+    pub error: HashMap<ExpressionId, TypeId>,
+}
+impl TypeNotes {
+    pub fn type_of(&self, expression: &ResolvedExpression) -> &TypeId {
+        return &self.expressions[&expression.get_node()];
+    }
+    // The type after any coercion, but before being made into an optional or result
+    pub fn given_as(&self, expression: &ResolvedExpression) -> &TypeId {
+        let node = expression.get_node();
+        return self
+            .coerced
+            .get(&node)
+            .unwrap_or_else(|| &self.expressions[&node]);
+    }
 }
 
 // TypeId
 //
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Default)]
 pub enum TypeId {
+    #[default]
     Void,
     Bool,
     Usize,
+    I32,
+    U8,
+    // Structs and enums, by the id of their name
+    Named(IdentifierId),
+    Pointer(Rc<TypeId>),
+    Optional(Rc<TypeId>),
+    Result {
+        ok: Rc<TypeId>,
+        error: Rc<TypeId>,
+    },
+    Tuple(Rc<[TypeId]>),
+    // A pointer to the first element and the number of elements
+    Slice(Rc<TypeId>),
+    // The type of none, which becomes whichever optional it is used as
+    None,
     Fn {
         params: Rc<[TypeId]>,
         ret: Rc<TypeId>,
+        // C variadics take any number of extra arguments
+        variadic: bool,
     },
 }
+impl TypeId {
+    // Optional pointers can use null for none
+    pub fn nullable(&self) -> bool {
+        return matches!(self, TypeId::Pointer(_) | TypeId::Fn { .. });
+    }
+}
 impl PartialEq<TypeId> for &TypeId {
     fn eq(&self, other: &TypeId) -> bool {
-        self == &other
-    }
-
-    fn ne(&self, other: &TypeId) -> bool {
-        !self.eq(other)
+        **self == *other
     }
 }
 impl From<IdentifierId> for TypeId {
@@ -150,7 +370,38 @@ impl From<IdentifierId> for TypeId {
             0 => TypeId::Void,
             1 => TypeId::Bool,
             2 => TypeId::Usize,
-            _ => panic!(),
+            3 => TypeId::I32,
+            4 => TypeId::U8,
+            5 => TypeId::Pointer(Rc::new(TypeId::U8)),
+            _ => TypeId::Named(value),
+        }
+    }
+}
+impl From<&ResolvedType> for TypeId {
+    fn from(value: &ResolvedType) -> Self {
+        match value {
+            ResolvedType::Named(id) => TypeId::from(id.id),
+            ResolvedType::Fn { params, ret, .. } => TypeId::Fn {
+                params: params.iter().map(TypeId::from).collect(),
+                ret: Rc::new(TypeId::from(ret.as_ref())),
+                variadic: false,
+            },
+            ResolvedType::Pointer { inner, .. } => {
+                TypeId::Pointer(Rc::new(TypeId::from(inner.as_ref())))
+            }
+            ResolvedType::Optional { inner, .. } => {
+                TypeId::Optional(Rc::new(TypeId::from(inner.as_ref())))
+            }
+            ResolvedType::Slice { inner, .. } => {
+                TypeId::Slice(Rc::new(TypeId::from(inner.as_ref())))
+            }
+            ResolvedType::Result { ok, error, .. } => TypeId::Result {
+                ok: Rc::new(TypeId::from(ok.as_ref())),
+                error: Rc::new(TypeId::from(error.as_ref())),
+            },
+            ResolvedType::Tuple { elements, .. } => {
+                TypeId::Tuple(elements.iter().map(TypeId::from).collect())
+            }
         }
     }
 }
@@ -161,6 +412,11 @@ impl TreeChecker<ResolvedAstTree> for TypeChecker {
     type CheckError = TypeError;
 
     fn check(&mut self, ast: &ResolvedAstTree) -> Result<(), Self::CheckError> {
+        self.len = ast
+            .symbols
+            .iter()
+            .position(|s| s.kind == SymbolKind::Field && *s.name == *"len")
+            .map(IdentifierId);
         // Add all the types in the file
         for chunk in &ast.body {
             match chunk {
@@ -170,20 +426,97 @@ impl TreeChecker<ResolvedAstTree> for TypeChecker {
                     name,
                     params,
                     rtype,
+                    must_use,
                     ..
                 } => {
-                    let params = params.iter().map(|p| TypeId::from(p.ptype.id)).collect();
+                    let params = params.iter().map(|p| TypeId::from(&p.ptype)).collect();
                     self.insert(
                         name.id,
                         TypeId::Fn {
                             params,
-                            ret: Rc::new(TypeId::from(rtype.id)),
+                            ret: Rc::new(TypeId::from(rtype)),
+                            variadic: false,
                         },
                     );
+                    self.fixed.insert(name.id);
+                    if *must_use {
+                        self.must_use.insert(name.id);
+                    }
+                }
+                ResolvedChunk::ExternFunction {
+                    name,
+                    params,
+                    variadic,
+                    rtype,
+                    must_use,
+                    ..
+                } => {
+                    let params = params.iter().map(|p| TypeId::from(&p.ptype)).collect();
+                    self.insert(
+                        name.id,
+                        TypeId::Fn {
+                            params,
+                            ret: Rc::new(TypeId::from(rtype)),
+                            variadic: *variadic,
+                        },
+                    );
+                    self.fixed.insert(name.id);
+                    if *must_use {
+                        self.must_use.insert(name.id);
+                    }
+                }
+                ResolvedChunk::ExternStatic { name, vtype, .. } => {
+                    self.insert(name.id, TypeId::from(vtype));
+                }
+                ResolvedChunk::TypeAlias {
+                    distinct: true,
+                    name,
+                    target,
+                    ..
+                } => {
+                    self.distinct.insert(name.id, TypeId::from(target));
+                }
+                ResolvedChunk::Struct { name, fields, .. } => {
+                    let fields = fields
+                        .iter()
+                        .map(|f| (f.name.id, TypeId::from(&f.ftype)))
+                        .collect();
+                    self.structs.insert(name.id, fields);
+                }
+                ResolvedChunk::TypeAlias { .. } => {}
+                ResolvedChunk::Enum { name, variants, .. } => {
+                    for variant in variants {
+                        self.insert(variant.id, TypeId::Named(name.id));
+                        self.fixed.insert(variant.id);
+                    }
                 }
                 ResolvedChunk::EOF => panic!(),
             }
         }
+        // A type holding itself by value would be endlessly big
+        for chunk in &ast.body {
+            if let ResolvedChunk::Struct { name, .. }
+            | ResolvedChunk::TypeAlias {
+                distinct: true,
+                name,
+                ..
+            } = chunk
+                && self
+                    .held(name.id)
+                    .any(|t| self.holds(t, name.id, &mut HashSet::new()))
+            {
+                return Err(TypeError::RecursiveType(name.span.clone()));
+            }
+        }
+        if let Some(ResolvedChunk::Function {
+            name,
+            params,
+            rtype,
+            ..
+        }) = ast.entry_point()
+        {
+            check_entry_point(name, params, rtype)?;
+        }
         //Check all chunks
         for chunk in &ast.body {
             self.check_chunk(chunk)?;
@@ -199,38 +532,59 @@ impl TreeChecker<ResolvedAstTree> for TypeChecker {
 impl TypeChecker {
     fn check_chunk(&mut self, chunk: &ResolvedChunk) -> Result<(), TypeError> {
         match chunk {
+            // Only declarations, registered in check
+            ResolvedChunk::ExternFunction { params, rtype, .. } => {
+                for p in params {
+                    check_type(&p.ptype)?;
+                }
+                check_type(rtype)
+            }
+            ResolvedChunk::ExternStatic { vtype, .. } => check_type(vtype),
+            ResolvedChunk::Struct { fields, .. } => {
+                for f in fields {
+                    check_type(&f.ftype)?;
+                }
+                Ok(())
+            }
+            ResolvedChunk::TypeAlias { target, .. } => check_type(target),
+            ResolvedChunk::Enum { .. } => Ok(()),
             ResolvedChunk::Constant => todo!(),
             ResolvedChunk::StaticVar => todo!(),
             ResolvedChunk::Function {
                 body,
                 params,
                 rtype,
-                span,
                 ..
             } => {
                 self.scope.push();
-
-                params
-                    .iter()
-                    .for_each(|p| self.insert(p.name.id, TypeId::from(p.ptype.id)));
-
-                let rtype = TypeId::from(rtype.id);
-                if let Some(v) = self.check_statement(body)? {
-                    if v != rtype {
-                        return Err(TypeError::BadReturnType(span.clone()));
-                    }
-                }
-
+                self.check_function(params, rtype, body)?;
                 self.scope.pop().unwrap();
                 Ok(())
             }
             ResolvedChunk::EOF => todo!(),
         }
     }
-    fn check_statement(
+    // Checks a function or lambda body in an already pushed scope
+    fn check_function(
         &mut self,
-        statement: &ResolvedStatement,
-    ) -> Result<Option<&TypeId>, TypeError> {
+        params: &[ResolvedParam],
+        rtype: &ResolvedType,
+        body: &ResolvedStatement,
+    ) -> Result<(), TypeError> {
+        for p in params {
+            check_type(&p.ptype)?;
+            self.insert(p.name.id, TypeId::from(&p.ptype));
+        }
+        check_type(rtype)?;
+
+        let outer = std::mem::replace(&mut self.rtype, TypeId::from(rtype));
+        let deferring = std::mem::replace(&mut self.deferring, false);
+        self.check_statement(body)?;
+        self.rtype = outer;
+        self.deferring = deferring;
+        Ok(())
+    }
+    fn check_statement(&mut self, statement: &ResolvedStatement) -> Result<(), TypeError> {
         match statement {
             ResolvedStatement::Decleration {
                 name,
@@ -238,25 +592,29 @@ impl TypeChecker {
                 rtype,
                 span,
             } => {
-                if &TypeId::from(rtype.id) != self.check_expression(expression)? {
+                check_type(rtype)?;
+                let rtype = TypeId::from(rtype);
+                if !self.check_value(expression, &rtype)? {
                     return Err(TypeError::DeclarationMatch(span.clone()));
                 }
-                self.insert(name.id, TypeId::from(rtype.id));
-                Ok(None)
+                self.insert(name.id, rtype);
+                Ok(())
             }
             ResolvedStatement::Reassignment {
-                name,
+                target,
                 expression,
+                operation,
                 span,
             } => {
-                let dtype = self.get(&name.id);
-                if dtype.is_none() {
-                    return Err(TypeError::NotDeclared(span.clone()));
+                let dtype = self.check_place(target)?;
+                // Compound assignments like += need the value unwrapped first
+                if matches!(dtype, TypeId::Optional(_)) && operation.is_some() {
+                    return Err(TypeError::Unchecked(span.clone()));
                 }
-                if dtype.unwrap() != self.check_expression(expression)? {
+                if !self.check_value(expression, &dtype)? {
                     return Err(TypeError::AssignmentMatch(span.clone()));
                 }
-                Ok(None)
+                Ok(())
             }
             ResolvedStatement::If {
                 condition,
@@ -264,7 +622,7 @@ impl TypeChecker {
                 ielse,
                 span,
             } => {
-                if self.get(&IdentifierId(1)).unwrap() != self.check_expression(&condition)? {
+                if self.check_expression(condition)? != TypeId::Bool {
                     return Err(TypeError::TypeMismatch(span.clone()));
                 }
                 self.check_statement(statement)?;
@@ -272,7 +630,31 @@ impl TypeChecker {
                     self.check_statement(st)?;
                 }
 
-                Ok(None)
+                Ok(())
+            }
+            ResolvedStatement::IfLet {
+                name,
+                expression,
+                statement,
+                ielse,
+                span,
+            } => {
+                let otype = self.check_expression(expression)?;
+                let inner;
+                match &otype {
+                    TypeId::Optional(t) | TypeId::Result { ok: t, .. } => inner = (**t).clone(),
+                    _ => return Err(TypeError::NotOptional(span.clone())),
+                }
+
+                self.scope.push();
+                self.insert(name.id, inner);
+                self.check_statement(statement)?;
+                self.scope.pop().unwrap();
+                if let Some(st) = ielse {
+                    self.check_statement(st)?;
+                }
+
+                Ok(())
             }
             ResolvedStatement::Scope { body, .. } => {
                 self.scope.push();
@@ -281,70 +663,442 @@ impl TypeChecker {
                 }
                 self.scope.pop().unwrap();
 
-                Ok(None)
+                Ok(())
             }
-            ResolvedStatement::VoidCall { name, params, span } => {
-                //TODO: Check this
-                let tid;
-                match self.get(&name.id) {
-                    Some(type_id) => tid = type_id,
-                    None => return Err(TypeError::NotDeclared(span.clone())),
+            ResolvedStatement::Expression { expression, span } => {
+                let etype = self.check_expression(expression)?;
+                if let TypeId::Result { .. } = etype {
+                    return Err(TypeError::IgnoredError(span.clone()));
                 }
-
-                let def_ret;
-                let def_params;
-                match tid {
-                    TypeId::Fn { params, ret } => {
-                        def_ret = ret;
-                        def_params = params;
-                    }
-                    _ => return Err(TypeError::TypeMismatch(span.clone())),
+                // Looks through ?, as the ok value of write(f)? is thrown away too
+                let mut call = expression.as_ref();
+                while let ResolvedExpression::Try { expression, .. } = call {
+                    call = expression;
                 }
-
-                if **def_ret != TypeId::Void {
-                    return Err(TypeError::NotVoid(span.clone()));
-                }
-
-                let mut call_params = Vec::new();
-                for p in params {
-                    call_params.push(self.check_expression(p)?);
+                if let ResolvedExpression::Call { name, .. } = call
+                    && self.must_use.contains(&name.id)
+                    && self.scope.global(&name.id)
+                    && etype != TypeId::Void
+                {
+                    return Err(TypeError::UnusedResult(span.clone()));
                 }
-                if *call_params != **def_params {
-                    return Err(TypeError::ParamTypes(span.clone()));
+                Ok(())
+            }
+            // Anything can be thrown away on purpose, even errors
+            ResolvedStatement::Discard { expression, .. } => {
+                self.check_expression(expression)?;
+                Ok(())
+            }
+            ResolvedStatement::Destructure {
+                names,
+                expression,
+                span,
+            } => {
+                let ttype = self.check_expression(expression)?;
+                match &ttype {
+                    TypeId::Tuple(elements) if elements.len() == names.len() => {
+                        for (name, t) in names.iter().zip(elements.iter()) {
+                            self.insert(name.id, t.clone());
+                        }
+                    }
+                    _ => return Err(TypeError::DeclarationMatch(span.clone())),
                 }
-                Ok(None)
+                Ok(())
             }
-            ResolvedStatement::Return { expression, .. } => {
-                if let Some(expression) = expression {
-                    Ok(Some(self.check_expression(expression)?))
-                } else {
-                    Ok(Some(&TypeId::Void))
+            ResolvedStatement::Return { expression, span } => {
+                if self.deferring {
+                    return Err(TypeError::DeferReturn(span.clone()));
+                }
+                let matches = match expression {
+                    Some(expression) => self.check_value(expression, &self.rtype.clone())?,
+                    // Functions returning void!E succeed with a bare return
+                    None => match &self.rtype {
+                        TypeId::Result { ok, .. } => **ok == TypeId::Void,
+                        rtype => *rtype == TypeId::Void,
+                    },
+                };
+                if !matches {
+                    return Err(TypeError::BadReturnType(span.clone()));
                 }
+                Ok(())
+            }
+            ResolvedStatement::Attributed { statement, .. } => self.check_statement(statement),
+            ResolvedStatement::Defer { statement, .. } => {
+                let outer = std::mem::replace(&mut self.deferring, true);
+                self.check_statement(statement)?;
+                self.deferring = outer;
+                Ok(())
             }
         }
     }
-    fn check_expression(&self, expression: &ResolvedExpression) -> Result<&TypeId, TypeError> {
+    // The type of an expression, noted down for the backends
+    fn check_expression(&mut self, expression: &ResolvedExpression) -> Result<TypeId, TypeError> {
+        let etype = self.expression_type(expression)?;
+        self.notes
+            .expressions
+            .insert(expression.get_node(), etype.clone());
+        return Ok(etype);
+    }
+    fn expression_type(&mut self, expression: &ResolvedExpression) -> Result<TypeId, TypeError> {
         match expression {
-            ResolvedExpression::Identifier(resolved_identifier) => self
-                .get(&resolved_identifier.id)
-                .ok_or(TypeError::NotDeclared(resolved_identifier.span.clone())),
-            ResolvedExpression::Literal { span } => todo!(),
+            ResolvedExpression::Identifier(resolved_identifier, _) => self.get(resolved_identifier),
+            // TODO: Literal types other than usize
+            ResolvedExpression::Literal { .. } => Ok(TypeId::Usize),
+            ResolvedExpression::StringLiteral { .. } => Ok(TypeId::Pointer(Rc::new(TypeId::U8))),
+            ResolvedExpression::NoneLiteral { .. } => Ok(TypeId::None),
             ResolvedExpression::UnaryOperator { expression, .. } => {
                 self.check_expression(expression)
             }
             ResolvedExpression::BinaryOperator {
                 left,
                 right,
-                presedence,
+                operation,
                 span,
+                ..
             } => {
                 // TODO: Add proper type checking here
                 let left = self.check_expression(left)?;
                 let right = self.check_expression(right)?;
+                for t in [&left, &right] {
+                    if matches!(
+                        t,
+                        TypeId::Optional(_) | TypeId::Result { .. } | TypeId::None
+                    ) {
+                        return Err(TypeError::Unchecked(span.clone()));
+                    }
+                }
+                if *operation == BinaryOperation::Equal {
+                    return Ok(TypeId::Bool);
+                }
                 return Ok(left);
             }
-            ResolvedExpression::Call => todo!(),
-            ResolvedExpression::Tmp => panic!(),
+            ResolvedExpression::Call {
+                name, params, span, ..
+            } => self.check_call(name, params, span),
+            ResolvedExpression::Tuple { elements, .. } => {
+                let mut types = Vec::new();
+                for element in elements {
+                    types.push(self.check_expression(element)?);
+                }
+                Ok(TypeId::Tuple(types.into()))
+            }
+            ResolvedExpression::TupleField {
+                tuple, index, span, ..
+            } => {
+                match self.check_expression(tuple)? {
+                    TypeId::Tuple(elements) if *index < elements.len() => {
+                        Ok(elements[*index].clone())
+                    }
+                    // The value a distinct type is made from, eg m.0
+                    TypeId::Named(id) if *index == 0 && self.distinct.contains_key(&id) => {
+                        Ok(self.distinct[&id].clone())
+                    }
+                    TypeId::Optional(_) | TypeId::Result { .. } => {
+                        Err(TypeError::Unchecked(span.clone()))
+                    }
+                    _ => Err(TypeError::NoField(span.clone())),
+                }
+            }
+            ResolvedExpression::Field {
+                expression,
+                field,
+                span,
+                ..
+            } => {
+                let stype = self.check_expression(expression)?;
+                let id;
+                match &stype {
+                    TypeId::Slice(_) if Some(field.id) == self.len => return Ok(TypeId::Usize),
+                    TypeId::Named(i) => id = *i,
+                    // Read through the pointer, like C's ->
+                    TypeId::Pointer(inner) => match **inner {
+                        TypeId::Named(i) => id = i,
+                        _ => return Err(TypeError::NoField(span.clone())),
+                    },
+                    TypeId::Optional(_) | TypeId::Result { .. } => {
+                        return Err(TypeError::Unchecked(span.clone()));
+                    }
+                    _ => return Err(TypeError::NoField(span.clone())),
+                }
+                match self.structs.get(&id).and_then(|f| f.get(&field.id)) {
+                    Some(t) => Ok(t.clone()),
+                    None => Err(TypeError::NoField(span.clone())),
+                }
+            }
+            ResolvedExpression::Index {
+                expression,
+                index,
+                span,
+                ..
+            } => {
+                let element;
+                match self.check_expression(expression)? {
+                    TypeId::Pointer(inner) | TypeId::Slice(inner) => element = (*inner).clone(),
+                    TypeId::Optional(_) => return Err(TypeError::Unchecked(span.clone())),
+                    _ => return Err(TypeError::TypeMismatch(span.clone())),
+                }
+                match self.check_expression(index)? {
+                    TypeId::Usize | TypeId::I32 | TypeId::U8 => Ok(element),
+                    _ => Err(TypeError::TypeMismatch(index.get_span())),
+                }
+            }
+            ResolvedExpression::Deref {
+                expression, span, ..
+            } => match self.check_expression(expression)? {
+                TypeId::Pointer(inner) => Ok((*inner).clone()),
+                TypeId::Optional(_) => Err(TypeError::Unchecked(span.clone())),
+                _ => Err(TypeError::TypeMismatch(span.clone())),
+            },
+            ResolvedExpression::Try {
+                expression, span, ..
+            } => self.check_try(expression, span),
+            ResolvedExpression::Orelse {
+                left, right, span, ..
+            } => {
+                let otype = self.check_expression(left)?;
+                let inner;
+                match &otype {
+                    TypeId::Optional(t) | TypeId::Result { ok: t, .. } => inner = (**t).clone(),
+                    _ => return Err(TypeError::NotOptional(span.clone())),
+                }
+                if !self.check_value(right, &inner)? {
+                    return Err(TypeError::TypeMismatch(span.clone()));
+                }
+                Ok(inner)
+            }
+            ResolvedExpression::Lambda {
+                params,
+                rtype,
+                body,
+                ..
+            } => {
+                self.scope.push_lambda();
+                self.check_function(params, rtype, body)?;
+                self.scope.pop().unwrap();
+
+                Ok(TypeId::Fn {
+                    params: params.iter().map(|p| TypeId::from(&p.ptype)).collect(),
+                    ret: Rc::new(TypeId::from(rtype)),
+                    variadic: false,
+                })
+            }
+        }
+    }
+    // Either a function, or a variable holding a function pointer
+    fn check_call(
+        &mut self,
+        name: &ResolvedIdentifier,
+        params: &[ResolvedExpression],
+        span: &Span,
+    ) -> Result<TypeId, TypeError> {
+        // Distinct types are made by calling them, eg Meters(5)
+        if name.kind == IdentifierKind::Type {
+            let base = self.distinct[&name.id].clone();
+            if params.len() != 1 || !self.check_value(&params[0], &base)? {
+                return Err(TypeError::ParamTypes(span.clone()));
+            }
+            return Ok(TypeId::Named(name.id));
+        }
+
+        let def_params;
+        let def_ret;
+        let def_variadic;
+        match self.get(name)? {
+            TypeId::Fn {
+                params,
+                ret,
+                variadic,
+            } => {
+                def_params = params;
+                def_ret = ret;
+                def_variadic = variadic;
+            }
+            // Function pointers that might be null
+            TypeId::Optional(_) => return Err(TypeError::Unchecked(span.clone())),
+            _ => return Err(TypeError::TypeMismatch(span.clone())),
+        }
+
+        if params.len() < def_params.len() || (params.len() > def_params.len() && !def_variadic) {
+            return Err(TypeError::ParamTypes(span.clone()));
+        }
+        for (p, t) in params.iter().zip(def_params.iter()) {
+            if !self.check_value(p, t)? {
+                return Err(TypeError::ParamTypes(span.clone()));
+            }
+        }
+        // Extra variadic arguments can be anything C can take
+        for p in &params[def_params.len()..] {
+            if matches!(self.check_expression(p)?, TypeId::Void | TypeId::None) {
+                return Err(TypeError::ParamTypes(span.clone()));
+            }
+        }
+        Ok((*def_ret).clone())
+    }
+    // The type of something being assigned to. Variables, and what is reached from them, can be
+    // changed, as can anything behind a pointer
+    fn check_place(&mut self, place: &ResolvedExpression) -> Result<TypeId, TypeError> {
+        match place {
+            ResolvedExpression::Identifier(id, _)
+                if self.fixed.contains(&id.id) && self.scope.global(&id.id) =>
+            {
+                return Err(TypeError::NotAssignable(id.span.clone()));
+            }
+            ResolvedExpression::Identifier(_, _)
+            | ResolvedExpression::Index { .. }
+            | ResolvedExpression::Deref { .. } => {}
+            // Fields through a pointer are always behind one
+            ResolvedExpression::Field { expression, .. } => {
+                match self.check_expression(expression)? {
+                    TypeId::Pointer(_) => {}
+                    // The length of a slice is fixed by what it points to
+                    TypeId::Slice(_) => return Err(TypeError::NotAssignable(place.get_span())),
+                    _ => _ = self.check_place(expression)?,
+                }
+            }
+            ResolvedExpression::TupleField { tuple, .. } => {
+                self.check_place(tuple)?;
+            }
+            _ => return Err(TypeError::NotAssignable(place.get_span())),
         }
+        return self.check_expression(place);
+    }
+    // The ok value of a result, returning early with its error
+    fn check_try(
+        &mut self,
+        expression: &ResolvedExpression,
+        span: &Span,
+    ) -> Result<TypeId, TypeError> {
+        if self.deferring {
+            return Err(TypeError::DeferReturn(span.clone()));
+        }
+        let rtype = self.check_expression(expression)?;
+        let ok;
+        let error;
+        match &rtype {
+            TypeId::Result { ok: o, error: e } => {
+                ok = (**o).clone();
+                error = e.clone();
+            }
+            _ => return Err(TypeError::NotOptional(span.clone())),
+        }
+        match &self.rtype {
+            TypeId::Result { error: e, .. } if *e == error => {}
+            _ => return Err(TypeError::BadTry(span.clone())),
+        }
+        return Ok(ok);
+    }
+    // Checks an expression given where a `target` is expected. Values and none become optionals,
+    // and values or errors become results, of their own accord
+    fn check_value(
+        &mut self,
+        expression: &ResolvedExpression,
+        target: &TypeId,
+    ) -> Result<bool, TypeError> {
+        let node = expression.get_node();
+        // Each element of a tuple literal is given as the element it becomes, eg (5, none)
+        if let (ResolvedExpression::Tuple { elements, .. }, TypeId::Tuple(targets)) =
+            (expression, target)
+            && elements.len() == targets.len()
+        {
+            for (element, t) in elements.iter().zip(targets.iter()) {
+                if !self.check_value(element, t)? {
+                    return Ok(false);
+                }
+            }
+            let types = elements.iter().map(|e| self.notes.type_of(e).clone());
+            let ttype = TypeId::Tuple(types.collect());
+            if ttype != *target {
+                self.notes.coerced.insert(node, target.clone());
+            }
+            self.notes.expressions.insert(node, ttype);
+            return Ok(true);
+        }
+        let vtype = self.check_expression(expression)?;
+        if vtype == *target {
+            return Ok(true);
+        }
+        // Literals are all usize for now, so they can be given as any integer
+        if let ResolvedExpression::Literal { .. } = expression
+            && matches!(target, TypeId::I32 | TypeId::U8)
+        {
+            self.notes.coerced.insert(node, target.clone());
+            return Ok(true);
+        }
+        // As in C, *void is given as any pointer and any pointer as *void, eg what malloc returns
+        if let (TypeId::Pointer(from), TypeId::Pointer(to)) = (&vtype, target)
+            && (**from == TypeId::Void || **to == TypeId::Void)
+        {
+            self.notes.coerced.insert(node, target.clone());
+            return Ok(true);
+        }
+        if let TypeId::Optional(inner) = target
+            && (vtype == TypeId::None || vtype == **inner)
+        {
+            self.notes.wrapped.insert(node, target.clone());
+            return Ok(true);
+        }
+        // A value is taken as ok, if it could be either
+        if let TypeId::Result { ok, error } = target {
+            if vtype == **ok {
+                self.notes.ok.insert(node, target.clone());
+                return Ok(true);
+            }
+            if vtype == **error {
+                self.notes.error.insert(node, target.clone());
+                return Ok(true);
+            }
+        }
+        return Ok(false);
+    }
+}
+
+// Values become whichever side of a result their type matches, so T!T can't be told apart
+fn check_type(rtype: &ResolvedType) -> Result<(), TypeError> {
+    match rtype {
+        ResolvedType::Named(_) => return Ok(()),
+        ResolvedType::Pointer { inner, .. }
+        | ResolvedType::Optional { inner, .. }
+        | ResolvedType::Slice { inner, .. } => {
+            return check_type(inner);
+        }
+        ResolvedType::Tuple { elements, .. } => {
+            for e in elements {
+                check_type(e)?;
+            }
+            return Ok(());
+        }
+        ResolvedType::Fn { params, ret, .. } => {
+            for p in params {
+                check_type(p)?;
+            }
+            return check_type(ret);
+        }
+        ResolvedType::Result { ok, error, span } => {
+            if TypeId::from(ok.as_ref()) == TypeId::from(error.as_ref()) {
+                return Err(TypeError::SameResultTypes(span.clone()));
+            }
+            check_type(ok)?;
+            return check_type(error);
+        }
+    }
+}
+
+// C calls main through a wrapper, which passes the arguments on as a slice of strings and
+// returns the exit status
+fn check_entry_point(
+    name: &ResolvedIdentifier,
+    params: &[ResolvedParam],
+    rtype: &ResolvedType,
+) -> Result<(), TypeError> {
+    let args = TypeId::Slice(Rc::new(TypeId::Pointer(Rc::new(TypeId::U8))));
+    let params: Vec<TypeId> = params.iter().map(|p| TypeId::from(&p.ptype)).collect();
+    let valid = match (params.as_slice(), TypeId::from(rtype)) {
+        ([], TypeId::Void | TypeId::I32) => true,
+        ([p], TypeId::I32) => *p == args,
+        _ => false,
+    };
+    if !valid {
+        return Err(TypeError::EntryPoint(name.span.clone()));
     }
+    return Ok(());
 }
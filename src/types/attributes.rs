@@ -0,0 +1,85 @@
+use super::Span;
+
+use AttributeTarget::*;
+
+// #[name] or #[name(value)], where the value is a string or number. Kept by both trees, as
+// there is nothing in them to resolve
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attribute {
+    pub name: Span,
+    pub value: Option<Span>,
+    pub span: Span,
+}
+
+// What an attribute is written on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Function,
+    ExternFunction,
+    ExternStatic,
+    Struct,
+    Enum,
+    TypeAlias,
+    Field,
+    Let,
+    Statement,
+}
+
+// The attributes passed on to C, with what they can be written on and whether they take a value.
+// include and export_name change the declaration itself, so are kept apart
+const KNOWN: &[(&str, &[AttributeTarget], AttributeValue)] = &[
+    ("inline", &[Function], AttributeValue::None),
+    ("cold", &[Function, ExternFunction], AttributeValue::None),
+    ("packed", &[Struct, Enum, Field], AttributeValue::None),
+    (
+        "align",
+        &[Function, ExternStatic, Struct, TypeAlias, Field, Let],
+        AttributeValue::Number,
+    ),
+    (
+        "deprecated",
+        &[
+            Function,
+            ExternFunction,
+            ExternStatic,
+            Struct,
+            Enum,
+            TypeAlias,
+            Field,
+            Let,
+        ],
+        AttributeValue::Optional,
+    ),
+];
+
+// Whether an attribute takes a value, eg #[align(16)] or #[deprecated("use bar")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeValue {
+    None,
+    Optional,
+    Number,
+}
+
+impl AttributeTarget {
+    pub fn describe(&self) -> &'static str {
+        return match self {
+            Function => "functions",
+            ExternFunction => "extern functions",
+            ExternStatic => "extern statics",
+            Struct => "structs",
+            Enum => "enums",
+            TypeAlias => "type aliases",
+            Field => "struct fields",
+            Let => "let statements",
+            Statement => "statements other than let",
+        };
+    }
+}
+
+// None for attributes notc doesn't know
+pub fn attribute_targets(name: &str) -> Option<(&'static [AttributeTarget], AttributeValue)> {
+    return KNOWN
+        .iter()
+        .find(|(known, _, _)| *known == name)
+        .map(|(_, targets, value)| (*targets, *value));
+}
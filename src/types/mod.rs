@@ -1,8 +1,10 @@
+mod attributes;
 pub mod resolved_types;
 pub mod spanned_types;
 mod tokens;
 
 use std::ops::Range;
 
+pub use attributes::{Attribute, AttributeTarget, AttributeValue, attribute_targets};
 pub use tokens::Token;
 pub type Span = Range<usize>;
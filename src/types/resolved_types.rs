@@ -1,50 +1,216 @@
-use super::Span;
-use std::ops::Range;
+use std::rc::Rc;
+
+use super::{Attribute, Span, spanned_types::BinaryOperation};
 // Resolved Nodes
 //
+// Each binding gets its own id, which every use of it shares
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct IdentifierId(pub usize);
+// Each expression gets its own id too, so passes can note things about it for later ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExpressionId(pub usize);
+
+// What an IdentifierId is bound to, as recorded by the name resolver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    // As written for locals and params, and the full path of module items, eg math::add
+    pub name: Rc<str>,
+    pub kind: SymbolKind,
+    // Where it is bound. The primitive types have nowhere
+    pub span: Option<Span>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    // Functions, including extern ones
+    Function,
+    // Extern statics
+    Static,
+    Param,
+    Local,
+    // Structs, enums, distinct types and the primitive types
+    Type,
+    Variant,
+    // Fields are found through the type of what they are read from, so every field with the
+    // same name shares one symbol
+    Field,
+}
+impl SymbolKind {
+    pub fn namespace(&self) -> IdentifierKind {
+        return match self {
+            SymbolKind::Type => IdentifierKind::Type,
+            SymbolKind::Field => IdentifierKind::Field,
+            _ => IdentifierKind::Value,
+        };
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ResolvedIdentifier {
     pub span: Span,
     pub id: IdentifierId,
+    pub kind: IdentifierKind,
 }
-#[derive(Debug, Clone)]
-pub struct ResolvedOperator {
-    pub span: Span,
-    pub presedence: usize,
+// The namespace an identifier was found in. Types and values are apart, so a struct and a
+// function can share a name, as in C. Calling a distinct type names it as a type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdentifierKind {
+    Value,
+    Type,
+    Field,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolvedType {
+    Named(ResolvedIdentifier),
+    Fn {
+        params: Vec<ResolvedType>,
+        ret: Box<ResolvedType>,
+        span: Span,
+    },
+    Pointer {
+        inner: Box<ResolvedType>,
+        span: Span,
+    },
+    // ?T, either a T or none. Optional pointers are nullable, plain ones are not
+    Optional {
+        inner: Box<ResolvedType>,
+        span: Span,
+    },
+    // []T, a pointer to the first of len Ts
+    Slice {
+        inner: Box<ResolvedType>,
+        span: Span,
+    },
+    // (T, U)
+    Tuple {
+        elements: Vec<ResolvedType>,
+        span: Span,
+    },
+    // T!E, either a T or an error E
+    Result {
+        ok: Box<ResolvedType>,
+        error: Box<ResolvedType>,
+        span: Span,
+    },
+}
+impl ResolvedType {
+    pub fn get_span(&self) -> Span {
+        return match self {
+            ResolvedType::Named(id) => id.span.clone(),
+            ResolvedType::Fn { span, .. } => span.clone(),
+            ResolvedType::Pointer { span, .. } => span.clone(),
+            ResolvedType::Optional { span, .. } => span.clone(),
+            ResolvedType::Result { span, .. } => span.clone(),
+            ResolvedType::Tuple { span, .. } => span.clone(),
+            ResolvedType::Slice { span, .. } => span.clone(),
+        };
+    }
+}
+#[derive(Debug, PartialEq, Eq)]
 pub struct ResolvedParam {
     pub name: ResolvedIdentifier,
-    pub ptype: ResolvedIdentifier,
+    pub ptype: ResolvedType,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct ResolvedField {
+    pub name: ResolvedIdentifier,
+    pub ftype: ResolvedType,
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<Span>,
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ResolvedChunk {
+    // Declarations of C functions and variables, optionally brought in with #[include(...)]
+    ExternFunction {
+        public: bool,
+        attributes: Vec<Attribute>,
+        // The /// comments above it, as the text after each ///
+        docs: Vec<Span>,
+        must_use: bool,
+        name: ResolvedIdentifier,
+        params: Vec<ResolvedParam>,
+        variadic: bool,
+        rtype: ResolvedType,
+        include: Option<Span>,
+        span: Span,
+    },
+    ExternStatic {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        name: ResolvedIdentifier,
+        vtype: ResolvedType,
+        include: Option<Span>,
+        span: Span,
+    },
+    // Types marked extern keep their C names, and are not defined again when they come from
+    // an #[include(...)]
+    Struct {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        external: bool,
+        include: Option<Span>,
+        // The C spelling of the type, eg "struct tm"
+        export: Option<Span>,
+        name: ResolvedIdentifier,
+        fields: Vec<ResolvedField>,
+        span: Span,
+    },
+    Enum {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        external: bool,
+        include: Option<Span>,
+        export: Option<Span>,
+        name: ResolvedIdentifier,
+        variants: Vec<ResolvedIdentifier>,
+        span: Span,
+    },
+    // Only distinct types are left by the name resolver, the rest are replaced by their target
+    TypeAlias {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        distinct: bool,
+        name: ResolvedIdentifier,
+        target: ResolvedType,
+        span: Span,
+    },
     Constant,
     StaticVar,
     Function {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        // The C symbol to use instead of the mangled name, from #[export_name(...)]
+        export: Option<Span>,
+        // From #[must_use], when the result can't be thrown away without _ =
+        must_use: bool,
         name: ResolvedIdentifier,
         params: Vec<ResolvedParam>,
-        rtype: ResolvedIdentifier,
+        rtype: ResolvedType,
         body: ResolvedStatement,
         span: Span,
     },
     EOF,
 }
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq)]
 pub enum ResolvedStatement {
     Decleration {
         name: ResolvedIdentifier,
         expression: Box<ResolvedExpression>,
-        rtype: ResolvedIdentifier,
+        rtype: ResolvedType,
         span: Span,
     },
+    // Assignments to a place, eg x = 1, p.x += 2, arr[i] = 3 or *ptr = v. Compound ones have the
+    // operation they do first, and the span is the operator's
     Reassignment {
-        name: ResolvedIdentifier,
+        target: Box<ResolvedExpression>,
         expression: Box<ResolvedExpression>,
+        operation: Option<BinaryOperation>,
         span: Span,
     },
     If {
@@ -53,36 +219,172 @@ pub enum ResolvedStatement {
         ielse: Option<Box<ResolvedStatement>>,
         span: Span,
     },
+    IfLet {
+        name: ResolvedIdentifier,
+        expression: Box<ResolvedExpression>,
+        statement: Box<ResolvedStatement>,
+        ielse: Option<Box<ResolvedStatement>>,
+        span: Span,
+    },
     Scope {
         body: Vec<ResolvedStatement>,
         span: Span,
     },
-    VoidCall {
-        name: ResolvedIdentifier,
-        params: Vec<ResolvedExpression>,
+    // Any expression, eg increment(counter); or write(f)?;
+    Expression {
+        expression: Box<ResolvedExpression>,
+        span: Span,
+    },
+    // Throws a value away on purpose, eg _ = increment(counter);
+    Discard {
+        expression: Box<ResolvedExpression>,
         span: Span,
     },
     Return {
         expression: Option<Box<ResolvedExpression>>,
         span: Span,
     },
+    // let (a, b) = pair;
+    Destructure {
+        names: Vec<ResolvedIdentifier>,
+        expression: Box<ResolvedExpression>,
+        span: Span,
+    },
+    // Runs the statement when the enclosing scope is left
+    Defer {
+        statement: Box<ResolvedStatement>,
+        span: Span,
+    },
+    // A statement with attributes, eg #[align(16)] let buf: usize = 0;
+    Attributed {
+        attributes: Vec<Attribute>,
+        statement: Box<ResolvedStatement>,
+        span: Span,
+    },
 }
 #[derive(Debug, PartialEq, Eq)]
 pub enum ResolvedExpression {
-    Identifier(ResolvedIdentifier),
+    Identifier(ResolvedIdentifier, ExpressionId),
     Literal {
         span: Span,
+        node: ExpressionId,
+    },
+    StringLiteral {
+        span: Span,
+        node: ExpressionId,
+    },
+    NoneLiteral {
+        span: Span,
+        node: ExpressionId,
     },
     UnaryOperator {
         operation: Span,
         expression: Box<ResolvedExpression>,
+        node: ExpressionId,
     },
     BinaryOperator {
         left: Box<ResolvedExpression>,
         span: Span,
-        presedence: usize,
+        operation: BinaryOperation,
+        right: Box<ResolvedExpression>,
+        node: ExpressionId,
+    },
+    Call {
+        name: ResolvedIdentifier,
+        params: Vec<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    Tuple {
+        elements: Vec<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    // p.x, through a pointer if p is one. The span is the field's
+    Field {
+        expression: Box<ResolvedExpression>,
+        field: ResolvedIdentifier,
+        span: Span,
+        node: ExpressionId,
+    },
+    // ptr[i], where the span covers the brackets
+    Index {
+        expression: Box<ResolvedExpression>,
+        index: Box<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    // *ptr, where the span is the *
+    Deref {
+        expression: Box<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    TupleField {
+        tuple: Box<ResolvedExpression>,
+        index: usize,
+        span: Span,
+        node: ExpressionId,
+    },
+    Try {
+        expression: Box<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    Orelse {
+        left: Box<ResolvedExpression>,
         right: Box<ResolvedExpression>,
+        span: Span,
+        node: ExpressionId,
+    },
+    // Lambdas are numbered so the backend can lift them to uniquely named functions
+    Lambda {
+        id: usize,
+        params: Vec<ResolvedParam>,
+        rtype: ResolvedType,
+        body: Box<ResolvedStatement>,
+        span: Span,
+        node: ExpressionId,
     },
-    Call,
-    Tmp,
+}
+impl ResolvedExpression {
+    pub fn get_node(&self) -> ExpressionId {
+        return match self {
+            ResolvedExpression::Identifier(_, node)
+            | ResolvedExpression::Literal { node, .. }
+            | ResolvedExpression::StringLiteral { node, .. }
+            | ResolvedExpression::NoneLiteral { node, .. }
+            | ResolvedExpression::UnaryOperator { node, .. }
+            | ResolvedExpression::BinaryOperator { node, .. }
+            | ResolvedExpression::Call { node, .. }
+            | ResolvedExpression::Tuple { node, .. }
+            | ResolvedExpression::TupleField { node, .. }
+            | ResolvedExpression::Field { node, .. }
+            | ResolvedExpression::Index { node, .. }
+            | ResolvedExpression::Deref { node, .. }
+            | ResolvedExpression::Try { node, .. }
+            | ResolvedExpression::Orelse { node, .. }
+            | ResolvedExpression::Lambda { node, .. } => *node,
+        };
+    }
+    // Where the expression is written, for errors
+    pub fn get_span(&self) -> Span {
+        return match self {
+            ResolvedExpression::Identifier(id, _) => id.span.clone(),
+            ResolvedExpression::Literal { span, .. } => span.clone(),
+            ResolvedExpression::StringLiteral { span, .. } => span.clone(),
+            ResolvedExpression::NoneLiteral { span, .. } => span.clone(),
+            ResolvedExpression::UnaryOperator { operation, .. } => operation.clone(),
+            ResolvedExpression::BinaryOperator { span, .. } => span.clone(),
+            ResolvedExpression::Call { span, .. } => span.clone(),
+            ResolvedExpression::Tuple { span, .. } => span.clone(),
+            ResolvedExpression::TupleField { span, .. } => span.clone(),
+            ResolvedExpression::Field { span, .. } => span.clone(),
+            ResolvedExpression::Index { span, .. } => span.clone(),
+            ResolvedExpression::Deref { span, .. } => span.clone(),
+            ResolvedExpression::Try { span, .. } => span.clone(),
+            ResolvedExpression::Orelse { span, .. } => span.clone(),
+            ResolvedExpression::Lambda { span, .. } => span.clone(),
+        };
+    }
 }
@@ -1,4 +1,4 @@
-use super::Span;
+use super::{Attribute, Span};
 
 // Spanned Nodes
 //
@@ -6,41 +6,196 @@ use super::Span;
 pub struct SpannedIdentifier {
     pub span: Span,
 }
+// How tightly each operator binds is up to the parser
 #[derive(Debug, Clone)]
 pub struct SpannedOperator {
     pub span: Span,
-    pub presedence: usize,
 }
-#[derive(Debug)]
+// What a binary operator does, so later passes don't go back to its text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOperation {
+    Equal,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpannedType {
+    Named(SpannedIdentifier),
+    Fn {
+        params: Vec<SpannedType>,
+        ret: Box<SpannedType>,
+        span: Span,
+    },
+    Pointer {
+        inner: Box<SpannedType>,
+        span: Span,
+    },
+    // ?T, either a T or none. Optional pointers are nullable, plain ones are not
+    Optional {
+        inner: Box<SpannedType>,
+        span: Span,
+    },
+    // []T, a pointer to the first of len Ts
+    Slice {
+        inner: Box<SpannedType>,
+        span: Span,
+    },
+    // (T, U)
+    Tuple {
+        elements: Vec<SpannedType>,
+        span: Span,
+    },
+    // T!E, either a T or an error E
+    Result {
+        ok: Box<SpannedType>,
+        error: Box<SpannedType>,
+        span: Span,
+    },
+}
+impl SpannedType {
+    pub fn get_span(&self) -> Span {
+        return match self {
+            SpannedType::Named(id) => id.span.clone(),
+            SpannedType::Fn { span, .. } => span.clone(),
+            SpannedType::Pointer { span, .. } => span.clone(),
+            SpannedType::Optional { span, .. } => span.clone(),
+            SpannedType::Result { span, .. } => span.clone(),
+            SpannedType::Tuple { span, .. } => span.clone(),
+            SpannedType::Slice { span, .. } => span.clone(),
+        };
+    }
+}
+#[derive(Debug, PartialEq, Eq)]
 pub struct SpannedParam {
     pub name: SpannedIdentifier,
-    pub ptype: SpannedIdentifier,
+    pub ptype: SpannedType,
+}
+#[derive(Debug, PartialEq, Eq)]
+pub struct SpannedField {
+    pub name: SpannedIdentifier,
+    pub ftype: SpannedType,
+    pub attributes: Vec<Attribute>,
+    pub docs: Vec<Span>,
 }
 
 #[derive(Debug)]
 pub enum SpannedChunk {
+    Import {
+        path: SpannedIdentifier,
+        span: Span,
+    },
+    // import_c "header.h"; replaced by the header's declarations when the module is loaded
+    ImportC {
+        header: Span,
+        span: Span,
+    },
+    // Declarations of C functions and variables, optionally brought in with #[include(...)]
+    ExternFunction {
+        public: bool,
+        attributes: Vec<Attribute>,
+        // The /// comments above it, as the text after each ///
+        docs: Vec<Span>,
+        must_use: bool,
+        name: SpannedIdentifier,
+        params: Vec<SpannedParam>,
+        variadic: bool,
+        rtype: SpannedType,
+        include: Option<Span>,
+        span: Span,
+    },
+    ExternStatic {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        name: SpannedIdentifier,
+        vtype: SpannedType,
+        include: Option<Span>,
+        span: Span,
+    },
+    // Types marked extern keep their C names, and are not defined again when they come from
+    // an #[include(...)]
+    Struct {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        external: bool,
+        include: Option<Span>,
+        // The C spelling of the type, eg "struct tm"
+        export: Option<Span>,
+        name: SpannedIdentifier,
+        fields: Vec<SpannedField>,
+        span: Span,
+    },
+    Enum {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        external: bool,
+        include: Option<Span>,
+        export: Option<Span>,
+        name: SpannedIdentifier,
+        variants: Vec<SpannedIdentifier>,
+        span: Span,
+    },
+    // type Handle = usize; names another type. Distinct types can't be used as the type they
+    // are made from, without going through Meters(v) and m.0
+    TypeAlias {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        distinct: bool,
+        name: SpannedIdentifier,
+        target: SpannedType,
+        span: Span,
+    },
     Constant,
     StaticVar,
     Function {
+        public: bool,
+        attributes: Vec<Attribute>,
+        docs: Vec<Span>,
+        // The C symbol to use instead of the mangled name, from #[export_name(...)]
+        export: Option<Span>,
+        // From #[must_use], when the result can't be thrown away without _ =
+        must_use: bool,
         name: SpannedIdentifier,
         params: Vec<SpannedParam>,
-        rtype: SpannedIdentifier,
+        rtype: SpannedType,
         body: SpannedStatement,
         span: Span,
     },
     EOF,
 }
-#[derive(Debug)]
+impl SpannedChunk {
+    // Imports and the end of the file don't take doc comments
+    pub fn docs_mut(&mut self) -> Option<&mut Vec<Span>> {
+        return match self {
+            SpannedChunk::ExternFunction { docs, .. }
+            | SpannedChunk::ExternStatic { docs, .. }
+            | SpannedChunk::Struct { docs, .. }
+            | SpannedChunk::Enum { docs, .. }
+            | SpannedChunk::TypeAlias { docs, .. }
+            | SpannedChunk::Function { docs, .. } => Some(docs),
+            _ => None,
+        };
+    }
+}
+#[derive(Debug, PartialEq, Eq)]
 pub enum SpannedStatement {
     Decleration {
         name: SpannedIdentifier,
         expression: Box<SpannedExpression>,
-        rtype: SpannedIdentifier,
+        rtype: SpannedType,
         span: Span,
     },
+    // Assignments to a place, eg x = 1, p.x += 2, arr[i] = 3 or *ptr = v. Compound ones have the
+    // operation they do first, and the span is the operator's
     Reassignment {
-        name: SpannedIdentifier,
+        target: Box<SpannedExpression>,
         expression: Box<SpannedExpression>,
+        operation: Option<BinaryOperation>,
         span: Span,
     },
     If {
@@ -49,19 +204,49 @@ pub enum SpannedStatement {
         ielse: Option<Box<SpannedStatement>>,
         span: Span,
     },
+    // if (let v = opt) ..., where v is only in scope of the first branch
+    IfLet {
+        name: SpannedIdentifier,
+        expression: Box<SpannedExpression>,
+        statement: Box<SpannedStatement>,
+        ielse: Option<Box<SpannedStatement>>,
+        span: Span,
+    },
     Scope {
         body: Vec<SpannedStatement>,
         span: Span,
     },
-    VoidCall {
-        name: Box<SpannedIdentifier>,
-        params: Vec<SpannedExpression>,
+    // Any expression, eg increment(counter); or write(f)?;
+    Expression {
+        expression: Box<SpannedExpression>,
+        span: Span,
+    },
+    // Throws a value away on purpose, eg _ = increment(counter);
+    Discard {
+        expression: Box<SpannedExpression>,
         span: Span,
     },
     Return {
         expr: Option<Box<SpannedExpression>>,
         span: Span,
     },
+    // let (a, b) = pair;
+    Destructure {
+        names: Vec<SpannedIdentifier>,
+        expression: Box<SpannedExpression>,
+        span: Span,
+    },
+    // Runs the statement when the enclosing scope is left
+    Defer {
+        statement: Box<SpannedStatement>,
+        span: Span,
+    },
+    // A statement with attributes, eg #[align(16)] let buf: usize = 0;
+    Attributed {
+        attributes: Vec<Attribute>,
+        statement: Box<SpannedStatement>,
+        span: Span,
+    },
 }
 #[derive(Debug, PartialEq, Eq)]
 pub enum SpannedExpression {
@@ -69,6 +254,13 @@ pub enum SpannedExpression {
     Literal {
         span: Span,
     },
+    StringLiteral {
+        span: Span,
+    },
+    NoneLiteral {
+        span: Span,
+    },
+    // -a or !a, where operation is the operator. Parentheses leave no node of their own
     UnaryOperator {
         operation: Span,
         expression: Box<SpannedExpression>,
@@ -76,7 +268,7 @@ pub enum SpannedExpression {
     BinaryOperator {
         left: Box<SpannedExpression>,
         span: Span,
-        presedence: usize,
+        operation: BinaryOperation,
         right: Box<SpannedExpression>,
     },
     Call {
@@ -84,5 +276,49 @@ pub enum SpannedExpression {
         params: Vec<SpannedExpression>,
         span: Span,
     },
-    Tmp,
+    // (a, b)
+    Tuple {
+        elements: Vec<SpannedExpression>,
+        span: Span,
+    },
+    // p.x, through a pointer if p is one. The span is the field's
+    Field {
+        expression: Box<SpannedExpression>,
+        field: SpannedIdentifier,
+        span: Span,
+    },
+    // ptr[i], where the span covers the brackets
+    Index {
+        expression: Box<SpannedExpression>,
+        index: Box<SpannedExpression>,
+        span: Span,
+    },
+    // *ptr, where the span is the *
+    Deref {
+        expression: Box<SpannedExpression>,
+        span: Span,
+    },
+    // pair.0
+    TupleField {
+        tuple: Box<SpannedExpression>,
+        index: usize,
+        span: Span,
+    },
+    // Returns early with the error of a result, eg parse(s)?
+    Try {
+        expression: Box<SpannedExpression>,
+        span: Span,
+    },
+    // opt orelse default
+    Orelse {
+        left: Box<SpannedExpression>,
+        right: Box<SpannedExpression>,
+        span: Span,
+    },
+    Lambda {
+        params: Vec<SpannedParam>,
+        rtype: SpannedType,
+        body: Box<SpannedStatement>,
+        span: Span,
+    },
 }
@@ -10,7 +10,20 @@ pub enum Token {
     Colon(Range<usize>),
     Comma(Range<usize>),
     LArrow(Range<usize>),
+    Pipe(Range<usize>),
+    Ellipsis(Range<usize>),
+    Hash(Range<usize>),
+    LBracket(Range<usize>),
+    RBracket(Range<usize>),
+    Question(Range<usize>),
+    Dot(Range<usize>),
     // chunk
+    Import(Range<usize>),
+    ImportC(Range<usize>),
+    Pub(Range<usize>),
+    Extern(Range<usize>),
+    Struct(Range<usize>),
+    Enum(Range<usize>),
     Const(Range<usize>),
     Static(Range<usize>),
     Fn(Range<usize>),
@@ -22,9 +35,15 @@ pub enum Token {
     LBrace(Range<usize>),
     RBrace(Range<usize>),
     Return(Range<usize>),
+    Defer(Range<usize>),
+    Type(Range<usize>),
+    Distinct(Range<usize>),
     EOL(Range<usize>),
     // Expression
     NumericLiteral(Range<usize>),
+    StringLiteral(Range<usize>),
+    NoneLiteral(Range<usize>),
+    Orelse(Range<usize>),
     Identifier(SpannedIdentifier),
     Operator(SpannedOperator),
     LParen(Range<usize>),
@@ -39,7 +58,20 @@ impl Token {
             Token::Colon(range) => range.clone(),
             Token::Comma(range) => range.clone(),
             Token::LArrow(range) => range.clone(),
+            Token::Pipe(range) => range.clone(),
+            Token::Ellipsis(range) => range.clone(),
+            Token::Hash(range) => range.clone(),
+            Token::LBracket(range) => range.clone(),
+            Token::RBracket(range) => range.clone(),
+            Token::Question(range) => range.clone(),
+            Token::Dot(range) => range.clone(),
             //
+            Token::Import(range) => range.clone(),
+            Token::ImportC(range) => range.clone(),
+            Token::Pub(range) => range.clone(),
+            Token::Extern(range) => range.clone(),
+            Token::Struct(range) => range.clone(),
+            Token::Enum(range) => range.clone(),
             Token::Const(range) => range.clone(),
             Token::Static(range) => range.clone(),
             Token::Fn(range) => range.clone(),
@@ -51,9 +83,15 @@ impl Token {
             Token::LBrace(range) => range.clone(),
             Token::RBrace(range) => range.clone(),
             Token::Return(range) => range.clone(),
+            Token::Defer(range) => range.clone(),
+            Token::Type(range) => range.clone(),
+            Token::Distinct(range) => range.clone(),
             Token::EOL(range) => range.clone(),
             //
             Token::NumericLiteral(range) => range.clone(),
+            Token::StringLiteral(range) => range.clone(),
+            Token::NoneLiteral(range) => range.clone(),
+            Token::Orelse(range) => range.clone(),
             Token::Identifier(id_token) => id_token.span.clone(),
             Token::Operator(op_token) => op_token.span.clone(),
             Token::LParen(range) => range.clone(),
@@ -67,7 +105,20 @@ impl Token {
             Token::Colon(range) => _ = std::mem::replace(range, s),
             Token::Comma(range) => _ = std::mem::replace(range, s),
             Token::LArrow(range) => _ = std::mem::replace(range, s),
+            Token::Pipe(range) => _ = std::mem::replace(range, s),
+            Token::Ellipsis(range) => _ = std::mem::replace(range, s),
+            Token::Hash(range) => _ = std::mem::replace(range, s),
+            Token::LBracket(range) => _ = std::mem::replace(range, s),
+            Token::RBracket(range) => _ = std::mem::replace(range, s),
+            Token::Question(range) => _ = std::mem::replace(range, s),
+            Token::Dot(range) => _ = std::mem::replace(range, s),
             //
+            Token::Import(range) => _ = std::mem::replace(range, s),
+            Token::ImportC(range) => _ = std::mem::replace(range, s),
+            Token::Pub(range) => _ = std::mem::replace(range, s),
+            Token::Extern(range) => _ = std::mem::replace(range, s),
+            Token::Struct(range) => _ = std::mem::replace(range, s),
+            Token::Enum(range) => _ = std::mem::replace(range, s),
             Token::Const(range) => _ = std::mem::replace(range, s),
             Token::Static(range) => _ = std::mem::replace(range, s),
             Token::Fn(range) => _ = std::mem::replace(range, s),
@@ -79,9 +130,15 @@ impl Token {
             Token::LBrace(range) => _ = std::mem::replace(range, s),
             Token::RBrace(range) => _ = std::mem::replace(range, s),
             Token::Return(range) => _ = std::mem::replace(range, s),
+            Token::Defer(range) => _ = std::mem::replace(range, s),
+            Token::Type(range) => _ = std::mem::replace(range, s),
+            Token::Distinct(range) => _ = std::mem::replace(range, s),
             Token::EOL(range) => _ = std::mem::replace(range, s),
             //
             Token::NumericLiteral(range) => _ = std::mem::replace(range, s),
+            Token::StringLiteral(range) => _ = std::mem::replace(range, s),
+            Token::NoneLiteral(range) => _ = std::mem::replace(range, s),
+            Token::Orelse(range) => _ = std::mem::replace(range, s),
             Token::Identifier(id_token) => _ = std::mem::replace(&mut id_token.span, s),
             Token::Operator(op_token) => _ = std::mem::replace(&mut op_token.span, s),
             Token::LParen(range) => _ = std::mem::replace(range, s),
@@ -0,0 +1,95 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+use std::path::PathBuf;
+
+#[test]
+fn aliases_are_their_target() {
+    let program = common::with_printf(
+        "type Handle = usize;
+type Named = (Handle, *u8);
+fn open(n: usize) Handle -> {
+    return n;
+}
+fn main() i32 -> {
+    let h: Handle = open(3);
+    let n: usize = h;
+    let named: Named = (n, \"three\");
+    printf(\"%u %u %s\\n\", h, named.0, named.1);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("alias_plain", &program), "3 3 three\n");
+}
+
+#[test]
+fn aliases_are_used_through_modules() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("alias_modules");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("units.nc"),
+        "pub type Count = usize;
+pub distinct type Id = usize;
+pub fn first() Id -> {
+    return Id(1);
+}
+",
+    )
+    .unwrap();
+
+    let program = common::with_printf(
+        "import units;
+fn main() i32 -> {
+    let c: units::Count = 2;
+    let id: units::Id = units::first();
+    printf(\"%u %u\\n\", c, id.0);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("alias_modules", &program), "2 1\n");
+}
+
+#[test]
+fn distinct_types_are_made_and_read() {
+    let program = common::with_printf(
+        "distinct type Meters = usize;
+fn twice(m: Meters) Meters -> {
+    let v: usize = m.0;
+    return Meters(v + v);
+}
+fn main() i32 -> {
+    let m: Meters = Meters(21);
+    printf(\"%u\\n\", twice(m).0);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("alias_distinct", &program), "42\n");
+}
+
+#[test]
+fn distinct_types_are_not_their_base() {
+    let program = "distinct type Meters = usize;
+fn main() i32 -> {
+    let m: Meters = 5;
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("alias_distinct_literal", program),
+        Err(TypeError::DeclarationMatch(_))
+    ));
+
+    let program = "distinct type Meters = usize;
+fn main() i32 -> {
+    let m: Meters = Meters(5);
+    return m;
+}
+";
+    assert!(matches!(
+        common::type_check("alias_distinct_return", program),
+        Err(TypeError::BadReturnType(_))
+    ));
+}
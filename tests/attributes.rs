@@ -0,0 +1,91 @@
+mod common;
+
+use notc::parsing::ModuleTree;
+use std::path::PathBuf;
+
+// Writes a program without building it, for looking at what the parser made of it
+fn load(name: &str, program: &str) -> Result<ModuleTree, notc::parsing::LoadError> {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = dir.join("main.nc");
+    std::fs::write(&root, program).unwrap();
+    ModuleTree::load(&root)
+}
+
+#[test]
+fn attributes_become_gnu_attributes() {
+    let program = common::with_printf(
+        "#[packed]
+struct Header {
+    tag: u8,
+    #[align(8)]
+    #[deprecated(\"use tag\")]
+    size: usize,
+}
+#[inline]
+pub fn add(a: usize, b: usize) usize -> {
+    return a + b;
+}
+#[cold]
+#[export_name(\"notc_fail\")]
+fn fail() usize -> {
+    return 1;
+}
+fn main() i32 -> {
+    #[align(16)]
+    let total: usize = add(2, 3);
+    printf(\"%u %u\\n\", total, fail());
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("attributes_gnu", &program), "5 1\n");
+
+    let c = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("attributes_gnu/main.c"),
+    )
+    .unwrap();
+    for expected in [
+        "typedef struct __attribute__((packed)) nc_Header {",
+        "unsigned int size __attribute__((aligned(8), deprecated(\"use tag\")));",
+        "extern unsigned int nc_add(unsigned int a, unsigned int b);",
+        "__attribute__((always_inline)) inline unsigned int nc_add(",
+        "__attribute__((cold)) unsigned int notc_fail(void)",
+        "static int nc_main(void)",
+        "unsigned int total __attribute__((aligned(16))) = ",
+    ] {
+        assert!(c.contains(expected), "{} not in\n{}", expected, c);
+    }
+}
+
+#[test]
+fn unknown_and_misplaced_attributes_warn() {
+    let tree = load(
+        "attributes_warn",
+        "#[frobnicate]
+fn main() i32 -> {
+    #[inline]
+    return 0;
+}
+#[packed]
+fn other() void -> {}
+",
+    )
+    .unwrap();
+    assert_eq!(tree.warnings.len(), 3, "{:?}", tree.warnings);
+    assert!(tree.warnings[0].ends_with("main.nc:1: unknown attribute #[frobnicate], ignored"));
+    assert!(tree.warnings[1].contains("#[inline] only applies to functions"));
+    assert!(tree.warnings[2].contains("main.nc:6: #[packed] only applies to structs"));
+}
+
+#[test]
+fn attribute_values_are_checked() {
+    for (name, attribute) in [
+        ("attributes_align", "#[align(\"16\")]"),
+        ("attributes_inline", "#[inline(1)]"),
+        ("attributes_export", "#[export_name]"),
+    ] {
+        let program = format!("{}\nfn main() i32 -> {{\n    return 0;\n}}\n", attribute);
+        assert!(load(name, &program).is_err(), "{} was accepted", attribute);
+    }
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use notc::codegen::c::CCodeGen;
+use notc::traits::{CodeGen, TreeChecker};
+use notc::tree_checker::TypeChecker;
+use notc::types::resolved_types::ResolvedChunk;
+
+const PROGRAM: &str = "#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+/* A block comment /* with a nested one */ still commented */
+/// A point on the grid
+/// in two dimensions
+struct Point {
+    /// Across
+    x: usize,
+    // Not a doc comment
+    y: usize,
+}
+/// Adds two numbers
+#[inline]
+fn add(a: usize, b: usize) usize -> {
+    return a /* inline */ + b; // trailing
+}
+//// Not a doc comment either
+fn main() i32 -> {
+    printf(\"%u\\n\", add(1, 2));
+    return 0;
+}
+";
+
+#[test]
+fn comments_are_skipped() {
+    assert_eq!(common::run("comments_skipped", PROGRAM), "3\n");
+}
+
+#[test]
+fn doc_comments_attach_to_chunks_and_fields() {
+    let (_, source, ast) = common::resolve("comments_attach", PROGRAM);
+    let text = |docs: &[std::ops::Range<usize>]| -> Vec<String> {
+        docs.iter().map(|d| source[d.clone()].to_string()).collect()
+    };
+
+    for chunk in &ast.body {
+        match chunk {
+            ResolvedChunk::Struct { docs, fields, .. } => {
+                assert_eq!(text(docs), [" A point on the grid", " in two dimensions"]);
+                assert_eq!(text(&fields[0].docs), [" Across"]);
+                assert!(fields[1].docs.is_empty());
+            }
+            ResolvedChunk::Function { name, docs, .. } => {
+                if &ast.symbols[name.id.0].name[..] == "add" {
+                    assert_eq!(text(docs), [" Adds two numbers"]);
+                } else {
+                    assert!(docs.is_empty());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn doc_comments_are_kept_in_c() {
+    let (root, source, ast) = common::resolve("comments_kept", PROGRAM);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).unwrap();
+
+    let c = root.with_extension("c");
+    CCodeGen::new(source, std::fs::File::create(&c).unwrap())
+        .with_notes(checker.notes)
+        .keep_comments(true)
+        .generate(&ast)
+        .unwrap();
+
+    let c = std::fs::read_to_string(c).unwrap();
+    for expected in [
+        "/// A point on the grid\n/// in two dimensions\ntypedef struct nc_Point {",
+        "    /// Across\n    unsigned int x;",
+        "/// Adds two numbers\nstatic unsigned int nc_add(",
+    ] {
+        assert!(c.contains(expected), "{} not in\n{}", expected, c);
+    }
+    assert!(!c.contains("Not a doc comment"));
+}
+
+#[test]
+fn unclosed_block_comments_are_errors() {
+    let dir = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("comments_unclosed");
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = dir.join("main.nc");
+    std::fs::write(&root, "fn main() i32 -> {\n    return 0;\n}\n/* /* */\n").unwrap();
+    assert!(notc::parsing::ModuleTree::load(&root).is_err());
+}
@@ -0,0 +1,89 @@
+use notc::codegen::c::CCodeGen;
+use notc::parsing::ModuleTree;
+use notc::traits::{CodeGen, TreeChecker};
+use notc::tree_checker::{NameResolver, ResolveError, ResolvedAstTree, TypeChecker, TypeError};
+use std::path::PathBuf;
+use std::process::Command;
+use std::rc::Rc;
+
+// Declares printf, which most test programs print their results with
+#[allow(dead_code)]
+pub const PRINTF: &str = "#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+";
+
+// The program with printf declared ahead of it
+#[allow(dead_code)]
+pub fn with_printf(program: &str) -> String {
+    format!("{PRINTF}{program}")
+}
+
+// Each test program gets its own directory, named after the test
+#[allow(dead_code)]
+pub fn try_resolve(
+    name: &str,
+    program: &str,
+) -> (PathBuf, Rc<str>, Result<ResolvedAstTree, ResolveError>) {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = dir.join("main.nc");
+    std::fs::write(&root, program).unwrap();
+
+    let tree = ModuleTree::load(&root).expect("Parsing error");
+    let source = tree.source.clone();
+    let mut nr = NameResolver::from_rc_str(source.clone()).pre_intern(notc::PRIMATIVE_TYPES);
+    (root, source, nr.resolve_modules(tree))
+}
+
+#[allow(dead_code)]
+pub fn resolve(name: &str, program: &str) -> (PathBuf, Rc<str>, ResolvedAstTree) {
+    let (root, source, ast) = try_resolve(name, program);
+    (root, source, ast.expect("Name error"))
+}
+
+#[allow(dead_code)]
+pub fn type_check(name: &str, program: &str) -> Result<(), TypeError> {
+    let (_, _, ast) = resolve(name, program);
+    TypeChecker::new().check(&ast)
+}
+
+// Compiles the program to C, builds it with gcc and returns what it printed
+#[allow(dead_code)]
+pub fn run(name: &str, program: &str) -> String {
+    let (stdout, _) = run_with_args(name, program, &[]);
+    stdout
+}
+
+// Runs the program like run, with command line arguments, also returning its exit status
+#[allow(dead_code)]
+pub fn run_with_args(name: &str, program: &str, args: &[&str]) -> (String, i32) {
+    let (root, source, ast) = resolve(name, program);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("Type error");
+
+    let c = root.with_extension("c");
+    let out = std::fs::File::create(&c).unwrap();
+    CCodeGen::new(source, out)
+        .with_notes(checker.notes)
+        .generate(&ast)
+        .expect("Error writing to file");
+
+    let exe = root.with_extension("");
+    let gcc = Command::new("gcc")
+        .arg(&c)
+        .arg("-o")
+        .arg(&exe)
+        .output()
+        .expect("Couldn't run gcc");
+    assert!(
+        gcc.status.success(),
+        "{}",
+        String::from_utf8_lossy(&gcc.stderr)
+    );
+
+    let output = Command::new(&exe).args(args).output().unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        output.status.code().unwrap(),
+    )
+}
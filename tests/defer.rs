@@ -0,0 +1,128 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn runs_at_end_of_scope_in_reverse() {
+    let program = common::with_printf(
+        "fn main() i32 -> {
+    {
+        defer printf(\"first\\n\");
+        defer printf(\"second\\n\");
+        printf(\"body\\n\");
+    }
+    printf(\"after\\n\");
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run("defer_scope", &program),
+        "body\nsecond\nfirst\nafter\n"
+    );
+}
+
+#[test]
+fn runs_before_return() {
+    let program = common::with_printf(
+        "fn count(n: usize) usize -> {
+    defer printf(\"leaving\\n\");
+    if (n == 0) {
+        return 10;
+    }
+    defer printf(\"counted\\n\");
+    printf(\"counting\\n\");
+    return n;
+}
+fn main() i32 -> {
+    let a: usize = count(0);
+    let b: usize = count(5);
+    printf(\"%u %u\\n\", a, b);
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run("defer_return", &program),
+        "leaving\ncounting\ncounted\nleaving\n10 5\n"
+    );
+}
+
+#[test]
+fn return_value_is_taken_first() {
+    let program = common::with_printf(
+        "fn bump() usize -> {
+    let n: usize = 1;
+    defer n = n + 1;
+    return n;
+}
+fn main() i32 -> {
+    let n: usize = bump();
+    printf(\"%u\\n\", n);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("defer_value", &program), "1\n");
+}
+
+#[test]
+fn nested_scopes_unwind_innermost_first() {
+    let program = common::with_printf(
+        "fn work(n: usize) void -> {
+    defer printf(\"outer\\n\");
+    {
+        defer printf(\"inner\\n\");
+        if (n == 0) {
+            defer printf(\"branch\\n\");
+            return;
+        }
+        printf(\"late\\n\");
+    }
+}
+fn main() i32 -> {
+    let early: usize = 0;
+    let late: usize = 1;
+    work(early);
+    work(late);
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run("defer_nested", &program),
+        "branch\ninner\nouter\nlate\ninner\nouter\n"
+    );
+}
+
+// The value returned is held in a name of its own, whatever the locals are called
+#[test]
+fn result_name_is_fresh() {
+    let program = common::with_printf(
+        "fn pick(n: usize) usize -> {
+    let notc_result: usize = n + 1;
+    let (notc_tuple, other) = (n, n);
+    defer printf(\"leaving\\n\");
+    return notc_result * 2 + notc_tuple + other;
+}
+fn main() i32 -> {
+    printf(\"%u\\n\", pick(3));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("defer_result_name", &program), "leaving\n14\n");
+}
+
+#[test]
+fn cannot_return_from_defer() {
+    let program = "fn main() i32 -> {
+    defer return 1;
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("defer_in_return", program),
+        Err(TypeError::DeferReturn(_))
+    ));
+}
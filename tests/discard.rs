@@ -0,0 +1,79 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+const HEAP: &str = "#[include(\"stdlib.h\")]
+extern fn malloc(size: usize) *usize;
+";
+
+#[test]
+fn expressions_are_statements() {
+    let program = common::with_printf(&format!(
+        "{HEAP}
+fn increment(counter: *usize) usize -> {{
+    *counter += 1;
+    return *counter;
+}}
+#[must_use]
+fn checked(counter: *usize) usize -> {{
+    return increment(counter);
+}}
+fn main() i32 -> {{
+    let counter: *usize = malloc(8);
+    *counter = 0;
+    increment(counter);
+    increment(counter);
+    _ = checked(counter);
+    printf(\"%u\\n\", *counter);
+    return 0;
+}}
+"
+    ));
+    assert_eq!(common::run("discard_statements", &program), "3\n");
+}
+
+#[test]
+fn must_use_results_are_used() {
+    let program = "#[must_use]
+fn answer() usize -> {
+    return 42;
+}
+fn main() i32 -> {
+    answer();
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("discard_must_use", program),
+        Err(TypeError::UnusedResult(_))
+    ));
+}
+
+#[test]
+fn errors_are_discarded_explicitly() {
+    let program = "fn fail() usize!bool -> {
+    return 1;
+}
+fn main() i32 -> {
+    fail();
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("discard_error", program),
+        Err(TypeError::IgnoredError(_))
+    ));
+
+    let program = common::with_printf(
+        "fn fail() void!usize -> {
+    return 1;
+}
+fn main() i32 -> {
+    _ = fail();
+    printf(\"done\\n\");
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("discard_error_explicit", &program), "done\n");
+}
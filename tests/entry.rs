@@ -0,0 +1,91 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn main_gets_the_arguments() {
+    let program = common::with_printf(
+        "fn count(args: []str, i: usize) i32 -> {
+    if (i == args.len) return 0;
+    let arg: *u8 = args[i];
+    printf(\"%s\\n\", arg);
+    return count(args, i + 1) + 1;
+}
+fn main(args: []str) i32 -> {
+    return count(args, 1);
+}
+",
+    );
+    assert_eq!(
+        common::run_with_args("entry_args", &program, &["one", "two"]),
+        ("one\ntwo\n".to_string(), 2)
+    );
+}
+
+#[test]
+fn void_main_exits_with_zero() {
+    let program = common::with_printf(
+        "fn main() void -> {
+    printf(\"done\\n\");
+}
+",
+    );
+    assert_eq!(
+        common::run_with_args("entry_void", &program, &[]),
+        ("done\n".to_string(), 0)
+    );
+}
+
+#[test]
+fn bad_main_signatures_are_rejected() {
+    for (name, program) in [
+        ("entry_usize", "fn main() usize -> {\n    return 0;\n}\n"),
+        (
+            "entry_param",
+            "fn main(in: usize) i32 -> {\n    return 0;\n}\n",
+        ),
+        ("entry_void_args", "fn main(args: []str) void -> {}\n"),
+        (
+            "entry_two_params",
+            "fn main(args: []str, n: usize) i32 -> {\n    return 0;\n}\n",
+        ),
+        (
+            "entry_old_args",
+            "fn main(args: *?*u8) i32 -> {\n    return 0;\n}\n",
+        ),
+    ] {
+        assert_eq!(
+            common::type_check(name, program),
+            Err(TypeError::EntryPoint(3..7)),
+            "{}",
+            name
+        );
+    }
+}
+
+// The elements can be replaced, but there are always as many as main was given
+#[test]
+fn slice_length_is_fixed() {
+    let program = common::with_printf(
+        "fn main(args: []str) i32 -> {
+    args[0] = \"first\";
+    printf(\"%s %u\\n\", args[0], args.len);
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run_with_args("entry_replace", &program, &["a"]),
+        ("first 2\n".to_string(), 0)
+    );
+
+    let program = "fn main(args: []str) i32 -> {
+    args.len = 0;
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("entry_len", program),
+        Err(TypeError::NotAssignable(_))
+    ));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+use std::path::PathBuf;
+
+#[test]
+fn c_functions_and_statics_are_used() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("extern_use");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("counter.h"),
+        "static unsigned int counter = 5;
+static unsigned int triple(unsigned int n) {
+    return n * 3;
+}
+",
+    )
+    .unwrap();
+
+    // triple is declared twice, once with the header and once written out as a prototype
+    let program = "#[include(\"stdio.h\")]
+extern fn puts(s: *u8) i32;
+#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+#[include(\"counter.h\")]
+extern static counter: usize;
+#[include(\"counter.h\")]
+extern fn triple(n: usize) usize;
+extern fn triple(n: usize) usize;
+fn main() i32 -> {
+    puts(\"hello\");
+    counter = counter + 1;
+    let c: u8 = 120;
+    printf(\"%u %u %c %s\\n\", counter, triple(2), c, \"there\");
+    return 0;
+}
+";
+    assert_eq!(common::run("extern_use", program), "hello\n6 6 x there\n");
+}
+
+#[test]
+fn externs_are_type_checked() {
+    for (name, body, error) in [
+        ("extern_too_few", "printf();", TypeError::ParamTypes(0..0)),
+        (
+            "extern_fixed_param",
+            "printf(1);",
+            TypeError::ParamTypes(0..0),
+        ),
+        (
+            "extern_args",
+            "puts(\"a\", \"b\");",
+            TypeError::ParamTypes(0..0),
+        ),
+        (
+            "extern_return",
+            "let n: usize = puts(\"a\");",
+            TypeError::DeclarationMatch(0..0),
+        ),
+        (
+            "extern_static",
+            "let b: bool = errno;",
+            TypeError::DeclarationMatch(0..0),
+        ),
+    ] {
+        let program = format!(
+            "#[include(\"stdio.h\")]
+extern fn puts(s: *u8) i32;
+#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+#[include(\"errno.h\")]
+extern static errno: i32;
+fn main() i32 -> {{
+    {}
+    return 0;
+}}
+",
+            body
+        );
+        let result = common::type_check(name, &program);
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(matched, "{}: expected {:?}, got {:?}", name, error, result);
+    }
+}
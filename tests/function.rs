@@ -0,0 +1,121 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn functions_are_values() {
+    let program = common::with_printf(
+        "fn double(n: usize) usize -> {
+    return n * 2;
+}
+fn square(n: usize) usize -> {
+    return n * n;
+}
+fn apply(f: fn(usize) usize, n: usize) usize -> {
+    return f(n);
+}
+fn pick(n: usize) fn(usize) usize -> {
+    if (n == 0) {
+        return double;
+    }
+    return square;
+}
+fn main() i32 -> {
+    let f: fn(usize) usize = double;
+    printf(\"%u %u\\n\", f(3), apply(square, 3));
+    let g: fn(usize) usize = pick(1);
+    let h: fn(usize) usize = pick(0);
+    printf(\"%u %u\\n\", g(5), h(5));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("function_values", &program), "6 9\n25 10\n");
+}
+
+// Written as C function pointer declarators, so they can be handed to C
+#[test]
+fn callbacks_are_handed_to_c() {
+    let dir = std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("function_callback");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("callback.h"),
+        "static unsigned int each(unsigned int *nums, unsigned int count,
+                         unsigned int (*f)(unsigned int, unsigned int), unsigned int start) {
+    for (unsigned int i = 0; i < count; i++) {
+        start = f(start, nums[i]);
+    }
+    return start;
+}
+",
+    )
+    .unwrap();
+
+    let program = common::with_printf(
+        "#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *usize;
+#[include(\"callback.h\")]
+extern fn each(nums: *usize, count: usize, f: fn(usize, usize) usize, start: usize) usize;
+fn add(total: usize, n: usize) usize -> {
+    return total + n;
+}
+fn multiply(total: usize, n: usize) usize -> {
+    return total * n;
+}
+fn main() i32 -> {
+    let nums: *usize = calloc(3, 4);
+    nums[0] = 2;
+    nums[1] = 3;
+    nums[2] = 4;
+    printf(\"%u %u\\n\", each(nums, 3, add, 0), each(nums, 3, multiply, 1));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("function_callback", &program), "9 24\n");
+}
+
+#[test]
+fn function_types_are_checked() {
+    for (name, body, error) in [
+        (
+            "function_wrong_params",
+            "let f: fn(usize) usize = both;",
+            TypeError::DeclarationMatch(0..0),
+        ),
+        (
+            "function_wrong_return",
+            "let f: fn(usize, usize) void = both;",
+            TypeError::DeclarationMatch(0..0),
+        ),
+        (
+            "function_call_args",
+            "let f: fn(usize, usize) usize = both;
+    f(1);",
+            TypeError::ParamTypes(0..0),
+        ),
+        (
+            "function_not_callable",
+            "let f: usize = 1;
+    f(1);",
+            TypeError::TypeMismatch(0..0),
+        ),
+    ] {
+        let program = format!(
+            "fn both(a: usize, b: usize) usize -> {{
+    return a + b;
+}}
+fn main() i32 -> {{
+    {}
+    return 0;
+}}
+",
+            body
+        );
+        let result = common::type_check(name, &program);
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(matched, "{}: expected {:?}, got {:?}", name, error, result);
+    }
+}
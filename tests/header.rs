@@ -0,0 +1,180 @@
+mod common;
+
+use notc::codegen::c::CCodeGen;
+use notc::codegen::header::CHeaderGen;
+use notc::traits::{CodeGen, TreeChecker};
+use notc::tree_checker::TypeChecker;
+use std::process::Command;
+
+// Writes the program's C and its header, main.h, then builds and runs them with a C caller
+fn call_from_c(name: &str, program: &str, caller: &str, flags: &[&str]) -> String {
+    let (root, source, ast) = common::resolve(name, program);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).expect("Type error");
+
+    let c = root.with_extension("c");
+    CCodeGen::new(source.clone(), std::fs::File::create(&c).unwrap())
+        .with_notes(checker.notes)
+        .generate(&ast)
+        .expect("Error writing to file");
+    let h = root.with_extension("h");
+    CHeaderGen::new(source, std::fs::File::create(&h).unwrap(), "main.h")
+        .generate(&ast)
+        .expect("Error writing to file");
+
+    let caller_c = root.with_file_name("caller.c");
+    std::fs::write(&caller_c, caller).unwrap();
+    let exe = root.with_file_name("caller");
+    let gcc = Command::new("gcc")
+        .args(flags)
+        .arg(&caller_c)
+        .arg(&c)
+        .arg("-o")
+        .arg(&exe)
+        .output()
+        .expect("Couldn't run gcc");
+    assert!(
+        gcc.status.success(),
+        "{}",
+        String::from_utf8_lossy(&gcc.stderr)
+    );
+
+    let output = Command::new(&exe).output().unwrap();
+    String::from_utf8(output.stdout).unwrap()
+}
+
+// The header uses C's bool, so it can come after stdbool.h, or be compiled as C23
+#[test]
+fn headers_use_c_bool() {
+    let program = "pub fn same(a: usize, b: usize) bool -> {
+    return a == b;
+}
+";
+    let caller = "#include <stdbool.h>
+#include <stdio.h>
+#include \"main.h\"
+int main(void) {
+    bool yes = true;
+    printf(\"%d %d\\n\", nc_same(1, 1) == yes, nc_same(1, 2) == false);
+    return 0;
+}
+";
+    for std in ["-std=c99", "-std=c2x"] {
+        assert_eq!(call_from_c("header_bool", program, caller, &[std]), "1 1\n");
+    }
+}
+
+// Private functions are static, so C linked with the program can use their names
+#[test]
+fn private_functions_stay_private() {
+    let program = "fn helper() usize -> {
+    return 40;
+}
+pub fn answer() usize -> {
+    return helper() + 2;
+}
+";
+    let caller = "#include <stdio.h>
+#include \"main.h\"
+unsigned int nc_helper(void) {
+    return 7;
+}
+int main(void) {
+    printf(\"%u %u\\n\", nc_answer(), nc_helper());
+    return 0;
+}
+";
+    assert_eq!(
+        call_from_c("header_private", program, caller, &[]),
+        "42 7\n"
+    );
+}
+
+// Public types are defined in the header and pub functions declared, under the names
+// #[export_name(...)] gives them. The guard lets the header be included twice
+#[test]
+fn pub_items_are_declared() {
+    let program = "pub struct Point {
+    x: usize,
+    y: usize,
+}
+pub enum Colour {
+    Red,
+    Green,
+}
+struct Hidden {
+    secret: usize,
+}
+#[export_name(\"point_sum\")]
+pub fn sum(p: *Point) usize -> {
+    return p.x + p.y;
+}
+pub fn green() Colour -> {
+    return Colour::Green;
+}
+fn hidden(h: *Hidden) usize -> {
+    return h.secret;
+}
+";
+    let caller = "#include <stdio.h>
+#include \"main.h\"
+#include \"main.h\"
+int main(void) {
+    nc_Point p = {.x = 2, .y = 3};
+    printf(\"%u %d\\n\", point_sum(&p), nc_green() == nc_Colour__Green);
+    return 0;
+}
+";
+    assert_eq!(call_from_c("header_items", program, caller, &[]), "5 1\n");
+
+    let header = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("header_items/main.h"),
+    )
+    .unwrap();
+    assert!(header.starts_with("#ifndef MAIN_H\n#define MAIN_H\n"));
+    assert!(header.ends_with("#endif\n"));
+    assert!(!header.contains("nc_sum"));
+    assert!(!header.contains("Hidden") && !header.contains("hidden"));
+}
+
+// Private types that pub items use are defined too, or C couldn't name them
+#[test]
+fn types_of_pub_items_are_defined() {
+    let program = "struct Inner {
+    value: usize,
+}
+struct Unit {
+    size: usize,
+}
+type Count = Unit;
+pub struct Outer {
+    inner: Inner,
+    count: ?Count,
+}
+struct Unused {
+    secret: usize,
+}
+pub fn take(i: *Inner) usize -> {
+    return i.value;
+}
+pub fn outer_value(o: *Outer) usize -> {
+    return o.inner.value;
+}
+";
+    let caller = "#include <stdio.h>
+#include \"main.h\"
+int main(void) {
+    nc_Inner i = {.value = 4};
+    nc_Outer o = {.inner = {.value = 5}};
+    printf(\"%u %u\\n\", nc_take(&i), nc_outer_value(&o));
+    return 0;
+}
+";
+    assert_eq!(call_from_c("header_reached", program, caller, &[]), "4 5\n");
+
+    let header = std::fs::read_to_string(
+        std::path::PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("header_reached/main.h"),
+    )
+    .unwrap();
+    assert!(!header.contains("Unused"));
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use notc::parsing::ModuleTree;
+use notc::tree_checker::TypeError;
+use std::path::PathBuf;
+
+// libc is used without writing any of it out as externs
+#[test]
+fn libc_is_imported() {
+    let program = "import_c \"stdio.h\";
+import_c \"stdlib.h\";
+import_c \"string.h\";
+#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *u8;
+fn main() i32 -> {
+    let out: *u8 = calloc(32, 1);
+    strcpy(out, \"hello\");
+    strcat(out, \" world\");
+    printf(\"%s %d %d\\n\", out, atoi(\"41\") + 1, strcmp(out, out));
+    free(out);
+    return 0;
+}
+";
+    assert_eq!(common::run("import_libc", program), "hello world 42 0\n");
+
+    let program = "import_c \"string.h\";
+fn main() i32 -> {
+    let n: i32 = strcmp(1, \"a\");
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("import_libc_types", program),
+        Err(TypeError::ParamTypes(_))
+    ));
+}
+
+// notc integers are 32 bits, so C's 64 bit types are left out instead of cut down
+#[test]
+fn wide_integers_are_skipped() {
+    let program = "import_c \"string.h\";
+fn main() i32 -> {
+    return 0;
+}
+";
+    let (root, _, _) = common::resolve("import_wide", program);
+    let tree = ModuleTree::load(&root).unwrap();
+    assert!(
+        tree.warnings
+            .iter()
+            .any(|w| w.contains("strlen") && w.contains("size_t")),
+        "No warning about strlen in {:?}",
+        tree.warnings
+    );
+
+    let program = "import_c \"string.h\";
+fn main() i32 -> {
+    let n: usize = strlen(\"abc\");
+    return 0;
+}
+";
+    assert!(common::try_resolve("import_wide_used", program).2.is_err());
+}
+
+#[test]
+fn header_declarations_become_externs() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("import_local");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("shapes.h"),
+        "typedef unsigned int shape_size;
+typedef unsigned long long shape_id;
+typedef shape_size area_t;
+#define SIDES 4
+#define SQUARE(x) ((x) * (x))
+typedef enum { RED, GREEN } colour;
+typedef struct {
+    area_t width;
+    area_t height;
+    colour fill;
+    unsigned int flags : 3;
+} rect;
+static area_t area(const rect *r) {
+    return r->width * r->height;
+}
+shape_id next_id(void);
+static double ratio(const rect *r) {
+    return (double)r->width / r->height;
+}
+",
+    )
+    .unwrap();
+
+    // rect can't keep its bit field, so it is only used behind a pointer
+    let program = common::with_printf(
+        "import_c \"shapes.h\";
+#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *rect;
+fn main() i32 -> {
+    let r: *rect = calloc(1, 64);
+    r.width = 3;
+    r.height = 4;
+    r.fill = colour::GREEN;
+    printf(\"%u %u %d\\n\", area(r), r.fill, SIDES);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("import_local", &program), "12 1 4\n");
+
+    let tree = ModuleTree::load(&dir.join("main.nc")).unwrap();
+    for skipped in ["SQUARE", "bit fields", "ratio", "next_id"] {
+        assert!(
+            tree.warnings.iter().any(|w| w.contains(skipped)),
+            "No warning about {} in {:?}",
+            skipped,
+            tree.warnings
+        );
+    }
+}
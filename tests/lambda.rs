@@ -0,0 +1,160 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+use std::path::PathBuf;
+
+#[test]
+fn lambdas_are_function_values() {
+    let program = common::with_printf(
+        "fn apply(f: fn(usize, usize) usize, a: usize, b: usize) usize -> {
+    return f(a, b);
+}
+fn double(n: usize) usize -> {
+    return n * 2;
+}
+fn main() i32 -> {
+    let add: fn(usize, usize) usize = |a: usize, b: usize| usize -> {
+        return a + b;
+    };
+    let product: usize = apply(|a: usize, b: usize| usize -> {
+        return a * b;
+    }, 3, 4);
+    // Items can be used inside, as they aren't captured
+    let twice_sum: fn(usize, usize) usize = |a: usize, b: usize| usize -> {
+        let inner: fn(usize) usize = |n: usize| usize -> {
+            return n + 1;
+        };
+        return double(inner(a + b));
+    };
+    printf(\"%u %u %u\\n\", add(1, 2), product, twice_sum(1, 2));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("lambda_values", &program), "3 12 8\n");
+
+    // Each is lifted to a static function of its own
+    let c = std::fs::read_to_string(
+        PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("lambda_values/main.c"),
+    )
+    .unwrap();
+    for lifted in [
+        "notc_lambda_0",
+        "notc_lambda_1",
+        "notc_lambda_2",
+        "notc_lambda_3",
+    ] {
+        assert!(
+            c.contains(&format!("static unsigned int {}(", lifted)),
+            "{} not in\n{}",
+            lifted,
+            c
+        );
+    }
+}
+
+#[test]
+fn lambdas_cannot_capture() {
+    for (name, program) in [
+        (
+            "lambda_capture_local",
+            "fn main() i32 -> {
+    let x: usize = 1;
+    let f: fn() usize = || usize -> {
+        return x;
+    };
+    return 0;
+}
+",
+        ),
+        (
+            "lambda_capture_param",
+            "fn make(x: usize) fn() usize -> {
+    return || usize -> {
+        return x;
+    };
+}
+",
+        ),
+        (
+            "lambda_capture_outer_lambda",
+            "fn main() i32 -> {
+    let f: fn(usize) usize = |x: usize| usize -> {
+        let g: fn() usize = || usize -> {
+            return x;
+        };
+        return g();
+    };
+    return 0;
+}
+",
+        ),
+    ] {
+        let result = common::type_check(name, program);
+        assert!(
+            matches!(result, Err(TypeError::Capture(_))),
+            "{}: expected a capture error, got {:?}",
+            name,
+            result
+        );
+    }
+}
+
+// notc reports the capture and fails, rather than panicking
+#[test]
+fn captures_are_reported_by_notc() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("lambda_capture_cli");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("main.nc"),
+        "fn main() i32 -> {
+    let x: usize = 1;
+    let f: fn() usize = || usize -> {
+        return x;
+    };
+    return 0;
+}
+",
+    )
+    .unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_notc"))
+        .arg("main.nc")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Err: Closures cannot capture local variables: x"),
+        "{}",
+        stdout
+    );
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("panicked"));
+}
+
+#[test]
+fn lambdas_are_type_checked() {
+    let program = "fn main() i32 -> {
+    let f: fn(usize) usize = |n: usize| bool -> {
+        return n == 1;
+    };
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("lambda_type", program),
+        Err(TypeError::DeclarationMatch(_))
+    ));
+
+    let program = "fn main() i32 -> {
+    let f: fn(usize) usize = |n: usize| usize -> {
+        return n == 1;
+    };
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("lambda_return", program),
+        Err(TypeError::BadReturnType(_))
+    ));
+}
@@ -0,0 +1,122 @@
+mod common;
+
+use notc::codegen::mangle::{demangle, demangle_text, mangle};
+use notc::types::resolved_types::SymbolKind;
+use std::path::PathBuf;
+
+#[test]
+fn reserved_names_are_escaped() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("mangle_reserved");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("util.nc"),
+        "pub fn main() usize -> {
+    return 1;
+}
+pub fn int(unsigned: usize) usize -> {
+    return unsigned + 1;
+}
+",
+    )
+    .unwrap();
+
+    let program = common::with_printf(
+        "import util;
+struct Box {
+    char: usize,
+}
+#[include(\"stdlib.h\")]
+extern fn malloc(size: usize) *Box;
+fn free(b: *Box) usize -> {
+    return b.char;
+}
+fn main() i32 -> {
+    let int: usize = util::main();
+    let unsigned: usize = util::int(int);
+    let b: *Box = malloc(8);
+    b.char = unsigned + 1;
+    printf(\"%u %u %u\\n\", int, unsigned, free(b));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("mangle_reserved", &program), "1 2 3\n");
+}
+
+// Items are kept apart from whatever the headers declare, not only from a list of names
+#[test]
+fn items_dont_clash_with_headers() {
+    let program = common::with_printf(
+        "fn remove(a: usize) usize -> {
+    return a - 1;
+}
+fn rename() void -> {
+    printf(\"renamed\\n\");
+}
+fn main() i32 -> {
+    rename();
+    printf(\"%u\\n\", remove(3));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("mangle_headers", &program), "renamed\n2\n");
+}
+
+// The names the generated C uses can be taken in notc
+#[test]
+fn generated_names_are_escaped() {
+    let program = common::with_printf(
+        "fn notc_lambda_0() usize -> {
+    return 1;
+}
+fn main() i32 -> {
+    let notc_result: usize = 2;
+    let twice: fn(usize) usize = |n: usize| usize -> {
+        return n * 2;
+    };
+    printf(\"%u %u\\n\", notc_lambda_0(), twice(notc_result));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("mangle_generated", &program), "1 4\n");
+}
+
+#[test]
+fn names_demangle_back() {
+    for (name, kind, c) in [
+        ("add", SymbolKind::Function, "nc_add"),
+        ("math::add", SymbolKind::Function, "nc_math__add"),
+        ("Colour::Red", SymbolKind::Variant, "nc_Colour__Red"),
+        ("main", SymbolKind::Function, "nc_main"),
+        ("math::int", SymbolKind::Function, "nc_4math3int"),
+        ("a::_b", SymbolKind::Function, "nc_1a2_b"),
+        ("two__parts", SymbolKind::Function, "nc_10two__parts"),
+        ("nc_3x", SymbolKind::Function, "nc_5nc_3x"),
+        ("notc_lambda_0", SymbolKind::Function, "nc_13notc_lambda_0"),
+        ("int", SymbolKind::Local, "nc_3int"),
+        ("notc_result", SymbolKind::Local, "nc_11notc_result"),
+    ] {
+        assert_eq!(mangle(name, kind), c);
+        assert_eq!(demangle(c).as_deref(), Some(name));
+    }
+    // Locals can hide the C library, and fields only need to stay clear of keywords. Neither is
+    // ever in a backtrace
+    assert_eq!(mangle("printf", SymbolKind::Local), "printf");
+    assert_eq!(mangle("__pad", SymbolKind::Field), "__pad");
+    assert_eq!(mangle("char", SymbolKind::Field), "nc_4char");
+    assert_eq!(demangle("printf"), None);
+    assert_eq!(demangle("__libc_start_main"), None);
+    assert_eq!(demangle("nc_9short"), None);
+}
+
+#[test]
+fn backtraces_are_demangled() {
+    let trace =
+        "#0 0x0000 in nc_4math3int () at main.c:12\n#1 nc_math__add+0x1c\n#2 __libc_start_main";
+    assert_eq!(
+        demangle_text(trace),
+        "#0 0x0000 in math::int () at main.c:12\n#1 math::add+0x1c\n#2 __libc_start_main"
+    );
+}
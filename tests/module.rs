@@ -0,0 +1,137 @@
+mod common;
+
+use notc::parsing::{LoadError, ModuleTree};
+use notc::tree_checker::ResolveError;
+use std::path::PathBuf;
+
+// Writes the modules a test program imports, by their path from the root file
+fn write_modules(name: &str, modules: &[(&str, &str)]) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    for (path, source) in modules {
+        let file = dir.join(path);
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(file, source).unwrap();
+    }
+    return dir;
+}
+
+#[test]
+fn modules_are_imported_by_path() {
+    write_modules(
+        "module_paths",
+        &[
+            (
+                "math.nc",
+                "fn helper(a: usize) usize -> {
+    return a * 10;
+}
+pub fn scale(a: usize) usize -> {
+    return helper(a);
+}
+",
+            ),
+            (
+                "shapes/square.nc",
+                "import math;
+pub struct Square {
+    side: usize,
+}
+fn helper(s: *Square) usize -> {
+    return s.side * s.side;
+}
+pub fn area(s: *Square) usize -> {
+    return math::scale(helper(s));
+}
+",
+            ),
+        ],
+    );
+    let program = common::with_printf(
+        "import math;
+import shapes::square;
+#[include(\"stdlib.h\")]
+extern fn malloc(size: usize) *square::Square;
+fn helper() usize -> {
+    return 1;
+}
+fn main() i32 -> {
+    let s: *square::Square = malloc(4);
+    s.side = 3;
+    printf(\"%u %u %u\\n\", math::scale(2), square::area(s), helper());
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("module_paths", &program), "20 90 1\n");
+}
+
+#[test]
+fn items_are_private_by_default() {
+    for (name, program, error) in [
+        (
+            "module_private_fn",
+            "import math;
+fn main() i32 -> {
+    return math::helper(1);
+}
+",
+            ResolveError::Undefined(0..0),
+        ),
+        (
+            "module_private_type",
+            "import math;
+fn main() i32 -> {
+    let x: math::Hidden = 1;
+    return 0;
+}
+",
+            ResolveError::UndefinedType(0..0),
+        ),
+        (
+            "module_not_imported",
+            "fn main() i32 -> {
+    return math::scale(1);
+}
+",
+            ResolveError::Undefined(0..0),
+        ),
+    ] {
+        write_modules(
+            name,
+            &[(
+                "math.nc",
+                "distinct type Hidden = usize;
+fn helper(a: usize) i32 -> {
+    return 0;
+}
+pub fn scale(a: usize) i32 -> {
+    return helper(a);
+}
+",
+            )],
+        );
+        let (_, _, result) = common::try_resolve(name, program);
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(
+            matched,
+            "{}: expected {:?}, got {:?}",
+            name,
+            error,
+            result.err()
+        );
+    }
+}
+
+#[test]
+fn missing_modules_are_errors() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("module_missing");
+    std::fs::create_dir_all(&dir).unwrap();
+    let root = dir.join("main.nc");
+    std::fs::write(&root, "import nowhere;\n").unwrap();
+    assert!(matches!(
+        ModuleTree::load(&root),
+        Err(LoadError::Io(file, _)) if file.ends_with("nowhere.nc")
+    ));
+}
@@ -0,0 +1,95 @@
+mod common;
+
+use notc::tree_checker::ResolveError;
+use notc::types::resolved_types::{IdentifierKind, ResolvedChunk};
+
+#[test]
+fn types_and_values_share_names() {
+    let program = common::with_printf(
+        "struct Point {
+    x: usize,
+    y: usize,
+}
+fn Point(x: usize, y: usize) Point -> {
+    let p: *Point = malloc(8);
+    p.x = x;
+    p.y = y;
+    return *p;
+}
+#[include(\"stdlib.h\")]
+extern fn malloc(size: usize) *Point;
+fn twice(bool: usize) usize -> {
+    return bool + bool;
+}
+fn main() i32 -> {
+    let usize: usize = twice(1);
+    let Point: Point = Point(usize, 3);
+    let q: Point = Point;
+    printf(\"%u %u\\n\", q.x, Point.y);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("namespace_shared", &program), "2 3\n");
+}
+
+#[test]
+fn identifiers_know_their_namespace() {
+    let program = "distinct type Meters = usize;
+fn twice(m: Meters) Meters -> {
+    return Meters(m.0 + m.0);
+}
+";
+    let (_, _, ast) = common::resolve("namespace_kinds", program);
+    let ResolvedChunk::TypeAlias { name, .. } = &ast.body[0] else {
+        panic!("Expected a distinct type");
+    };
+    assert_eq!(name.kind, IdentifierKind::Type);
+    let ResolvedChunk::Function { name, params, .. } = &ast.body[1] else {
+        panic!("Expected a function");
+    };
+    assert_eq!(name.kind, IdentifierKind::Value);
+    assert_eq!(params[0].name.kind, IdentifierKind::Value);
+}
+
+#[test]
+fn primitive_types_stay_types() {
+    for (name, program, error) in [
+        (
+            "namespace_primitive_struct",
+            "struct usize {
+    x: u8,
+}",
+            ResolveError::Duplicate(0..0),
+        ),
+        (
+            "namespace_primitive_value",
+            "fn main() i32 -> {
+    let x: usize = usize;
+    return x;
+}",
+            ResolveError::NotAValue(0..0),
+        ),
+        (
+            "namespace_local_type",
+            "fn main() i32 -> {
+    let total: usize = 1;
+    let x: total = 1;
+    return x;
+}",
+            ResolveError::NotAType(0..0),
+        ),
+    ] {
+        let (_, _, result) = common::try_resolve(name, program);
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(
+            matched,
+            "{}: expected {:?}, got {:?}",
+            name,
+            error,
+            result.err()
+        );
+    }
+}
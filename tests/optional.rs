@@ -0,0 +1,115 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn values_unwrap_with_orelse_and_if_let() {
+    let program = common::with_printf(
+        "fn find(n: usize) ?usize -> {
+    if (n == 0) {
+        return none;
+    }
+    return n;
+}
+fn main() i32 -> {
+    let a: ?usize = find(0);
+    let b: ?usize = find(7);
+    printf(\"%u %u\\n\", a orelse 42, b orelse 42);
+    if (let v = b) printf(\"got %u\\n\", v); else printf(\"nothing\\n\");
+    if (let v = a) printf(\"got %u\\n\", v); else printf(\"nothing\\n\");
+    a = 5;
+    printf(\"%u\\n\", a orelse 0);
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run("optional_values", &program),
+        "42 7\ngot 7\nnothing\n5\n"
+    );
+}
+
+#[test]
+fn pointers_are_nullable() {
+    let program = common::with_printf(
+        "fn name(n: usize) ?*u8 -> {
+    if (n == 0) {
+        return none;
+    }
+    return \"named\";
+}
+fn main() i32 -> {
+    let one: usize = 1;
+    let s: ?*u8 = name(one);
+    let t: ?*u8 = name(0);
+    if (let p = s) printf(\"%s\\n\", p);
+    if (let p = t) printf(\"%s\\n\", p); else printf(\"null\\n\");
+    let f: ?fn(usize) ?*u8 = none;
+    if (let g = f) printf(\"called\\n\"); else printf(\"no function\\n\");
+    return 0;
+}
+",
+    );
+    assert_eq!(
+        common::run("optional_pointers", &program),
+        "named\nnull\nno function\n"
+    );
+}
+
+#[test]
+fn optionals_must_be_checked() {
+    let program = "fn main() i32 -> {
+    let a: ?usize = none;
+    return a + 1;
+}
+";
+    assert!(matches!(
+        common::type_check("optional_arithmetic", program),
+        Err(TypeError::Unchecked(_))
+    ));
+
+    let program = "fn main() i32 -> {
+    let a: ?usize = none;
+    let b: usize = a;
+    return b;
+}
+";
+    assert!(matches!(
+        common::type_check("optional_assign", program),
+        Err(TypeError::DeclarationMatch(_))
+    ));
+
+    let program = "fn main() i32 -> {
+    let f: ?fn() usize = none;
+    return f();
+}
+";
+    assert!(matches!(
+        common::type_check("optional_call", program),
+        Err(TypeError::Unchecked(_))
+    ));
+}
+
+#[test]
+fn plain_pointers_are_not_nullable() {
+    let program = "fn main() i32 -> {
+    let p: *u8 = none;
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("optional_non_null", program),
+        Err(TypeError::DeclarationMatch(_))
+    ));
+
+    let program = "fn main() i32 -> {
+    let n: usize = 1;
+    if (let v = n) return v;
+    return 0;
+}
+";
+    assert!(matches!(
+        common::type_check("optional_not_optional", program),
+        Err(TypeError::NotOptional(_))
+    ));
+}
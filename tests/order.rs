@@ -0,0 +1,86 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn functions_are_used_before_they_are_defined() {
+    let program = common::with_printf(
+        "fn main() i32 -> {
+    printf(\"%u %u\\n\", is_even(10), is_odd(7));
+    return 0;
+}
+fn is_even(n: usize) usize -> {
+    if (n == 0) return 1;
+    return is_odd(n - 1);
+}
+fn is_odd(n: usize) usize -> {
+    if (n == 0) return 0;
+    return is_even(n - 1);
+}
+",
+    );
+    assert_eq!(common::run("order_functions", &program), "1 1\n");
+}
+
+#[test]
+fn types_are_defined_before_they_are_held() {
+    let program = common::with_printf(
+        "#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *Line;
+struct Line {
+    start: Point,
+    end: ?Point,
+    length: Meters,
+}
+struct Node {
+    value: usize,
+    next: ?*Node,
+    tree: *Tree,
+}
+struct Tree {
+    root: ?*Node,
+}
+distinct type Meters = usize;
+struct Point {
+    x: usize,
+    y: usize,
+}
+fn main() i32 -> {
+    let line: *Line = calloc(1, 64);
+    line.start.y = 3;
+    line.length = Meters(4);
+    line.end = none;
+    printf(\"%u %u\\n\", line.start.y, line.length.0);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("order_types", &program), "3 4\n");
+}
+
+// Without a pointer between them, each would have to be bigger than the other
+#[test]
+fn types_cannot_hold_themselves() {
+    for (name, program) in [
+        (
+            "order_cycle",
+            "struct A {\n    b: B,\n}\nstruct B {\n    a: ?A,\n}\n",
+        ),
+        (
+            "order_cycle_self",
+            "struct List {\n    value: usize,\n    rest: (usize, List),\n}\n",
+        ),
+        (
+            "order_cycle_distinct",
+            "distinct type Wrapped = Holder;\nstruct Holder {\n    inner: usize!Wrapped,\n}\n",
+        ),
+    ] {
+        let result = common::type_check(name, program);
+        assert!(
+            matches!(result, Err(TypeError::RecursiveType(_))),
+            "{}: got {:?}",
+            name,
+            result
+        );
+    }
+}
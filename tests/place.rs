@@ -0,0 +1,89 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+const HEAP: &str = "#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *Point;
+#[include(\"stdlib.h\")]
+extern fn malloc(size: usize) *usize;
+struct Point {
+    x: usize,
+    y: usize,
+}
+";
+
+#[test]
+fn fields_indexes_and_derefs_are_assigned() {
+    let program = common::with_printf(&format!(
+        "{HEAP}
+fn main() i32 -> {{
+    let p: *Point = calloc(2, 8);
+    p.x = 3;
+    p.y += 4;
+    p[1].x = 7;
+    let v: Point = *p;
+    v.x *= 10;
+    *p = v;
+    let nums: *usize = malloc(16);
+    *nums = 5;
+    let i: usize = 1;
+    nums[i] = nums[0];
+    nums[i] += 2;
+    printf(\"%u %u %u %u\\n\", p.x, p[0].y, p[1].x, nums[1]);
+    return 0;
+}}
+"
+    ));
+    assert_eq!(common::run("place_assign", &program), "30 4 7 7\n");
+}
+
+#[test]
+fn tuple_fields_are_assigned() {
+    let program = common::with_printf(
+        "fn main() i32 -> {
+    let pair: (usize, usize) = (1, 2);
+    pair.1 = 5;
+    printf(\"%u %u\\n\", pair.0, pair.1);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("place_tuple", &program), "1 5\n");
+}
+
+#[test]
+fn only_places_are_assigned() {
+    for (name, statement, error) in [
+        (
+            "place_function",
+            "main = main;",
+            TypeError::NotAssignable(0..0),
+        ),
+        ("place_value", "*v = 1;", TypeError::TypeMismatch(0..0)),
+        ("place_field", "v.z = 1;", TypeError::NoField(0..0)),
+        ("place_index", "v[0] = 1;", TypeError::TypeMismatch(0..0)),
+        ("place_type", "p.x = p;", TypeError::AssignmentMatch(0..0)),
+    ] {
+        let program = common::with_printf(&format!(
+            "{HEAP}
+fn main() i32 -> {{
+    let p: *Point = calloc(1, 8);
+    let v: Point = *p;
+    {}
+    return 0;
+}}
+",
+            statement
+        ));
+        let result = common::type_check(name, &program);
+        // Only the kind of error matters, not where it is
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(
+            matched,
+            "{}: expected {:?}, got {:?}",
+            statement, error, result
+        );
+    }
+}
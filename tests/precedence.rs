@@ -0,0 +1,376 @@
+mod common;
+
+use notc::lexer::Lexer;
+use notc::parsing::{ParseError, SpannedAstTree};
+use notc::traits::LexerTrait;
+use notc::types::Token;
+use notc::types::spanned_types::{BinaryOperation, SpannedExpression, SpannedStatement};
+use std::rc::Rc;
+
+fn parse(source: &str) -> Result<SpannedExpression, ParseError> {
+    let source: Rc<str> = source.into();
+    let mut lexer = Lexer::from_rc_str(source.clone());
+    let expression = SpannedAstTree::from_rc_str(source).parse_expression(&mut lexer)?;
+    assert!(
+        matches!(lexer.next_token(), Token::EOF(_)),
+        "Expression ended early"
+    );
+    Ok(expression)
+}
+
+// Writes the tree back out with every operation in parentheses, eg ((a - b) - c)
+fn grouped(expression: &SpannedExpression, source: &str) -> String {
+    match expression {
+        SpannedExpression::Identifier(id) => source[id.span.clone()].to_string(),
+        SpannedExpression::Literal { span } => source[span.clone()].to_string(),
+        SpannedExpression::UnaryOperator {
+            operation,
+            expression,
+        } => format!(
+            "({}{})",
+            &source[operation.clone()],
+            grouped(expression, source)
+        ),
+        SpannedExpression::Deref { expression, .. } => {
+            format!("(*{})", grouped(expression, source))
+        }
+        SpannedExpression::BinaryOperator {
+            left, span, right, ..
+        } => format!(
+            "({} {} {})",
+            grouped(left, source),
+            &source[span.clone()],
+            grouped(right, source)
+        ),
+        SpannedExpression::Orelse { left, right, .. } => format!(
+            "({} orelse {})",
+            grouped(left, source),
+            grouped(right, source)
+        ),
+        SpannedExpression::Field {
+            expression, field, ..
+        } => format!(
+            "{}.{}",
+            grouped(expression, source),
+            &source[field.span.clone()]
+        ),
+        SpannedExpression::TupleField { tuple, index, .. } => {
+            format!("{}.{}", grouped(tuple, source), index)
+        }
+        SpannedExpression::Call { name, params, .. } => format!(
+            "{}({})",
+            &source[name.span.clone()],
+            params
+                .iter()
+                .map(|p| grouped(p, source))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        e => panic!("Not expected in these tests: {:?}", e),
+    }
+}
+
+#[test]
+fn operators_group_by_precedence_and_associativity() {
+    for (source, expected) in [
+        ("a - b - c", "((a - b) - c)"),
+        ("a / b / c", "((a / b) / c)"),
+        ("a - b * c - d", "((a - (b * c)) - d)"),
+        ("a * (b - c) / d", "((a * (b - c)) / d)"),
+        ("!a == b", "((!a) == b)"),
+        ("!(a == b)", "(!(a == b))"),
+        ("-a * -b", "((-a) * (-b))"),
+        ("- - a - b", "((-(-a)) - b)"),
+        ("*p.x + 1", "((*p.x) + 1)"),
+        ("-f(a - b - c) * 2", "((-f(((a - b) - c))) * 2)"),
+        ("a orelse b orelse c", "(a orelse (b orelse c))"),
+        ("t.0.x", "t.0.x"),
+        ("t.0.1", "t.0.1"),
+        ("t.0.1.x - t.1.y", "(t.0.1.x - t.1.y)"),
+        ("t.x.0", "t.x.0"),
+        ("a orelse 1 + 2 == 3", "((a orelse (1 + 2)) == 3)"),
+    ] {
+        let expression = parse(source).unwrap();
+        assert_eq!(grouped(&expression, source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn chains_that_dont_group_are_rejected() {
+    assert!(matches!(
+        parse("a == b == c"),
+        Err(ParseError::BadSyntax(Token::Operator(_), _))
+    ));
+
+    let source: Rc<str> = "a = b = c;".into();
+    let mut lexer = Lexer::from_rc_str(source.clone());
+    let statement = SpannedAstTree::from_rc_str(source).parse_statement(&mut lexer);
+    assert!(matches!(
+        statement,
+        Err(ParseError::BadSyntax(Token::Operator(_), _))
+    ));
+
+    for (source, operation) in [
+        ("a = b + c;", None),
+        ("a += b;", Some(BinaryOperation::Add)),
+        ("a /= b;", Some(BinaryOperation::Divide)),
+    ] {
+        let source: Rc<str> = source.into();
+        let mut lexer = Lexer::from_rc_str(source.clone());
+        let statement = SpannedAstTree::from_rc_str(source).parse_statement(&mut lexer);
+        assert!(matches!(
+            statement,
+            Ok(SpannedStatement::Reassignment { operation: o, .. }) if o == operation
+        ));
+    }
+}
+
+#[test]
+fn parentheses_carry_into_c() {
+    let program = common::with_printf(
+        "#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *Point;
+struct Point {
+    x: usize,
+    y: usize,
+}
+fn main() i32 -> {
+    let p: *Point = calloc(1, 8);
+    p.x = 2;
+    p.y = 3;
+    printf(\"%u %u %u\\n\", (*p).x * (p.y + 1), (p.x + 4) / (p.y - 1), 10 - (4 - 3));
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("precedence_parens", &program), "8 3 9\n");
+}
+
+// Property tests, parsing random expressions and checking they evaluate the same as a textbook
+// recursive descent parser would have them
+//
+// xorshift, so the runs are repeatable without another dependency
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+// The operators random expressions are made of
+struct Operators {
+    unary: &'static [&'static str],
+    binary: &'static [&'static str],
+}
+const ALL: Operators = Operators {
+    unary: &["-", "!"],
+    binary: &["+", "-", "*", "/", "=="],
+};
+
+// An expression as its tokens. Comparisons don't chain, so there is at most one per group
+fn random_expression(rng: &mut Rng, operators: &Operators, depth: usize, tokens: &mut Vec<String>) {
+    let mut compared = false;
+    loop {
+        for _ in 0..rng.below(3) {
+            let unary = operators.unary[rng.below(operators.unary.len() as u64) as usize];
+            tokens.push(unary.to_string());
+        }
+        if depth > 0 && rng.below(4) == 0 {
+            tokens.push("(".to_string());
+            random_expression(rng, operators, depth - 1, tokens);
+            tokens.push(")".to_string());
+        } else {
+            tokens.push(rng.below(10).to_string());
+        }
+
+        if rng.below(3) == 0 {
+            return;
+        }
+        let binary = operators.binary;
+        let mut operator = binary[rng.below(binary.len() as u64) as usize];
+        if operator == "==" {
+            if compared {
+                operator = "+";
+            }
+            compared = true;
+        }
+        tokens.push(operator.to_string());
+    }
+}
+
+// Division by zero has no value
+fn binary(operator: &str, left: Option<u32>, right: Option<u32>) -> Option<u32> {
+    let (left, right) = (left?, right?);
+    match operator {
+        "+" => Some(left.wrapping_add(right)),
+        "-" => Some(left.wrapping_sub(right)),
+        "*" => Some(left.wrapping_mul(right)),
+        "/" if right == 0 => None,
+        "/" => Some(left.wrapping_div(right)),
+        "==" => Some((left == right) as u32),
+        o => panic!("Unknown operator {}", o),
+    }
+}
+fn unary(operator: &str, value: Option<u32>) -> Option<u32> {
+    match operator {
+        "-" => Some(value?.wrapping_neg()),
+        "!" => Some((value? == 0) as u32),
+        o => panic!("Unknown operator {}", o),
+    }
+}
+
+fn evaluate(expression: &SpannedExpression, source: &str) -> Option<u32> {
+    match expression {
+        SpannedExpression::Literal { span } => Some(source[span.clone()].parse().unwrap()),
+        SpannedExpression::UnaryOperator {
+            operation,
+            expression,
+        } => unary(&source[operation.clone()], evaluate(expression, source)),
+        // By what the parser says the operator does, rather than its text
+        SpannedExpression::BinaryOperator {
+            left,
+            operation,
+            right,
+            ..
+        } => {
+            let operator = match operation {
+                BinaryOperation::Equal => "==",
+                BinaryOperation::Add => "+",
+                BinaryOperation::Subtract => "-",
+                BinaryOperation::Multiply => "*",
+                BinaryOperation::Divide => "/",
+            };
+            binary(operator, evaluate(left, source), evaluate(right, source))
+        }
+        e => panic!("Not expected in these tests: {:?}", e),
+    }
+}
+
+// comparison := sum ('==' sum)?
+// sum := product (('+' | '-') product)*
+// product := prefix (('*' | '/') prefix)*
+// prefix := ('-' | '!') prefix | number | '(' comparison ')'
+struct Reference<'a> {
+    tokens: &'a [String],
+    next: usize,
+}
+impl Reference<'_> {
+    fn peek(&self) -> Option<&str> {
+        self.tokens.get(self.next).map(|t| t.as_str())
+    }
+    fn take(&mut self) -> &str {
+        self.next += 1;
+        &self.tokens[self.next - 1]
+    }
+    fn comparison(&mut self) -> Option<u32> {
+        let left = self.sum();
+        if self.peek() == Some("==") {
+            self.take();
+            let right = self.sum();
+            return binary("==", left, right);
+        }
+        left
+    }
+    fn sum(&mut self) -> Option<u32> {
+        let mut left = self.product();
+        while let Some(operator @ ("+" | "-")) = self.peek() {
+            let operator = operator.to_string();
+            self.take();
+            let right = self.product();
+            left = binary(&operator, left, right);
+        }
+        left
+    }
+    fn product(&mut self) -> Option<u32> {
+        let mut left = self.prefix();
+        while let Some(operator @ ("*" | "/")) = self.peek() {
+            let operator = operator.to_string();
+            self.take();
+            let right = self.prefix();
+            left = binary(&operator, left, right);
+        }
+        left
+    }
+    fn prefix(&mut self) -> Option<u32> {
+        match self.take().to_string().as_str() {
+            operator @ ("-" | "!") => {
+                let value = self.prefix();
+                unary(operator, value)
+            }
+            "(" => {
+                let value = self.comparison();
+                assert_eq!(self.take(), ")");
+                value
+            }
+            number => Some(number.parse().unwrap()),
+        }
+    }
+}
+
+#[test]
+fn random_expressions_match_the_reference() {
+    let mut rng = Rng(0x2545F4914F6CDD1D);
+    for _ in 0..5000 {
+        let mut tokens = Vec::new();
+        random_expression(&mut rng, &ALL, 3, &mut tokens);
+        let source = tokens.join(" ");
+
+        let expression = parse(&source).unwrap();
+        let mut reference = Reference {
+            tokens: &tokens,
+            next: 0,
+        };
+        let expected = reference.comparison();
+        assert_eq!(reference.next, tokens.len());
+        assert_eq!(evaluate(&expression, &source), expected, "{}", source);
+    }
+}
+
+// The C written for random expressions works out the same as the reference does. Literals are
+// ints in C, so the numbers are usize variables instead, and the comparisons and ! that C makes
+// ints of are left out
+#[test]
+fn c_evaluates_random_expressions_like_the_tree() {
+    const ARITHMETIC: Operators = Operators {
+        unary: &["-"],
+        binary: &["+", "-", "*", "/"],
+    };
+    let mut rng = Rng(0x9E3779B97F4A7C15);
+    let mut program = common::with_printf("fn main() i32 -> {\n");
+    for n in 0..10 {
+        program += &format!("    let v{}: usize = {};\n", n, n);
+    }
+
+    let mut expected = String::new();
+    while expected.lines().count() < 300 {
+        let mut tokens = Vec::new();
+        random_expression(&mut rng, &ARITHMETIC, 3, &mut tokens);
+        let mut reference = Reference {
+            tokens: &tokens,
+            next: 0,
+        };
+        // C leaves dividing by zero undefined
+        let Some(value) = reference.comparison() else {
+            continue;
+        };
+        expected += &format!("{}\n", value);
+
+        let variables: Vec<String> = tokens
+            .iter()
+            .map(|t| match t.parse::<u32>() {
+                Ok(n) => format!("v{}", n),
+                Err(_) => t.clone(),
+            })
+            .collect();
+        program += &format!("    printf(\"%u\\n\", {});\n", variables.join(" "));
+    }
+    program += "    return 0;\n}\n";
+
+    assert_eq!(common::run("precedence_c", &program), expected);
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use notc::tree_checker::ResolveError;
+
+#[test]
+fn names_are_resolved_or_reported() {
+    for (name, program, error) in [
+        (
+            "resolve_variable",
+            "fn main() i32 -> {
+    return count;
+}",
+            ResolveError::Undefined(0..0),
+        ),
+        (
+            "resolve_function",
+            "fn main() i32 -> {
+    return mian();
+}",
+            ResolveError::Undefined(0..0),
+        ),
+        (
+            "resolve_type",
+            "fn main() i32 -> {
+    let x: uszie = 1;
+    return x;
+}",
+            ResolveError::UndefinedType(0..0),
+        ),
+        (
+            "resolve_duplicate_function",
+            "fn main() i32 -> {
+    return 0;
+}
+fn main() i32 -> {
+    return 1;
+}",
+            ResolveError::Duplicate(0..0),
+        ),
+        (
+            "resolve_duplicate_param",
+            "fn add(a: usize, a: usize) usize -> {
+    return a;
+}",
+            ResolveError::DuplicateParam(0..0),
+        ),
+        (
+            "resolve_not_a_type",
+            "fn main() i32 -> {
+    let x: main = 1;
+    return 0;
+}",
+            ResolveError::NotAType(0..0),
+        ),
+        (
+            "resolve_not_a_value",
+            "struct Point {
+    x: usize,
+}
+fn main() i32 -> {
+    let p: usize = Point;
+    return 0;
+}",
+            ResolveError::NotAValue(0..0),
+        ),
+        (
+            "resolve_alias_cycle",
+            "type Loop = *Loop;
+fn main() i32 -> {
+    return 0;
+}
+fn take(l: Loop) void -> {}",
+            ResolveError::AliasCycle(0..0),
+        ),
+        (
+            "resolve_alias_mutual_cycle",
+            "type A = B;
+type B = (usize, A);
+fn main() i32 -> {
+    let x: A = 1;
+    return 0;
+}",
+            ResolveError::AliasCycle(0..0),
+        ),
+        (
+            "resolve_param_not_a_type",
+            "fn take(count: usize) void -> {
+    let x: count = 1;
+}",
+            ResolveError::NotAType(0..0),
+        ),
+    ] {
+        let (_, _, result) = common::try_resolve(name, program);
+        // Only the kind of error matters, not where it is
+        let matched = result
+            .as_ref()
+            .is_err_and(|e| std::mem::discriminant(e) == std::mem::discriminant(&error));
+        assert!(
+            matched,
+            "{}: expected {:?}, got {:?}",
+            name,
+            error,
+            result.err()
+        );
+    }
+}
+
+#[test]
+fn errors_point_at_the_name() {
+    let program = "fn main() i32 -> {
+    let total: usize = 1;
+    return totl;
+}";
+    let (_, source, result) = common::try_resolve("resolve_span", program);
+    let Err(ResolveError::Undefined(span)) = result else {
+        panic!("Expected an undefined name");
+    };
+    assert_eq!(&source[span], "totl");
+}
+
+#[test]
+fn externs_can_be_declared_again() {
+    let program = "#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+distinct type Meters = usize;
+fn main() i32 -> {
+    let m: Meters = Meters(3);
+    printf(\"%u\\n\", m.0);
+    return 0;
+}
+";
+    assert_eq!(common::run("resolve_extern_again", program), "3\n");
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+const PROGRAM: &str = "#[include(\"stdio.h\")]
+extern fn printf(format: *u8, ...) void;
+
+enum ParseError {
+    Empty,
+    TooBig,
+}
+
+fn parse(n: usize) usize!ParseError -> {
+    if (n == 0) {
+        return ParseError::Empty;
+    }
+    if (n == 100) {
+        return ParseError::TooBig;
+    }
+    return n;
+}
+";
+
+#[test]
+fn errors_pass_up_with_try() {
+    let program = format!(
+        "{PROGRAM}
+fn twice(n: usize) usize!ParseError -> {{
+    defer printf(\"leaving\\n\");
+    let v: usize = parse(n)?;
+    return v + v;
+}}
+fn main() i32 -> {{
+    let four: usize = 4;
+    printf(\"%u\\n\", twice(four) orelse 0);
+    printf(\"%u\\n\", twice(0) orelse 99);
+    if (let v = twice(100)) printf(\"%u\\n\", v); else printf(\"too big\\n\");
+    return 0;
+}}
+"
+    );
+    assert_eq!(
+        common::run("result_try", &program),
+        "leaving\n8\nleaving\n99\nleaving\ntoo big\n"
+    );
+}
+
+#[test]
+fn void_results_succeed_at_the_end() {
+    let program = format!(
+        "{PROGRAM}
+fn check(n: usize) void!ParseError -> {{
+    if (n == 0) {{
+        return ParseError::Empty;
+    }}
+}}
+fn run(n: usize) void!ParseError -> {{
+    check(n)?;
+    printf(\"checked %u\\n\", n);
+}}
+fn main() i32 -> {{
+    let one: usize = 1;
+    if (let v = run(one)) printf(\"ok\\n\");
+    if (let v = run(0)) printf(\"ok\\n\"); else printf(\"failed\\n\");
+    return 0;
+}}
+"
+    );
+    assert_eq!(
+        common::run("result_void", &program),
+        "checked 1\nok\nfailed\n"
+    );
+}
+
+#[test]
+fn errors_cannot_be_ignored() {
+    let program = format!(
+        "{PROGRAM}
+fn check(n: usize) void!ParseError -> {{
+    return;
+}}
+fn main() i32 -> {{
+    check(0);
+    return 0;
+}}
+"
+    );
+    assert!(matches!(
+        common::type_check("result_ignored", &program),
+        Err(TypeError::IgnoredError(_))
+    ));
+}
+
+#[test]
+fn try_needs_a_matching_result() {
+    let program = format!(
+        "{PROGRAM}
+fn main() i32 -> {{
+    let v: usize = parse(1)?;
+    return v;
+}}
+"
+    );
+    assert!(matches!(
+        common::type_check("result_try_plain", &program),
+        Err(TypeError::BadTry(_))
+    ));
+
+    let program = format!(
+        "{PROGRAM}
+enum OtherError {{
+    Other,
+}}
+fn other() usize!OtherError -> {{
+    let v: usize = parse(1)?;
+    return v;
+}}
+"
+    );
+    assert!(matches!(
+        common::type_check("result_try_other", &program),
+        Err(TypeError::BadTry(_))
+    ));
+
+    let program = format!(
+        "{PROGRAM}
+fn other() usize!ParseError -> {{
+    let v: usize = parse(1)? + 1;
+    defer parse(v)?;
+    return v;
+}}
+"
+    );
+    assert!(matches!(
+        common::type_check("result_try_defer", &program),
+        Err(TypeError::DeferReturn(_))
+    ));
+}
+
+// A value of a T!T could be either side
+#[test]
+fn ok_and_error_types_differ() {
+    for (name, program) in [
+        (
+            "result_same_return",
+            "fn f() usize!usize -> {\n    return 1;\n}\n",
+        ),
+        (
+            "result_same_alias",
+            "type Code = i32;\nfn f(n: fn() Code!i32) void -> {\n    return;\n}\n",
+        ),
+        (
+            "result_same_local",
+            "fn f() void -> {\n    let r: (usize, u8!u8) = (0, 1);\n}\n",
+        ),
+        (
+            "result_same_field",
+            "struct S {\n    r: fn() ParseError!ParseError,\n}\n",
+        ),
+        ("result_same_extern", "extern fn f() i32!i32;\n"),
+    ] {
+        let result = common::type_check(name, &format!("{PROGRAM}{program}"));
+        assert!(
+            matches!(result, Err(TypeError::SameResultTypes(_))),
+            "{}: got {:?}",
+            name,
+            result
+        );
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+use notc::tree_checker::ResolveError;
+use notc::types::resolved_types::{
+    ResolvedChunk, ResolvedExpression, ResolvedStatement, SymbolKind,
+};
+
+#[test]
+fn locals_shadow_each_other() {
+    let program = common::with_printf(
+        "fn twice(x: usize) usize -> {
+    let x: usize = x + x;
+    return x;
+}
+fn main() i32 -> {
+    let x: usize = 1;
+    let x: usize = x + 1;
+    {
+        let x: usize = twice(x);
+        printf(\"%u \", x);
+    }
+    printf(\"%u\\n\", x);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("scope_shadow", &program), "4 2\n");
+}
+
+#[test]
+fn bindings_end_with_their_scope() {
+    let program = "fn main() i32 -> {
+    {
+        let inner: usize = 1;
+    }
+    return inner;
+}
+";
+    assert!(matches!(
+        common::try_resolve("scope_block", program).2,
+        Err(ResolveError::Undefined(_))
+    ));
+
+    let program = "fn main() i32 -> {
+    let maybe: ?usize = none;
+    if (let value = maybe) return value;
+    return value;
+}
+";
+    assert!(matches!(
+        common::try_resolve("scope_if_let", program).2,
+        Err(ResolveError::Undefined(_))
+    ));
+}
+
+#[test]
+fn symbols_record_their_kind_and_span() {
+    let program = "struct Point {
+    x: usize,
+}
+fn get(p: Point) usize -> {
+    let x: usize = p.x;
+    return x;
+}
+";
+    let (_, source, ast) = common::resolve("scope_symbols", program);
+    let symbol = |id: usize| {
+        let symbol = &ast.symbols[id];
+        let span = symbol.span.clone().expect("Bound nowhere");
+        (symbol.kind, &source[span])
+    };
+
+    let ResolvedChunk::Struct { name, .. } = &ast.body[0] else {
+        panic!("Expected a struct");
+    };
+    assert_eq!(symbol(name.id.0), (SymbolKind::Type, "Point"));
+
+    let ResolvedChunk::Function {
+        name, params, body, ..
+    } = &ast.body[1]
+    else {
+        panic!("Expected a function");
+    };
+    assert_eq!(symbol(name.id.0), (SymbolKind::Function, "get"));
+    assert_eq!(symbol(params[0].name.id.0), (SymbolKind::Param, "p"));
+
+    let ResolvedStatement::Scope { body, .. } = body else {
+        panic!("Expected a scope");
+    };
+    let ResolvedStatement::Decleration { name, .. } = &body[0] else {
+        panic!("Expected a decleration");
+    };
+    assert_eq!(symbol(name.id.0), (SymbolKind::Local, "x"));
+    // The local x is its own binding, not the field
+    let ResolvedStatement::Return {
+        expression: Some(expression),
+        ..
+    } = &body[1]
+    else {
+        panic!("Expected a return");
+    };
+    let ResolvedExpression::Identifier(x, _) = &**expression else {
+        panic!("Expected an identifier");
+    };
+    assert_eq!(x.id, name.id);
+}
@@ -0,0 +1,206 @@
+mod common;
+
+use notc::cfront::translate::{check, translate};
+use std::path::PathBuf;
+use std::process::Command;
+
+// Fields, indexes and dereferences, assigned through pointers and read back
+const COUNTER: &str = "#include <stdio.h>
+#include \"alloc.h\"
+
+struct counter {
+    unsigned int count;
+    unsigned int step;
+};
+
+/* Moves the counter on by its step */
+static unsigned int bump(struct counter *c) {
+    c->count += c->step;
+    return c->count;
+}
+
+int main(void) {
+    struct counter *c = alloc(8);
+    unsigned int *totals = alloc(8);
+    c->step = 2;
+    (*c).count = 1;
+    (void)bump(c);
+    totals[1] = bump(c);
+    *totals = c->count - 1;
+    if (totals[0] == 4) {
+        printf(\"%u %u\\n\", totals[0], totals[1]);
+    } else {
+        printf(\"no\\n\");
+    }
+    return 0;
+}
+";
+
+// calloc takes size_t, which notc can't import, so the programs allocate through this
+const ALLOC: &str = "#include <stdlib.h>
+static void *alloc(unsigned int size) {
+    return calloc(size, 1);
+}
+";
+
+// The translation prints the same as the C it came from
+#[test]
+fn translations_run_like_the_c() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("translate_run");
+    std::fs::create_dir_all(&dir).unwrap();
+    let c = dir.join("counter.c");
+    std::fs::write(&c, COUNTER).unwrap();
+    std::fs::write(dir.join("alloc.h"), ALLOC).unwrap();
+    let exe = dir.join("counter");
+    let gcc = Command::new("gcc")
+        .arg(&c)
+        .arg("-o")
+        .arg(&exe)
+        .output()
+        .unwrap();
+    assert!(gcc.status.success());
+    let expected = Command::new(&exe).output().unwrap().stdout;
+
+    let translation = translate(COUNTER);
+    assert_eq!(translation.todos, 0, "{}", translation.source);
+    for line in [
+        "    c.count += c.step;",
+        "    (*c).count = 1;",
+        "    _ = bump(c);",
+        "    totals[1] = bump(c);",
+        "    *totals = c.count - 1;",
+    ] {
+        assert!(
+            translation.source.contains(line),
+            "{} not in\n{}",
+            line,
+            translation.source
+        );
+    }
+    assert_eq!(
+        common::run("translate_run", &translation.source).as_bytes(),
+        expected
+    );
+}
+
+#[test]
+fn unsupported_c_becomes_todos() {
+    let translation = translate(
+        "unsigned int sum(unsigned int *nums, unsigned int count) {
+    unsigned int total = 0;
+    for (unsigned int i = 0; i < count; i++) {
+        total += nums[i];
+    }
+    unsigned int *first = &nums[0];
+    total + 1;
+    return total;
+}
+",
+    );
+    assert_eq!(translation.todos, 3, "{}", translation.source);
+    for todo in [
+        "// TODO: notc has no loops yet",
+        "// TODO: notc has no unary & operator yet",
+        "// TODO: only calls, assignments and (void) discards can be statements",
+    ] {
+        assert!(
+            translation.source.contains(todo),
+            "{} not in\n{}",
+            todo,
+            translation.source
+        );
+    }
+    // What is kept still parses
+    assert!(check(&translation.source).is_ok());
+}
+
+#[test]
+fn typedefs_become_aliases() {
+    let translation = translate(
+        "#include <stdio.h>
+
+typedef unsigned int count_t;
+typedef struct {
+    count_t total;
+} tally;
+typedef tally *tally_ref;
+unsigned int hits = 0;
+
+static count_t add(tally_ref t, count_t n) {
+    t->total += n;
+    return t->total;
+}
+",
+    );
+    assert_eq!(translation.todos, 1, "{}", translation.source);
+    for line in [
+        "type count_t = usize;",
+        "struct tally {\n    total: count_t,\n}",
+        "type tally_ref = *tally;",
+        "fn add(t: tally_ref, n: count_t) count_t -> ",
+        "// TODO: global variables aren't supported yet\n//     unsigned int hits = 0;",
+    ] {
+        assert!(
+            translation.source.contains(line),
+            "{} not in\n{}",
+            line,
+            translation.source
+        );
+    }
+    assert!(!translation.source.contains("type tally ="));
+    assert!(common::type_check("translate_typedefs", &translation.source).is_ok());
+}
+
+// Compiling a translation writes C, which mustn't be the C it was translated from
+#[test]
+fn the_c_source_is_never_overwritten() {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("translate_cli");
+    // Left from the last run, with files notc didn't write
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("counter.c"), COUNTER).unwrap();
+    std::fs::write(dir.join("alloc.h"), ALLOC).unwrap();
+    let notc = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_notc"))
+            .args(args)
+            .current_dir(&dir)
+            .output()
+            .unwrap()
+            .status
+            .success()
+    };
+
+    assert!(notc(&["from-c", "counter.c"]));
+    assert!(!notc(&["counter.nc"]));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("counter.c")).unwrap(),
+        COUNTER
+    );
+    // Nor is a hand written .nc file
+    assert!(
+        std::fs::read_to_string(dir.join("counter.nc"))
+            .unwrap()
+            .starts_with("// Generated by notc\n")
+    );
+    std::fs::write(
+        dir.join("counter.nc"),
+        "fn main() i32 -> {\n    return 0;\n}\n",
+    )
+    .unwrap();
+    assert!(!notc(&["from-c", "counter.c"]));
+
+    // Another file is given instead, and can be written again
+    assert!(notc(&["from-c", "counter.c", "--out=translated.nc"]));
+    assert!(notc(&["translated.nc", "--out=translated.c"]));
+    assert!(notc(&["translated.nc", "--out=translated.c"]));
+    assert!(
+        Command::new("gcc")
+            .arg("translated.c")
+            .arg("-o")
+            .arg("translated")
+            .current_dir(&dir)
+            .status()
+            .unwrap()
+            .success()
+    );
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use notc::tree_checker::TypeError;
+
+#[test]
+fn tuples_are_returned_and_destructured() {
+    let program = common::with_printf(
+        "fn divmod(a: usize, b: usize) (usize, usize) -> {
+    let q: usize = a / b;
+    let whole: usize = q * b;
+    return (q, a - whole);
+}
+fn main() i32 -> {
+    let (q, r) = divmod(7, 2);
+    printf(\"%u %u\\n\", q, r);
+    let pair: (usize, *u8) = (3, \"three\");
+    printf(\"%u %s\\n\", pair.0, pair.1);
+    printf(\"%u\\n\", divmod(9, 4).1);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("tuple_divmod", &program), "3 1\n3 three\n1\n");
+}
+
+#[test]
+fn tuples_nest_and_hold_optionals() {
+    let program = common::with_printf(
+        "fn main() i32 -> {
+    let nested: ((usize, usize), ?usize) = ((1, 2), none);
+    printf(\"%u %u\\n\", nested.0.1, nested.1 orelse 9);
+    let (inner, maybe) = nested;
+    let (a, b) = inner;
+    printf(\"%u %u %u\\n\", a, b, maybe orelse 8);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("tuple_nested", &program), "2 9\n1 2 8\n");
+}
+
+// A number then a dot lexes as one literal, eg the 0. in pair.0.x
+#[test]
+fn fields_follow_tuple_fields() {
+    let program = common::with_printf(
+        "#[include(\"stdlib.h\")]
+extern fn calloc(count: usize, size: usize) *Point;
+struct Point {
+    x: usize,
+    y: usize,
+}
+fn main() i32 -> {
+    let p: *Point = calloc(1, 8);
+    p.x = 3;
+    p.y = 4;
+    let pair: (Point, (usize, Point)) = (*p, (5, *p));
+    pair.0.x = 6;
+    printf(\"%u %u %u %u\\n\", pair.0.x, pair.0.y, pair.1.0, pair.1.1.y);
+    return 0;
+}
+",
+    );
+    assert_eq!(common::run("tuple_field_names", &program), "6 4 5 4\n");
+}
+
+#[test]
+fn tuple_fields_are_checked() {
+    let program = "fn main() i32 -> {
+    let pair: (usize, usize) = (1, 2);
+    return pair.2;
+}
+";
+    assert_eq!(
+        common::type_check("tuple_field", program),
+        Err(TypeError::NoField(74..75))
+    );
+
+    let program = "fn main() i32 -> {
+    let pair: (usize, usize) = (1, 2);
+    let (a, b, c) = pair;
+    return a;
+}
+";
+    assert!(matches!(
+        common::type_check("tuple_destructure", program),
+        Err(TypeError::DeclarationMatch(_))
+    ));
+}
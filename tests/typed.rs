@@ -0,0 +1,186 @@
+mod common;
+
+use notc::traits::TreeChecker;
+use notc::tree_checker::{ResolvedAstTree, TypeChecker, TypeId};
+use notc::types::resolved_types::{
+    IdentifierId, ResolvedChunk, ResolvedExpression, ResolvedStatement,
+};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+const PROGRAM: &str = "struct Point {
+    x: usize,
+    y: usize,
+}
+distinct type Meters = usize;
+fn split(p: *Point, m: Meters) (usize, ?Meters) -> {
+    let (a, b) = (p.x, m);
+    let twice: fn(usize) usize = |n: usize| usize -> {
+        return n * 2;
+    };
+    return (twice(a) + m.0, none);
+}
+";
+
+fn id(ast: &ResolvedAstTree, name: &str) -> IdentifierId {
+    let i = ast.symbols.iter().position(|s| *s.name == *name).unwrap();
+    IdentifierId(i)
+}
+// The span of `len` characters, `skip` characters into where the text is in the program
+fn at(text: &str, skip: usize, len: usize) -> std::ops::Range<usize> {
+    let start = PROGRAM.find(text).unwrap() + skip;
+    start..start + len
+}
+
+#[test]
+fn every_binding_has_a_type() {
+    let (_, _, ast) = common::resolve("typed_bindings", PROGRAM);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).unwrap();
+    let bindings = &checker.notes.bindings;
+
+    let point = TypeId::Named(id(&ast, "Point"));
+    let meters = TypeId::Named(id(&ast, "Meters"));
+    assert_eq!(bindings[&id(&ast, "p")], TypeId::Pointer(Rc::new(point)));
+    assert_eq!(bindings[&id(&ast, "a")], TypeId::Usize);
+    assert_eq!(bindings[&id(&ast, "b")], meters.clone());
+    assert_eq!(bindings[&id(&ast, "n")], TypeId::Usize);
+    assert_eq!(
+        bindings[&id(&ast, "split")],
+        TypeId::Fn {
+            params: [bindings[&id(&ast, "p")].clone(), meters.clone()].into(),
+            ret: Rc::new(TypeId::Tuple(
+                [TypeId::Usize, TypeId::Optional(Rc::new(meters))].into()
+            )),
+            variadic: false,
+        }
+    );
+}
+
+// Every expression in the program, parents before their children
+fn nodes(ast: &ResolvedAstTree) -> Vec<&ResolvedExpression> {
+    fn walk_statement<'a>(s: &'a ResolvedStatement, out: &mut Vec<&'a ResolvedExpression>) {
+        match s {
+            ResolvedStatement::Scope { body, .. } => {
+                body.iter().for_each(|s| walk_statement(s, out))
+            }
+            ResolvedStatement::Decleration { expression, .. }
+            | ResolvedStatement::Destructure { expression, .. }
+            | ResolvedStatement::Expression { expression, .. }
+            | ResolvedStatement::Return {
+                expression: Some(expression),
+                ..
+            } => walk_expression(expression, out),
+            s => panic!("Not expected in these tests: {:?}", s),
+        }
+    }
+    fn walk_expression<'a>(e: &'a ResolvedExpression, out: &mut Vec<&'a ResolvedExpression>) {
+        out.push(e);
+        match e {
+            ResolvedExpression::BinaryOperator { left, right, .. } => {
+                walk_expression(left, out);
+                walk_expression(right, out);
+            }
+            ResolvedExpression::Tuple {
+                elements: children, ..
+            }
+            | ResolvedExpression::Call {
+                params: children, ..
+            } => children.iter().for_each(|c| walk_expression(c, out)),
+            ResolvedExpression::Field {
+                expression: inner, ..
+            }
+            | ResolvedExpression::TupleField { tuple: inner, .. } => walk_expression(inner, out),
+            ResolvedExpression::Lambda { body, .. } => walk_statement(body, out),
+            _ => {}
+        }
+    }
+    let mut out = Vec::new();
+    for chunk in &ast.body {
+        if let ResolvedChunk::Function { body, .. } = chunk {
+            walk_statement(body, &mut out);
+        }
+    }
+    out
+}
+// The expression written at the span
+fn node<'a>(
+    nodes: &[&'a ResolvedExpression],
+    span: std::ops::Range<usize>,
+) -> &'a ResolvedExpression {
+    nodes.iter().find(|e| e.get_span() == span).unwrap()
+}
+
+#[test]
+fn every_expression_has_a_type() {
+    let (_, _, ast) = common::resolve("typed_expressions", PROGRAM);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).unwrap();
+    let notes = &checker.notes;
+    let nodes = nodes(&ast);
+
+    // Each is noted down once, under its own id
+    let ids: HashSet<_> = nodes.iter().map(|e| e.get_node()).collect();
+    assert_eq!(ids.len(), nodes.len());
+    assert_eq!(notes.expressions.len(), nodes.len());
+
+    let meters = TypeId::Named(id(&ast, "Meters"));
+    // Fields are written at the field's name, and operators at the operator
+    assert_eq!(*notes.type_of(node(&nodes, at("p.x", 2, 1))), TypeId::Usize);
+    assert_eq!(*notes.type_of(node(&nodes, at("m.0", 2, 1))), TypeId::Usize);
+    assert_eq!(
+        *notes.type_of(node(&nodes, at("+ m.0", 0, 1))),
+        TypeId::Usize
+    );
+    assert_eq!(
+        *notes.type_of(node(&nodes, at("n * 2", 2, 1))),
+        TypeId::Usize
+    );
+    assert_eq!(
+        *notes.type_of(node(&nodes, at("(p.x, m)", 0, 8))),
+        TypeId::Tuple([TypeId::Usize, meters.clone()].into())
+    );
+    // Written with a none, and given as the return type
+    let tuple = node(&nodes, at("(twice(a) + m.0, none)", 0, 22));
+    assert_eq!(
+        *notes.type_of(tuple),
+        TypeId::Tuple([TypeId::Usize, TypeId::None].into())
+    );
+    let optional = TypeId::Optional(Rc::new(meters));
+    assert_eq!(
+        *notes.given_as(tuple),
+        TypeId::Tuple([TypeId::Usize, optional.clone()].into())
+    );
+    let none = node(&nodes, at("none", 0, 4));
+    assert_eq!(*notes.type_of(none), TypeId::None);
+    assert_eq!(notes.wrapped[&none.get_node()], optional);
+    assert_eq!(
+        *notes.type_of(node(&nodes, at("|n: usize| usize", 0, 16))),
+        TypeId::Fn {
+            params: [TypeId::Usize].into(),
+            ret: Rc::new(TypeId::Usize),
+            variadic: false,
+        }
+    );
+}
+
+#[test]
+fn literals_are_coerced_to_smaller_integers() {
+    let program = "fn small() i32 -> {
+    let c: u8 = 120;
+    return 7;
+}
+";
+    let (_, _, ast) = common::resolve("typed_coerced", program);
+    let mut checker = TypeChecker::new();
+    checker.check(&ast).unwrap();
+    let notes = &checker.notes;
+    let nodes = nodes(&ast);
+
+    let byte = nodes.iter().find(|e| e.get_span() == (36..39)).unwrap();
+    let code = nodes.iter().find(|e| e.get_span() == (52..53)).unwrap();
+    assert_eq!(*notes.type_of(byte), TypeId::Usize);
+    assert_eq!(*notes.given_as(byte), TypeId::U8);
+    assert_eq!(*notes.type_of(code), TypeId::Usize);
+    assert_eq!(*notes.given_as(code), TypeId::I32);
+}